@@ -176,6 +176,7 @@ pub async fn follow(authority_client: NetworkAuthorityClient, download_txes: boo
                 .handle_batch_stream(BatchInfoRequest {
                     start: Some(start),
                     length: 10_000,
+                    headers_only: false,
                 })
                 .await;
 
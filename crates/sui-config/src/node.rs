@@ -22,6 +22,7 @@ use sui_types::crypto::NetworkKeyPair;
 use sui_types::crypto::NetworkPublicKey;
 use sui_types::crypto::PublicKey as AccountsPublicKey;
 use sui_types::crypto::SuiKeyPair;
+use sui_types::gas::KindGasMultipliers;
 use sui_types::sui_serde::KeyPairBase64;
 
 // Default max number of concurrent requests served
@@ -79,6 +80,14 @@ pub struct NodeConfig {
     #[serde(default = "default_concurrency_limit")]
     pub grpc_concurrency_limit: Option<usize>,
 
+    /// Number of dedicated threads signing authority batches. Batches are signed on the
+    /// batch service task when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch_signing_workers: Option<usize>,
+
+    #[serde(default, skip_serializing_if = "AuthorityConfig::is_default")]
+    pub authority_config: AuthorityConfig,
+
     pub genesis: Genesis,
 }
 
@@ -192,6 +201,90 @@ impl ConsensusConfig {
     }
 }
 
+/// The default for `AuthorityConfig::max_object_errors`.
+pub const DEFAULT_MAX_OBJECT_ERRORS: usize = 10;
+
+/// The default for `AuthorityConfig::max_batch_stream_length`.
+pub const DEFAULT_MAX_BATCH_STREAM_LENGTH: u64 = 1_000_000;
+
+/// Settings of the authority's transaction checks. Every setting defaults to the behaviour of
+/// an authority without any specific configuration.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct AuthorityConfig {
+    #[serde(default)]
+    pub kind_gas_multipliers: KindGasMultipliers,
+    /// The most object errors reported for the inputs of a single transaction.
+    #[serde(default = "default_max_object_errors")]
+    pub max_object_errors: usize,
+    /// Batch stream requests asking for more items than this are rejected. Shorter requests are
+    /// served up to the authority's per-request item limit, so a cap below that limit also
+    /// lowers the number of items served.
+    #[serde(default = "default_max_batch_stream_length")]
+    pub max_batch_stream_length: u64,
+    /// Remember up to this many transactions whose sender signature was verified, so that
+    /// resubmissions of the same transaction skip the signature check. Disabled when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature_cache_capacity: Option<usize>,
+    /// Verify sender signatures on a bounded pool of blocking threads, with a time budget per
+    /// verification. Signatures are verified inline when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature_verification_pool: Option<SignatureVerificationPoolConfig>,
+}
+
+fn default_max_object_errors() -> usize {
+    DEFAULT_MAX_OBJECT_ERRORS
+}
+
+fn default_max_batch_stream_length() -> u64 {
+    DEFAULT_MAX_BATCH_STREAM_LENGTH
+}
+
+impl Default for AuthorityConfig {
+    fn default() -> Self {
+        Self {
+            kind_gas_multipliers: KindGasMultipliers::default(),
+            max_object_errors: DEFAULT_MAX_OBJECT_ERRORS,
+            max_batch_stream_length: DEFAULT_MAX_BATCH_STREAM_LENGTH,
+            signature_cache_capacity: None,
+            signature_verification_pool: None,
+        }
+    }
+}
+
+impl AuthorityConfig {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// The default for `SignatureVerificationPoolConfig::max_concurrent`.
+pub const DEFAULT_MAX_CONCURRENT_SIGNATURE_VERIFICATIONS: usize = 64;
+
+/// The default for `SignatureVerificationPoolConfig::budget_ms`.
+pub const DEFAULT_SIGNATURE_VERIFICATION_BUDGET_MS: u64 = 1_000;
+
+/// Limits on the sender signature verifications run by the authority.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct SignatureVerificationPoolConfig {
+    /// The most signatures verified at the same time. Further verifications wait for one of
+    /// them to finish.
+    pub max_concurrent: usize,
+    /// How long a verification may run before it is failed, in milliseconds. Waiting for a
+    /// turn does not count.
+    pub budget_ms: u64,
+}
+
+impl Default for SignatureVerificationPoolConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: DEFAULT_MAX_CONCURRENT_SIGNATURE_VERIFICATIONS,
+            budget_ms: DEFAULT_SIGNATURE_VERIFICATION_BUDGET_MS,
+        }
+    }
+}
+
 /// Publicly known information about a validator
 /// TODO read most of this from on-chain
 #[serde_as]
@@ -113,6 +113,8 @@ impl NetworkConfig {
             genesis: validator_config.genesis.clone(),
             grpc_load_shed: None,
             grpc_concurrency_limit: None,
+            batch_signing_workers: None,
+            authority_config: Default::default(),
         }
     }
 }
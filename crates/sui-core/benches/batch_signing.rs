@@ -0,0 +1,69 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use fastcrypto::traits::KeyPair;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use sui_core::authority::StableSyncAuthoritySigner;
+use sui_core::batch_signer::BatchSigner;
+use sui_types::base_types::{AuthorityName, ExecutionDigests};
+use sui_types::batch::AuthorityBatch;
+use sui_types::crypto::{get_key_pair, AuthorityKeyPair};
+use tokio::runtime::Builder;
+
+const NUM_TASKS: usize = 64;
+const BATCHES_PER_TASK: usize = 50;
+const NUM_WORKERS: usize = 4;
+
+/// Signs NUM_TASKS * BATCHES_PER_TASK batches from concurrent tasks and returns the
+/// total wall time and the mean latency of a single signing request.
+fn run(signer: Arc<BatchSigner>) -> (Duration, Duration) {
+    let runtime = Builder::new_multi_thread().enable_all().build().unwrap();
+
+    let batch = AuthorityBatch::make_next(
+        &AuthorityBatch::initial(),
+        &[(0, ExecutionDigests::random())],
+    )
+    .unwrap();
+
+    let start = Instant::now();
+    let total_latency = runtime.block_on(async move {
+        let mut futures = Vec::new();
+        for _ in 0..NUM_TASKS {
+            let signer = signer.clone();
+            let batch = batch.clone();
+            futures.push(tokio::spawn(async move {
+                let mut latency = Duration::ZERO;
+                for _ in 0..BATCHES_PER_TASK {
+                    let begin = Instant::now();
+                    signer.sign(0, batch.clone()).await.unwrap();
+                    latency += begin.elapsed();
+                }
+                latency
+            }));
+        }
+
+        let mut total = Duration::ZERO;
+        while let Some(f) = futures.pop() {
+            total += f.await.unwrap();
+        }
+        total
+    });
+
+    (
+        start.elapsed(),
+        total_latency / (NUM_TASKS * BATCHES_PER_TASK) as u32,
+    )
+}
+
+fn main() {
+    let (_, key): (_, AuthorityKeyPair) = get_key_pair();
+    let name: AuthorityName = key.public().into();
+    let secret: StableSyncAuthoritySigner = Arc::pin(key);
+
+    let (elapsed, latency) = run(Arc::new(BatchSigner::inline(secret.clone(), name)));
+    println!("inline: total {elapsed:?}, mean latency {latency:?}");
+
+    let (elapsed, latency) = run(Arc::new(BatchSigner::pooled(secret, name, NUM_WORKERS)));
+    println!("pooled ({NUM_WORKERS} workers): total {elapsed:?}, mean latency {latency:?}");
+}
@@ -43,6 +43,7 @@ use std::{
 use sui_adapter::adapter;
 use sui_adapter::temporary_store::InnerTemporaryStore;
 use sui_config::genesis::Genesis;
+use sui_config::node::AuthorityConfig;
 use sui_json_rpc_types::{SuiEventEnvelope, SuiTransactionEffects};
 use sui_simulator::nondeterministic;
 use sui_storage::{
@@ -151,6 +152,11 @@ pub struct AuthorityMetrics {
     pub follower_connections_concurrent: IntGauge,
     pub follower_start_seq_num: Histogram,
 
+    pub batch_stream_buffered_items: IntGauge,
+
+    pub gas_price_cache_hits: IntCounter,
+    pub gas_price_cache_misses: IntCounter,
+
     // TODO: consolidate these into GossipMetrics
     // (issue: https://github.com/MystenLabs/sui/issues/3926)
     pub gossip_queued_count: IntCounter,
@@ -334,6 +340,24 @@ impl AuthorityMetrics {
                 registry,
             )
             .unwrap(),
+            batch_stream_buffered_items: register_int_gauge_with_registry!(
+                "batch_stream_buffered_items",
+                "Number of historical items loaded for batch stream subscribers and not yet sent",
+                registry,
+            )
+            .unwrap(),
+            gas_price_cache_hits: register_int_counter_with_registry!(
+                "gas_price_cache_hits",
+                "Number of gas checks served from the epoch gas parameters cache",
+                registry,
+            )
+            .unwrap(),
+            gas_price_cache_misses: register_int_counter_with_registry!(
+                "gas_price_cache_misses",
+                "Number of gas checks that had to read the gas parameters from the system state",
+                registry,
+            )
+            .unwrap(),
             gossip_queued_count: register_int_counter_with_registry!(
                 "gossip_queued_count",
                 "Number of digests queued from gossip peers",
@@ -419,6 +443,22 @@ pub struct AuthorityState {
 
     /// A channel to tell consensus to reconfigure.
     tx_reconfigure_consensus: Sender<ReconfigConsensusMessage>,
+
+    /// Digests of transactions whose sender signature was already verified. Disabled by default.
+    signature_cache: Option<transaction_input_checker::VerifiedSignatureCache>,
+
+    /// Bounds the number and duration of concurrent sender signature checks. Signatures are
+    /// checked inline when unset.
+    signature_verifier: Option<transaction_input_checker::SignatureVerificationPool>,
+
+    /// The gas parameters of the current epoch.
+    gas_params_cache: transaction_input_checker::EpochGasParamsCache,
+
+    /// The most object errors reported for the inputs of a single transaction.
+    max_object_errors: usize,
+
+    /// Batch stream requests asking for more items than this are rejected.
+    max_batch_stream_length: u64,
 }
 
 /// The authority state encapsulates all state, drives execution, and ensures safety.
@@ -468,11 +508,15 @@ impl AuthorityState {
             return Err(SuiError::ValidatorHaltedAtEpochEnd);
         }
 
-        let (_gas_status, input_objects) =
-            transaction_input_checker::check_transaction_input(&self.database, &transaction)
-                .await?;
+        let (_gas_status, input_objects) = transaction_input_checker::check_transaction_input(
+            &self.database,
+            &transaction,
+            Some((self.epoch(), &self.gas_params_cache)),
+            self.max_object_errors,
+        )
+        .await?;
 
-        let owned_objects = input_objects.filter_owned_objects();
+        let owned_objects = input_objects.classify().owned;
 
         let signed_transaction =
             SignedTransaction::new(self.epoch(), transaction, self.name, &*self.secret);
@@ -499,7 +543,14 @@ impl AuthorityState {
 
         self.metrics.tx_orders.inc();
         // Check the sender's signature.
-        transaction.verify().map_err(|e| {
+        transaction_input_checker::check_sender_signature(
+            &transaction,
+            self.epoch(),
+            self.signature_cache.as_ref(),
+            self.signature_verifier.as_ref(),
+        )
+        .await
+        .map_err(|e| {
             self.metrics.signature_errors.inc();
             e
         })?;
@@ -795,12 +846,17 @@ impl AuthorityState {
         transaction_digest: TransactionDigest,
     ) -> SuiResult<(InnerTemporaryStore, SignedTransactionEffects)> {
         let _metrics_guard = start_timer(self.metrics.prepare_certificate_latency.clone());
-        let (gas_status, input_objects) =
-            transaction_input_checker::check_certificate_input(&self.database, certificate).await?;
+        let (gas_status, input_objects) = transaction_input_checker::check_certificate_input(
+            &self.database,
+            certificate,
+            Some((self.epoch(), &self.gas_params_cache)),
+            self.max_object_errors,
+        )
+        .await?;
 
         // At this point we need to check if any shared objects need locks,
         // and whether they have them.
-        let shared_object_refs = input_objects.filter_shared_objects();
+        let shared_object_refs = input_objects.classify().shared;
         if !shared_object_refs.is_empty() && !certificate.signed_data.data.kind.is_change_epoch_tx()
         {
             // If the transaction contains shared objects, we need to ensure they have been scheduled
@@ -845,9 +901,14 @@ impl AuthorityState {
         transaction_digest: TransactionDigest,
     ) -> Result<SuiTransactionEffects, anyhow::Error> {
         transaction.verify()?;
-        let (gas_status, input_objects) =
-            transaction_input_checker::check_transaction_input(&self.database, transaction).await?;
-        let shared_object_refs = input_objects.filter_shared_objects();
+        let (gas_status, input_objects) = transaction_input_checker::check_transaction_input(
+            &self.database,
+            transaction,
+            Some((self.epoch(), &self.gas_params_cache)),
+            self.max_object_errors,
+        )
+        .await?;
+        let shared_object_refs = input_objects.classify().shared;
 
         let transaction_dependencies = input_objects.transaction_dependencies();
         let temporary_store =
@@ -1120,7 +1181,8 @@ impl AuthorityState {
     /// Handles a request for a batch info. It returns a sequence of
     /// [batches, transactions, batches, transactions] as UpdateItems, and a flag
     /// that if true indicates the request goes beyond the last batch in the
-    /// database.
+    /// database. If `headers_only` is set the transactions are left out and only
+    /// the batches are returned.
     pub async fn handle_batch_info_request(
         &self,
         request: BatchInfoRequest,
@@ -1132,12 +1194,36 @@ impl AuthorityState {
         ),
         SuiError,
     > {
-        // Ensure the range contains some elements and end > start
-        if request.length == 0 {
-            return Err(SuiError::InvalidSequenceRangeError);
-        };
+        // Ensure the range contains some elements, and starts between the first batch we still
+        // have and the next transaction to be sequenced.
+        let earliest = self
+            .first_batch()?
+            .ok_or(SuiError::NoBatchesFoundError)?
+            .data()
+            .next_sequence_number;
+        let latest = self.batch_notifier.low_watermark();
+        fp_ensure!(
+            request.length > 0
+                && request
+                    .start
+                    .map_or(true, |start| earliest <= start && start <= latest),
+            SuiError::InvalidBatchRange {
+                start: request.start,
+                length: request.length,
+                earliest,
+                latest,
+            }
+        );
 
-        // Ensure we are not doing too much work per request
+        // Requests that no well-behaved follower would make are rejected outright, the others
+        // are clamped so that we are not doing too much work per request.
+        fp_ensure!(
+            request.length <= self.max_batch_stream_length,
+            SuiError::BatchRequestTooLarge {
+                requested: request.length,
+                max: self.max_batch_stream_length,
+            }
+        );
         let length = std::cmp::min(request.length, MAX_ITEMS_LIMIT);
 
         // If we do not have a start, pick next sequence number that has
@@ -1172,7 +1258,9 @@ impl AuthorityState {
                 }
 
                 let current_transaction = dq_transactions.pop_front().unwrap();
-                items.push_back(UpdateItem::Transaction(current_transaction));
+                if !request.headers_only {
+                    items.push_back(UpdateItem::Transaction(current_transaction));
+                }
             }
 
             // Now send the batch
@@ -1185,9 +1273,11 @@ impl AuthorityState {
         let should_subscribe = end > last_batch_next_seq;
 
         // If any transactions are left they must be outside a batch
-        while let Some(current_transaction) = dq_transactions.pop_front() {
-            // Remember the last sequence sent
-            items.push_back(UpdateItem::Transaction(current_transaction));
+        if !request.headers_only {
+            while let Some(current_transaction) = dq_transactions.pop_front() {
+                // Remember the last sequence sent
+                items.push_back(UpdateItem::Transaction(current_transaction));
+            }
         }
 
         Ok((items, (should_subscribe, start, end)))
@@ -1239,8 +1329,10 @@ impl AuthorityState {
         genesis: &Genesis,
         prometheus_registry: &prometheus::Registry,
         tx_reconfigure_consensus: Sender<ReconfigConsensusMessage>,
+        config: AuthorityConfig,
     ) -> Self {
         let (tx, _rx) = tokio::sync::broadcast::channel(BROADCAST_CAPACITY);
+        let metrics = Arc::new(AuthorityMetrics::new(prometheus_registry));
         let native_functions =
             sui_framework::natives::all_natives(MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS);
         let move_vm = Arc::new(
@@ -1284,8 +1376,21 @@ impl AuthorityState {
                     .expect("Notifier cannot start."),
             ),
             consensus_guardrail: AtomicUsize::new(0),
-            metrics: Arc::new(AuthorityMetrics::new(prometheus_registry)),
             tx_reconfigure_consensus,
+            signature_cache: config
+                .signature_cache_capacity
+                .map(transaction_input_checker::VerifiedSignatureCache::new),
+            signature_verifier: config
+                .signature_verification_pool
+                .as_ref()
+                .map(transaction_input_checker::SignatureVerificationPool::from),
+            gas_params_cache: transaction_input_checker::EpochGasParamsCache::new(
+                config.kind_gas_multipliers,
+                &metrics,
+            ),
+            metrics,
+            max_object_errors: config.max_object_errors,
+            max_batch_stream_length: config.max_batch_stream_length,
         };
 
         // Process tx recovery log first, so that the batch and checkpoint recovery (below)
@@ -1395,6 +1500,7 @@ impl AuthorityState {
             genesis,
             &prometheus::Registry::new(),
             tx_reconfigure_consensus,
+            AuthorityConfig::default(),
         )
         .await
     }
@@ -1447,9 +1553,13 @@ impl AuthorityState {
             SuiError::from("Invalid new epoch to sign and update")
         );
 
+        let ended_epoch = self.epoch();
         self.committee_store.insert_new_committee(&new_committee)?;
         // TODO: Do we want to make it possible to subscribe to committee changes?
         self.committee.swap(Arc::new(new_committee));
+        if let Some(signature_cache) = &self.signature_cache {
+            signature_cache.invalidate_epoch(ended_epoch);
+        }
         Ok(())
     }
 
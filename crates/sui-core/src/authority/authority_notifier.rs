@@ -8,11 +8,38 @@ use std::{
 };
 use sui_types::batch::TxSequenceNumber;
 
+use thiserror::Error;
 use tokio::sync::Notify;
 use typed_store::traits::Map;
 
 use parking_lot::Mutex;
 
+#[derive(Eq, PartialEq, Clone, Debug, Error)]
+pub enum BatchNotifierError {
+    /// The notifier was closed, e.g. because the authority is shutting down,
+    /// and no longer hands out tickets.
+    #[error("The notifier subsystem is closed.")]
+    Closed,
+    /// A ticket was about to be handed out with a sequence number that is
+    /// already live or below the low watermark. This is a bug in the notifier.
+    #[error("Ticket sequence number {received} regressed, expected at least {expected}.")]
+    SequenceRegression {
+        expected: TxSequenceNumber,
+        received: TxSequenceNumber,
+    },
+}
+
+impl From<BatchNotifierError> for SuiError {
+    fn from(error: BatchNotifierError) -> Self {
+        match error {
+            BatchNotifierError::Closed => SuiError::ClosedNotifierError,
+            BatchNotifierError::SequenceRegression { expected, received } => {
+                SuiError::SequenceRegression { expected, received }
+            }
+        }
+    }
+}
+
 pub struct TransactionNotifier {
     state: Arc<AuthorityStore>,
     low_watermark: AtomicU64,
@@ -69,14 +96,32 @@ impl TransactionNotifier {
     }
 
     /// Get a ticket with a sequence number
-    pub fn ticket(self: &Arc<Self>) -> SuiResult<TransactionNotifierTicket> {
+    pub fn ticket(self: &Arc<Self>) -> Result<TransactionNotifierTicket, BatchNotifierError> {
         if self.is_closed.load(Ordering::SeqCst) {
-            return Err(SuiError::ClosedNotifierError);
+            return Err(BatchNotifierError::Closed);
         }
 
         let mut inner = self.inner.lock();
-        // Insert the ticket into the set of live tickets.
         let seq = inner.high_watermark;
+        // Sequence numbers must be strictly increasing: a ticket may never share its
+        // sequence number with a live ticket or one that was already notified.
+        let expected = std::cmp::max(
+            self.low_watermark.load(Ordering::SeqCst),
+            inner
+                .live_tickets
+                .iter()
+                .next_back()
+                .map_or(0, |last| last + 1),
+        );
+        debug_assert!(seq >= expected, "ticket sequence number {seq} regressed");
+        if seq < expected {
+            return Err(BatchNotifierError::SequenceRegression {
+                expected,
+                received: seq,
+            });
+        }
+
+        // Insert the ticket into the set of live tickets.
         inner.high_watermark += 1;
         inner.live_tickets.insert(seq);
         Ok(TransactionNotifierTicket {
@@ -339,4 +384,62 @@ mod tests {
         // After we drop an iterator we can get another one
         assert!(notifier.iter_from(0).is_ok());
     }
+
+    #[tokio::test]
+    async fn test_ticket_after_close() {
+        let dir = env::temp_dir();
+        let path = dir.join(format!("DB_{:?}", ObjectID::random()));
+        fs::create_dir(&path).unwrap();
+
+        let store = Arc::new(AuthorityStore::open(&path, None));
+
+        let notifier = Arc::new(TransactionNotifier::new(store).unwrap());
+        assert!(notifier.ticket().is_ok());
+
+        notifier.close();
+        assert!(matches!(notifier.ticket(), Err(BatchNotifierError::Closed)));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_tickets_are_increasing() {
+        let dir = env::temp_dir();
+        let path = dir.join(format!("DB_{:?}", ObjectID::random()));
+        fs::create_dir(&path).unwrap();
+
+        let store = Arc::new(AuthorityStore::open(&path, None));
+        let notifier = Arc::new(TransactionNotifier::new(store).unwrap());
+
+        const THREADS: u64 = 8;
+        const TICKETS_PER_THREAD: u64 = 500;
+        let handles: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let notifier = notifier.clone();
+                std::thread::spawn(move || {
+                    (0..TICKETS_PER_THREAD)
+                        .map(|i| {
+                            let ticket = notifier.ticket().expect("ok");
+                            let seq = ticket.seq();
+                            // Keep some tickets live while others are notified.
+                            if i % 2 == 0 {
+                                ticket.notify();
+                            }
+                            seq
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        let mut all = Vec::new();
+        for handle in handles {
+            let seqs = handle.join().unwrap();
+            // Each thread sees strictly increasing sequence numbers.
+            assert!(seqs.windows(2).all(|w| w[0] < w[1]));
+            all.extend(seqs);
+        }
+
+        // Across threads, every sequence number was handed out exactly once.
+        all.sort_unstable();
+        assert_eq!(all, (0..THREADS * TICKETS_PER_THREAD).collect::<Vec<_>>());
+    }
 }
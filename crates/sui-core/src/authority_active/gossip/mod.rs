@@ -413,6 +413,7 @@ where
         let req = BatchInfoRequest {
             start: None,
             length: REQUEST_FOLLOW_NUM_DIGESTS,
+            headers_only: false,
         };
         let mut streamx = Box::pin(self.client.handle_batch_stream(req).await?);
         let metrics = handler.get_metrics();
@@ -463,6 +464,7 @@ where
                             let req = BatchInfoRequest {
                                 start: None,
                                 length: REQUEST_FOLLOW_NUM_DIGESTS,
+                                headers_only: false,
                             };
                             streamx = Box::pin(self.client.handle_batch_stream(req).await?);
                         },
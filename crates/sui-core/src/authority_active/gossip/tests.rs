@@ -140,6 +140,7 @@ pub async fn test_gossip_after_revert() {
                 .handle_batch_info_request(BatchInfoRequest {
                     start: Some(0),
                     length: 2,
+                    headers_only: false,
                 })
                 .await
                 .unwrap()
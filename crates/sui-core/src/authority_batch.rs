@@ -12,14 +12,16 @@ use sui_types::messages::BatchInfoRequest;
 use sui_types::messages::BatchInfoResponseItem;
 
 use crate::authority::AuthorityMetrics;
+use crate::batch_signer::BatchSigner;
 
 use std::collections::VecDeque;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::interval;
+use tokio_util::sync::CancellationToken;
 
-use futures::stream::{self, Stream};
-use futures::StreamExt;
+use futures::stream::{self, BoxStream, Stream};
+use futures::{FutureExt, StreamExt};
 use typed_store::Map;
 
 use tokio::sync::broadcast::{error::RecvError, Receiver};
@@ -57,6 +59,19 @@ pub type BroadcastReceiver = tokio::sync::broadcast::Receiver<UpdateItem>;
 pub type BroadcastPair = (BroadcastSender, BroadcastReceiver);
 
 impl crate::authority::AuthorityState {
+    /// The earliest batch still in the store. Transactions sequenced before it can no longer
+    /// be streamed.
+    pub fn first_batch(&self) -> Result<Option<SignedBatch>, SuiError> {
+        let first_batch = self
+            .db()
+            .tables
+            .batches
+            .iter()
+            .next()
+            .map(|(_, batch)| batch);
+        Ok(first_batch)
+    }
+
     pub fn last_batch(&self) -> Result<Option<SignedBatch>, SuiError> {
         let last_batch = self
             .db()
@@ -131,6 +146,33 @@ impl crate::authority::AuthorityState {
         &self,
         min_batch_size: u64,
         max_delay: Duration,
+    ) -> SuiResult<()> {
+        self.run_batch_service_until(min_batch_size, max_delay, &CancellationToken::new())
+            .await
+    }
+
+    /// Like `run_batch_service`, but also stops once `cancel` is triggered, after making a
+    /// last batch of the transactions received so far. This is for owners that cannot close
+    /// the batch notifier to stop the service, e.g. because it is shared.
+    pub async fn run_batch_service_until(
+        &self,
+        min_batch_size: u64,
+        max_delay: Duration,
+        cancel: &CancellationToken,
+    ) -> SuiResult<()> {
+        let signer = BatchSigner::inline(self.secret.clone(), self.name);
+        self.run_batch_service_with_signer(min_batch_size, max_delay, &signer, cancel)
+            .await
+    }
+
+    /// Like `run_batch_service_until`, but lets the caller choose how batches are signed,
+    /// e.g. to offload signing to a pool of worker threads.
+    pub async fn run_batch_service_with_signer(
+        &self,
+        min_batch_size: u64,
+        max_delay: Duration,
+        signer: &BatchSigner,
+        cancel: &CancellationToken,
     ) -> SuiResult<()> {
         debug!("Batch service started");
         // This assumes we have initialized the database with a batch.
@@ -172,6 +214,11 @@ impl crate::authority::AuthorityState {
                     // but it should never be empty. But never empty.
                     make_batch = true;
                 },
+                _ = cancel.cancelled() => {
+                    // Seal what we have so far and stop, as if the notifier was closed.
+                    make_batch = true;
+                    exit = true;
+                },
                 item_option = transaction_stream.next() => {
                     match item_option {
                         None => {
@@ -199,13 +246,13 @@ impl crate::authority::AuthorityState {
                 }
 
                 // Make and store a new batch.
-                let new_batch = SignedBatch::new(
-                    self.epoch(),
-                    // Unwrap safe since we tested above it is not empty
-                    AuthorityBatch::make_next(&prev_batch, &current_batch).unwrap(),
-                    &*self.secret,
-                    self.name,
-                );
+                let new_batch = signer
+                    .sign(
+                        self.epoch(),
+                        // Unwrap safe since we tested above it is not empty
+                        AuthorityBatch::make_next(&prev_batch, &current_batch).unwrap(),
+                    )
+                    .await?;
                 self.db()
                     .tables
                     .batches
@@ -245,6 +292,28 @@ impl crate::authority::AuthorityState {
     pub async fn handle_batch_streaming(
         &self,
         request: BatchInfoRequest,
+    ) -> Result<impl Stream<Item = Result<BatchInfoResponseItem, SuiError>>, SuiError> {
+        self.handle_batch_streaming_impl(request, None).await
+    }
+
+    /// Same as `handle_batch_streaming`, but the client reports through `acks` the highest
+    /// sequence number it has processed, e.g. because it got the items from another authority.
+    /// Historical items are then dropped from the subscriber's buffer without being sent, which
+    /// bounds the memory held for a client that is slow to consume the stream. The stream
+    /// always starts with the initial batch, and only skips whole batches: the next item sent
+    /// is the last acknowledged batch, so that the client can resume the chain from it.
+    pub async fn handle_batch_streaming_with_ack(
+        &self,
+        request: BatchInfoRequest,
+        acks: BoxStream<'static, TxSequenceNumber>,
+    ) -> Result<impl Stream<Item = Result<BatchInfoResponseItem, SuiError>>, SuiError> {
+        self.handle_batch_streaming_impl(request, Some(acks)).await
+    }
+
+    async fn handle_batch_streaming_impl(
+        &self,
+        request: BatchInfoRequest,
+        acks: Option<BoxStream<'static, TxSequenceNumber>>,
     ) -> Result<impl Stream<Item = Result<BatchInfoResponseItem, SuiError>>, SuiError> {
         let metrics = self.metrics.clone();
         metrics.follower_connections.inc();
@@ -261,6 +330,7 @@ impl crate::authority::AuthorityState {
 
         // Register a subscriber to not miss any updates
         let subscriber = self.subscribe_batch();
+        let headers_only = request.headers_only;
 
         // Get the historical data requested
         let (items, (should_subscribe, _start, end)) =
@@ -270,6 +340,9 @@ impl crate::authority::AuthorityState {
         metrics
             .follower_items_loaded
             .inc_by(items.len().try_into().unwrap());
+        metrics
+            .batch_stream_buffered_items
+            .add(items.len().try_into().unwrap());
 
         // Define a local structure to support the stream construction.
         struct BatchStreamingLocals<GuardT> {
@@ -277,12 +350,86 @@ impl crate::authority::AuthorityState {
             next_expected_seq: TxSequenceNumber,
             next_expected_batch: TxSequenceNumber,
             subscriber: Receiver<UpdateItem>,
+            acks: Option<BoxStream<'static, TxSequenceNumber>>,
+            acked: Option<TxSequenceNumber>,
             exit: bool,
             should_subscribe: bool,
             metrics: Arc<AuthorityMetrics>,
             _guard: GuardT,
         }
 
+        impl<GuardT> BatchStreamingLocals<GuardT> {
+            // Update the last processed items to ensure we do not repeat them
+            fn advance_past(&mut self, item: &UpdateItem) {
+                match item {
+                    UpdateItem::Transaction((seq, _)) => {
+                        self.next_expected_seq = *seq + 1;
+                    }
+                    UpdateItem::Batch(signed_batch) => {
+                        self.next_expected_batch = signed_batch.data().next_sequence_number + 1;
+                    }
+                }
+            }
+
+            fn pop_item(&mut self) -> Option<UpdateItem> {
+                let item = self.items.pop_front()?;
+                self.metrics.batch_stream_buffered_items.dec();
+                self.advance_past(&item);
+                Some(item)
+            }
+
+            // Drop the historical items up to the last batch the client has acknowledged, so
+            // that this batch is sent next. Nothing is dropped before the initial batch is sent.
+            fn drop_acked_items(&mut self) {
+                if let Some(acks) = self.acks.as_mut() {
+                    while let Some(Some(seq)) = acks.next().now_or_never() {
+                        self.acked = self.acked.max(Some(seq));
+                    }
+                }
+                let acked = match self.acked {
+                    Some(acked) if self.next_expected_batch > 0 => acked,
+                    _ => return,
+                };
+
+                let mut last_acked_batch = None;
+                for (index, item) in self.items.iter().enumerate() {
+                    match item {
+                        UpdateItem::Transaction((seq, _)) if *seq > acked => break,
+                        UpdateItem::Transaction(_) => {}
+                        UpdateItem::Batch(signed_batch) => {
+                            let next_sequence_number = signed_batch.data().next_sequence_number;
+                            if next_sequence_number > acked.saturating_add(1) {
+                                break;
+                            }
+                            last_acked_batch = Some((index, next_sequence_number));
+                        }
+                    }
+                }
+                let (index, next_sequence_number) = match last_acked_batch {
+                    Some(last_acked_batch) => last_acked_batch,
+                    None => return,
+                };
+
+                self.items.drain(..index);
+                self.next_expected_seq = self.next_expected_seq.max(next_sequence_number);
+                self.metrics
+                    .batch_stream_buffered_items
+                    .sub(index.try_into().unwrap());
+                // Only reallocate once enough of the buffer is free.
+                if self.items.len() < self.items.capacity() / 2 {
+                    self.items.shrink_to_fit();
+                }
+            }
+        }
+
+        impl<GuardT> Drop for BatchStreamingLocals<GuardT> {
+            fn drop(&mut self) {
+                self.metrics
+                    .batch_stream_buffered_items
+                    .sub(self.items.len().try_into().unwrap());
+            }
+        }
+
         let local_state = BatchStreamingLocals {
             // The historical items
             items,
@@ -291,6 +438,9 @@ impl crate::authority::AuthorityState {
             next_expected_batch: 0,
             // A subscriber that listens to the latest item updates
             subscriber,
+            // Where the client acknowledges the items it has processed
+            acks,
+            acked: None,
             // A flag signifying the loop should exit
             exit: false,
             // A flag indicating if real-time subscrition is needed.
@@ -307,18 +457,8 @@ impl crate::authority::AuthorityState {
             }
 
             // If there are historical items send them.
-            if let Some(item) = local_state.items.pop_front() {
-                // Update the last processed items to ensure we do not repeat them
-                match &item {
-                    UpdateItem::Transaction((seq, _)) => {
-                        local_state.next_expected_seq = *seq + 1;
-                    }
-                    UpdateItem::Batch(signed_batch) => {
-                        local_state.next_expected_batch =
-                            signed_batch.data().next_sequence_number + 1;
-                    }
-                }
-
+            local_state.drop_acked_items();
+            if let Some(item) = local_state.pop_item() {
                 local_state.metrics.follower_items_streamed.inc();
                 Some((Ok(BatchInfoResponseItem(item)), local_state))
             } else {
@@ -334,8 +474,10 @@ impl crate::authority::AuthorityState {
                             Ok(item) => {
                                 match &item {
                                     UpdateItem::Transaction((seq, _)) => {
-                                        // Do not re-send transactions already sent from the database
-                                        if !(local_state.next_expected_seq <= *seq) {
+                                        // Do not re-send transactions already sent from the database,
+                                        // nor any transaction if only headers were requested.
+                                        if headers_only || !(local_state.next_expected_seq <= *seq)
+                                        {
                                             continue;
                                         }
                                     }
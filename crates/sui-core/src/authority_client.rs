@@ -6,7 +6,11 @@ use crate::authority::AuthorityState;
 use anyhow::anyhow;
 use async_trait::async_trait;
 use fastcrypto::traits::ToFromBytes;
-use futures::{stream::BoxStream, TryStreamExt};
+use futures::{
+    future,
+    stream::{self, BoxStream},
+    StreamExt, TryStreamExt,
+};
 use multiaddr::Multiaddr;
 use mysten_network::config::Config;
 use prometheus::{register_histogram_with_registry, Histogram};
@@ -14,6 +18,7 @@ use std::collections::BTreeMap;
 use std::sync::Arc;
 use sui_config::genesis::Genesis;
 use sui_network::{api::ValidatorClient, tonic};
+use sui_types::batch::TxSequenceNumber;
 use sui_types::crypto::AuthorityPublicKeyBytes;
 use sui_types::messages_checkpoint::{CheckpointRequest, CheckpointResponse};
 use sui_types::sui_system_state::SuiSystemState;
@@ -62,6 +67,17 @@ pub trait AuthorityAPI {
         request: BatchInfoRequest,
     ) -> Result<BatchInfoResponseItemStream, SuiError>;
 
+    /// Same as `handle_batch_stream`, but the client reports through `acks` the highest
+    /// sequence number it has processed, so that the authority can skip whole batches of
+    /// historical items. Authorities are free to ignore the acknowledgements.
+    async fn handle_batch_stream_with_ack(
+        &self,
+        request: BatchInfoRequest,
+        _acks: BatchStreamAcks,
+    ) -> Result<BatchInfoResponseItemStream, SuiError> {
+        self.handle_batch_stream(request).await
+    }
+
     async fn handle_checkpoint(
         &self,
         request: CheckpointRequest,
@@ -75,6 +91,8 @@ pub trait AuthorityAPI {
 
 pub type BatchInfoResponseItemStream = BoxStream<'static, Result<BatchInfoResponseItem, SuiError>>;
 
+pub type BatchStreamAcks = BoxStream<'static, TxSequenceNumber>;
+
 #[derive(Clone)]
 pub struct NetworkAuthorityClient {
     client: ValidatorClient<tonic::transport::Channel>,
@@ -230,6 +248,23 @@ impl AuthorityAPI for NetworkAuthorityClient {
         Ok(Box::pin(stream))
     }
 
+    async fn handle_batch_stream_with_ack(
+        &self,
+        request: BatchInfoRequest,
+        acks: BatchStreamAcks,
+    ) -> Result<BatchInfoResponseItemStream, SuiError> {
+        let messages = stream::once(future::ready(BatchInfoStreamMessage::Request(request)))
+            .chain(acks.map(BatchInfoStreamMessage::Ack));
+        let stream = self
+            .client()
+            .batch_info_with_ack(messages)
+            .await
+            .map(tonic::Response::into_inner)?
+            .map_err(Into::into);
+
+        Ok(Box::pin(stream))
+    }
+
     /// Handle Object information requests for this account.
     async fn handle_checkpoint(
         &self,
@@ -393,6 +428,17 @@ impl AuthorityAPI for LocalAuthorityClient {
         Ok(Box::pin(update_items))
     }
 
+    async fn handle_batch_stream_with_ack(
+        &self,
+        request: BatchInfoRequest,
+        acks: BatchStreamAcks,
+    ) -> Result<BatchInfoResponseItemStream, SuiError> {
+        let state = self.state.clone();
+
+        let update_items = state.handle_batch_streaming_with_ack(request, acks).await?;
+        Ok(Box::pin(update_items))
+    }
+
     async fn handle_checkpoint(
         &self,
         request: CheckpointRequest,
@@ -14,7 +14,7 @@ use anyhow::anyhow;
 use anyhow::Result;
 use async_trait::async_trait;
 use fastcrypto::traits::KeyPair;
-use futures::{stream::BoxStream, TryStreamExt};
+use futures::{future, stream::BoxStream, StreamExt, TryStreamExt};
 use multiaddr::Multiaddr;
 use prometheus::{register_histogram_with_registry, Histogram, Registry};
 use std::{io, sync::Arc, time::Duration};
@@ -551,6 +551,42 @@ impl Validator for ValidatorService {
         Ok(tonic::Response::new(Box::pin(response)))
     }
 
+    type FollowTxStreamWithAckStream =
+        BoxStream<'static, Result<BatchInfoResponseItem, tonic::Status>>;
+
+    async fn batch_info_with_ack(
+        &self,
+        request: tonic::Request<tonic::Streaming<BatchInfoStreamMessage>>,
+    ) -> Result<tonic::Response<Self::FollowTxStreamWithAckStream>, tonic::Status> {
+        let mut messages = request.into_inner();
+        let request = match messages.message().await? {
+            Some(BatchInfoStreamMessage::Request(request)) => request,
+            _ => {
+                return Err(tonic::Status::invalid_argument(
+                    "The stream must start with a batch info request",
+                ))
+            }
+        };
+        let acks = messages
+            .filter_map(|message| {
+                future::ready(match message {
+                    Ok(BatchInfoStreamMessage::Ack(seq)) => Some(seq),
+                    _ => None,
+                })
+            })
+            .boxed();
+
+        let xstream = self
+            .state
+            .handle_batch_streaming_with_ack(request, acks)
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+
+        let response = xstream.map_err(|e| tonic::Status::internal(e.to_string()));
+
+        Ok(tonic::Response::new(Box::pin(response)))
+    }
+
     async fn checkpoint(
         &self,
         request: tonic::Request<CheckpointRequest>,
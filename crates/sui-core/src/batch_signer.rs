@@ -0,0 +1,99 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::thread;
+
+use sui_types::base_types::AuthorityName;
+use sui_types::batch::{AuthorityBatch, SignedBatch};
+use sui_types::committee::EpochId;
+use sui_types::error::{SuiError, SuiResult};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::authority::StableSyncAuthoritySigner;
+
+type SigningJob = (EpochId, AuthorityBatch, oneshot::Sender<SignedBatch>);
+
+/// Signs the batches produced by the batch service.
+///
+/// By default signing happens inline on the task that builds the batch. Under
+/// high throughput the signature can become a bottleneck for that task, so the
+/// signer may instead be backed by a small pool of dedicated threads, in which
+/// case the signed batch is handed back through a oneshot channel.
+pub struct BatchSigner {
+    secret: StableSyncAuthoritySigner,
+    authority: AuthorityName,
+    pool: Option<mpsc::Sender<SigningJob>>,
+}
+
+impl BatchSigner {
+    /// Sign batches on the calling task.
+    pub fn inline(secret: StableSyncAuthoritySigner, authority: AuthorityName) -> Self {
+        Self {
+            secret,
+            authority,
+            pool: None,
+        }
+    }
+
+    /// Offload signing to `num_workers` dedicated threads. At most `num_workers` batches
+    /// wait for a free thread; further calls to `sign` wait for room in the queue. The
+    /// threads exit once the signer is dropped.
+    pub fn pooled(
+        secret: StableSyncAuthoritySigner,
+        authority: AuthorityName,
+        num_workers: usize,
+    ) -> Self {
+        assert!(num_workers > 0, "A signing pool needs at least one worker");
+
+        let (tx, rx) = mpsc::channel::<SigningJob>(num_workers);
+        let rx = Arc::new(Mutex::new(rx));
+        for i in 0..num_workers {
+            let rx = rx.clone();
+            let secret = secret.clone();
+            thread::Builder::new()
+                .name(format!("batch-signer-{i}"))
+                .spawn(move || loop {
+                    // The lock is only held while waiting for the next job, not while signing.
+                    let job = rx.lock().blocking_recv();
+                    let (epoch, batch, reply) = match job {
+                        Some(job) => job,
+                        None => return,
+                    };
+                    let _ = reply.send(SignedBatch::new(epoch, batch, &*secret, authority));
+                })
+                .expect("Failed to spawn batch signing thread");
+        }
+
+        Self {
+            secret,
+            authority,
+            pool: Some(tx),
+        }
+    }
+
+    pub fn is_pooled(&self) -> bool {
+        self.pool.is_some()
+    }
+
+    pub async fn sign(&self, epoch: EpochId, batch: AuthorityBatch) -> SuiResult<SignedBatch> {
+        match &self.pool {
+            None => Ok(SignedBatch::new(
+                epoch,
+                batch,
+                &*self.secret,
+                self.authority,
+            )),
+            Some(pool) => {
+                let (reply, signed) = oneshot::channel();
+                pool.send((epoch, batch, reply))
+                    .await
+                    .map_err(|_| SuiError::from("Batch signing pool is closed"))?;
+                signed
+                    .await
+                    .map_err(|_| SuiError::from("Batch signing worker dropped the request"))
+            }
+        }
+    }
+}
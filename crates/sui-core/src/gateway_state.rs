@@ -723,10 +723,15 @@ where
         self.download_object_from_authorities(SUI_SYSTEM_STATE_OBJECT_ID)
             .await?;
 
-        let (_gas_status, input_objects) =
-            transaction_input_checker::check_transaction_input(&self.store, transaction).await?;
+        let (_gas_status, input_objects) = transaction_input_checker::check_transaction_input(
+            &self.store,
+            transaction,
+            None,
+            transaction_input_checker::DEFAULT_MAX_OBJECT_ERRORS,
+        )
+        .await?;
 
-        let owned_objects = input_objects.filter_owned_objects();
+        let owned_objects = input_objects.classify().owned;
         if let Err(err) = self
             .set_transaction_lock(&owned_objects, transaction.clone())
             .instrument(tracing::trace_span!("db_set_transaction_lock"))
@@ -1452,10 +1457,7 @@ where
     ) -> Result<TransactionData, anyhow::Error> {
         fp_ensure!(
             !single_transaction_params.is_empty(),
-            SuiError::InvalidBatchTransaction {
-                error: "Batch Transaction cannot be empty".to_owned(),
-            }
-            .into()
+            SuiError::EmptyTransactionKind.into()
         );
         let mut all_tx_kind = vec![];
         let mut used_object_ids = BTreeSet::new();
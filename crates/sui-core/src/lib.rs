@@ -7,6 +7,7 @@ pub mod authority_aggregator;
 pub mod authority_batch;
 pub mod authority_client;
 pub mod authority_server;
+pub mod batch_signer;
 pub mod checkpoints;
 pub mod consensus_adapter;
 pub mod epoch;
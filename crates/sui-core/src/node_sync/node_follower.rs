@@ -256,6 +256,7 @@ where
     let req = BatchInfoRequest {
         start: Some(start_seq),
         length: max_stream_items,
+        headers_only: false,
     };
 
     let mut stream = Box::pin(client.handle_batch_stream(req).await?);
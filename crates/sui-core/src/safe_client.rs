@@ -2,7 +2,7 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::authority_client::{AuthorityAPI, BatchInfoResponseItemStream};
+use crate::authority_client::{AuthorityAPI, BatchInfoResponseItemStream, BatchStreamAcks};
 use crate::epoch::committee_store::CommitteeStore;
 use crate::histogram::{Histogram, HistogramVec};
 use futures::StreamExt;
@@ -23,6 +23,7 @@ use sui_types::{
     error::{SuiError, SuiResult},
     messages::*,
 };
+use tokio::sync::watch;
 use tracing::info;
 
 /// Prometheus metrics which can be displayed in Grafana, queried and alerted on
@@ -81,6 +82,20 @@ impl SafeClientMetrics {
     }
 }
 
+/// What a fully verified batch stream contained, as returned by
+/// `SafeClient::verify_batch_stream`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BatchStreamSummary {
+    /// Number of transactions covered by a signed batch.
+    pub total_transactions: u64,
+    /// Number of signed batches, including the initial one.
+    pub total_batches: u64,
+    /// Sequence number of the first transaction covered by a signed batch.
+    pub first_sequence: Option<TxSequenceNumber>,
+    /// Sequence number of the last transaction covered by a signed batch.
+    pub last_sequence: Option<TxSequenceNumber>,
+}
+
 /// See `SafeClientMetrics::new` for description of each metrics.
 /// The metrics are per validator client.
 #[derive(Clone)]
@@ -206,6 +221,19 @@ impl<C> SafeClient<C> {
             .ok_or(SuiError::MissingCommitteeAtEpoch(*epoch_id))
     }
 
+    /// Returns the committee of `epoch`, reusing `cached` unless it belongs to another epoch,
+    /// in which case the committee is loaded from the committee store and cached instead.
+    pub(crate) fn get_cached_committee<'a>(
+        &self,
+        cached: &'a mut Option<Committee>,
+        epoch: EpochId,
+    ) -> SuiResult<&'a Committee> {
+        if cached.as_ref().map(|committee| committee.epoch) != Some(epoch) {
+            *cached = Some(self.get_committee(&epoch)?);
+        }
+        Ok(cached.as_ref().unwrap())
+    }
+
     // Here we centralize all checks for transaction info responses
     fn check_transaction_response(
         &self,
@@ -383,15 +411,29 @@ impl<C> SafeClient<C> {
 
     fn check_update_item_batch_response(
         &self,
-        _request: BatchInfoRequest,
+        request: BatchInfoRequest,
         signed_batch: &SignedBatch,
         transactions_and_last_batch: &Option<(
             Vec<(TxSequenceNumber, ExecutionDigests)>,
             AuthorityBatch,
         )>,
+        committee: &mut Option<Committee>,
     ) -> SuiResult {
-        // check the signature of the batch
-        signed_batch.verify(&self.get_committee(&signed_batch.auth_sig().epoch)?)?;
+        // check the signature of the batch, against the committee of the epoch it was signed
+        // in since the authority may have moved to a new epoch mid-stream.
+        signed_batch
+            .verify(self.get_cached_committee(committee, signed_batch.auth_sig().epoch)?)?;
+
+        // The first batch of the stream is the one preceding the requested transactions, it
+        // must not skip past the requested start.
+        if let (None, Some(start)) = (transactions_and_last_batch, request.start) {
+            fp_ensure!(
+                signed_batch.data().next_sequence_number <= start,
+                SuiError::MissingInitialBatch {
+                    authority: self.address
+                }
+            );
+        }
 
         // ensure transactions enclosed match requested range
 
@@ -413,6 +455,22 @@ impl<C> SafeClient<C> {
         // is constructed correctly:
 
         if let Some((transactions, prev_batch)) = transactions_and_last_batch {
+            // Without the transactions we can only check that the batch extends the previous one.
+            if request.headers_only {
+                let batch = signed_batch.data();
+                fp_ensure!(
+                    batch.previous_digest == Some(prev_batch.digest())
+                        && batch.size > 0
+                        && prev_batch.next_sequence_number <= batch.initial_sequence_number
+                        && batch.initial_sequence_number < batch.next_sequence_number,
+                    SuiError::ByzantineAuthoritySuspicion {
+                        authority: self.address,
+                        reason: "Inconsistent batch".to_string()
+                    }
+                );
+                return Ok(());
+            }
+
             fp_ensure!(
                 !transactions.is_empty(),
                 SuiError::GenericAuthorityError {
@@ -433,6 +491,78 @@ impl<C> SafeClient<C> {
         Ok(())
     }
 
+    /// On an acknowledged stream, the authority may skip the batches the client has acknowledged
+    /// and resume from the last of them. Such a batch does not extend the previous one, so it
+    /// is only accepted if it is correctly signed and every transaction it skips over is acked.
+    fn is_acked_batch_restart(
+        &self,
+        signed_batch: &SignedBatch,
+        transactions_and_last_batch: &Option<(
+            Vec<(TxSequenceNumber, ExecutionDigests)>,
+            AuthorityBatch,
+        )>,
+        committee: &mut Option<Committee>,
+        acked: Option<TxSequenceNumber>,
+    ) -> bool {
+        let (last_batch, acked) = match (transactions_and_last_batch, acked) {
+            (Some((_, last_batch)), Some(acked)) => (last_batch, acked),
+            _ => return false,
+        };
+        let next_sequence_number = signed_batch.data().next_sequence_number;
+        last_batch.next_sequence_number < next_sequence_number
+            && next_sequence_number <= acked.saturating_add(1)
+            && self
+                .get_cached_committee(committee, signed_batch.auth_sig().epoch)
+                .and_then(|committee| signed_batch.verify(committee))
+                .is_ok()
+    }
+
+    /// Batch stream items must arrive in order: transactions with strictly increasing
+    /// sequence numbers following the previous batch, and batches with increasing
+    /// `next_sequence_number`.
+    fn check_update_item_order(
+        &self,
+        item: &UpdateItem,
+        transactions_and_last_batch: &Option<(
+            Vec<(TxSequenceNumber, ExecutionDigests)>,
+            AuthorityBatch,
+        )>,
+    ) -> SuiResult {
+        // The first item of a stream has nothing to be ordered against.
+        let (transactions, last_batch) = match transactions_and_last_batch {
+            Some(transactions_and_last_batch) => transactions_and_last_batch,
+            None => return Ok(()),
+        };
+
+        let (previous, received, in_order) = match item {
+            UpdateItem::Transaction((seq, _)) => match transactions.last() {
+                Some((last_seq, _)) => (*last_seq, *seq, seq > last_seq),
+                None => (
+                    last_batch.next_sequence_number,
+                    *seq,
+                    *seq >= last_batch.next_sequence_number,
+                ),
+            },
+            UpdateItem::Batch(signed_batch) => {
+                let next = signed_batch.data().next_sequence_number;
+                (
+                    last_batch.next_sequence_number,
+                    next,
+                    next > last_batch.next_sequence_number,
+                )
+            }
+        };
+        fp_ensure!(
+            in_order,
+            SuiError::BatchStreamOutOfOrder {
+                authority: self.address,
+                previous,
+                received,
+            }
+        );
+        Ok(())
+    }
+
     /// This function is used by the higher level authority logic to report an
     /// error that could be due to this authority.
     /// TODO: Get rid of this. https://github.com/MystenLabs/sui/issues/3740
@@ -747,12 +877,43 @@ where
             .handle_batch_stream(request.clone())
             .await?;
         self.metrics_total_ok_responses_handle_batch_stream.inc();
+        Ok(self.check_batch_stream(request, batch_info_items, None))
+    }
+
+    /// Same as `handle_batch_stream`, but reports `acks` to the authority, which may then
+    /// skip the batches the client has acknowledged.
+    pub async fn handle_batch_stream_with_ack(
+        &self,
+        request: BatchInfoRequest,
+        acks: BatchStreamAcks,
+    ) -> Result<BatchInfoResponseItemStream, SuiError> {
+        self.metrics_total_requests_handle_batch_stream.inc();
+        let (acked_tx, acked_rx) = watch::channel(None);
+        let acks = acks
+            .inspect(move |seq| {
+                acked_tx.send_modify(|acked| *acked = (*acked).max(Some(*seq)));
+            })
+            .boxed();
+        let batch_info_items = self
+            .authority_client
+            .handle_batch_stream_with_ack(request.clone(), acks)
+            .await?;
+        self.metrics_total_ok_responses_handle_batch_stream.inc();
+        Ok(self.check_batch_stream(request, batch_info_items, Some(acked_rx)))
+    }
+
+    fn check_batch_stream(
+        &self,
+        request: BatchInfoRequest,
+        batch_info_items: BatchInfoResponseItemStream,
+        acked: Option<watch::Receiver<Option<TxSequenceNumber>>>,
+    ) -> BatchInfoResponseItemStream {
         let client = self.clone();
         let address = self.address;
         let count: u64 = 0;
         let stream = Box::pin(batch_info_items.scan(
-            (None, count),
-            move |(txs_and_last_batch, count), batch_info_item| {
+            (None, None, count),
+            move |(txs_and_last_batch, committee, count), batch_info_item| {
                 let req_clone = request.clone();
                 let client = client.clone();
 
@@ -762,31 +923,64 @@ where
                     // If we exceed it return None to end stream
                     return futures::future::ready(None);
                 }
+                if let Ok(BatchInfoResponseItem(item)) = &batch_info_item {
+                    if let Err(err) = client.check_update_item_order(item, txs_and_last_batch) {
+                        client.report_client_error(&err);
+                        return futures::future::ready(Some(Err(err)));
+                    }
+                }
                 let result = match &batch_info_item {
                     Ok(BatchInfoResponseItem(UpdateItem::Batch(signed_batch))) => {
-                        if let Err(err) = client.check_update_item_batch_response(
-                            req_clone,
-                            signed_batch,
-                            txs_and_last_batch,
-                        ) {
+                        let acked = acked.as_ref().and_then(|acked| *acked.borrow());
+                        if let Err(err) = client
+                            .check_update_item_batch_response(
+                                req_clone,
+                                signed_batch,
+                                txs_and_last_batch,
+                                committee,
+                            )
+                            .or_else(|err| {
+                                if client.is_acked_batch_restart(
+                                    signed_batch,
+                                    txs_and_last_batch,
+                                    committee,
+                                    acked,
+                                ) {
+                                    Ok(())
+                                } else {
+                                    Err(err)
+                                }
+                            })
+                        {
                             client.report_client_error(&err);
                             Some(Err(err))
                         } else {
+                            // The transactions are not streamed, so count them from the batch.
+                            if request.headers_only && txs_and_last_batch.is_some() {
+                                *count += signed_batch.data().size;
+                            }
                             // Insert a fresh vector for the new batch of transactions
                             let _ = txs_and_last_batch
                                 .insert((Vec::new(), signed_batch.data().clone()));
                             Some(batch_info_item)
                         }
                     }
+                    Ok(BatchInfoResponseItem(UpdateItem::Transaction(_)))
+                        if request.headers_only =>
+                    {
+                        let err = SuiError::ByzantineAuthoritySuspicion {
+                            authority: address,
+                            reason: "Transaction in a headers only batch stream".to_string(),
+                        };
+                        client.report_client_error(&err);
+                        Some(Err(err))
+                    }
                     Ok(BatchInfoResponseItem(UpdateItem::Transaction((seq, digest)))) => {
                         // A stream always starts with a batch, so the previous should have initialized it.
                         // And here we insert the tuple into the batch.
                         match txs_and_last_batch {
                             None => {
-                                let err = SuiError::ByzantineAuthoritySuspicion {
-                                    authority: address,
-                                    reason: "Stream does not start with a batch".to_string(),
-                                };
+                                let err = SuiError::MissingInitialBatch { authority: address };
                                 client.report_client_error(&err);
                                 Some(Err(err))
                             }
@@ -804,6 +998,51 @@ where
                 futures::future::ready(result)
             },
         ));
-        Ok(Box::pin(stream))
+        Box::pin(stream)
+    }
+
+    /// Drive the whole batch stream for `request`, applying the same checks as
+    /// `handle_batch_stream` (ordering, batch chaining and signatures) and additionally
+    /// rejecting gaps in the transaction sequence numbers. Transactions streamed after
+    /// the last signed batch are not covered by a signature and are left out of the summary.
+    pub async fn verify_batch_stream(
+        &self,
+        request: BatchInfoRequest,
+    ) -> SuiResult<BatchStreamSummary> {
+        let mut stream = self.handle_batch_stream(request).await?;
+        let mut summary = BatchStreamSummary::default();
+        let mut pending: Vec<TxSequenceNumber> = Vec::new();
+        let mut next_expected = None;
+
+        while let Some(item) = stream.next().await {
+            match item?.0 {
+                UpdateItem::Batch(signed_batch) => {
+                    if let (Some(first), Some(last)) = (pending.first(), pending.last()) {
+                        summary.first_sequence = summary.first_sequence.or(Some(*first));
+                        summary.last_sequence = Some(*last);
+                        summary.total_transactions += pending.len() as u64;
+                        pending.clear();
+                    }
+                    summary.total_batches += 1;
+                    next_expected = Some(signed_batch.data().next_sequence_number);
+                }
+                UpdateItem::Transaction((seq, _)) => {
+                    if let Some(expected) = next_expected {
+                        if seq != expected {
+                            let err = SuiError::BatchStreamGap {
+                                authority: self.address,
+                                expected,
+                                received: seq,
+                            };
+                            self.report_client_error(&err);
+                            return Err(err);
+                        }
+                    }
+                    pending.push(seq);
+                    next_expected = Some(seq + 1);
+                }
+            }
+        }
+        Ok(summary)
     }
 }
@@ -76,6 +76,7 @@ pub async fn wait_for_all_txes(wait_digests: Vec<TransactionDigest>, state: Arc<
             .handle_batch_streaming(BatchInfoRequest {
                 start: max_seq,
                 length: 1000,
+                headers_only: false,
             })
             .await
             .unwrap(),
@@ -112,6 +113,7 @@ pub async fn wait_for_all_txes(wait_digests: Vec<TransactionDigest>, state: Arc<
                                     .handle_batch_streaming(BatchInfoRequest {
                                         start: max_seq,
                                         length: 1000,
+                                        headers_only: false,
                                     })
                                     .await
                                     .unwrap(),
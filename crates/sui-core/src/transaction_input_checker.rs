@@ -1,14 +1,24 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::authority::SuiDataStore;
+use crate::authority::{AuthorityMetrics, SuiDataStore};
+use lru::LruCache;
+use parking_lot::Mutex;
+use prometheus::IntCounter;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+pub use sui_config::node::{SignatureVerificationPoolConfig, DEFAULT_MAX_OBJECT_ERRORS};
 use sui_types::base_types::ObjectRef;
+use sui_types::committee::EpochId;
+use sui_types::crypto::{FastCryptoVerifier, Signable, Signature, SignatureVerifier, SuiSignature};
+pub use sui_types::gas::{EpochGasParams, KindGasMultipliers};
 use sui_types::messages::TransactionKind;
 use sui_types::{
-    base_types::{SequenceNumber, SuiAddress},
+    base_types::{SequenceNumber, SuiAddress, TransactionDigest},
     error::{SuiError, SuiResult},
     fp_ensure,
     gas::{self, SuiGasStatus},
@@ -18,11 +28,246 @@ use sui_types::{
     },
     object::{Object, Owner},
 };
+use tokio::sync::Semaphore;
 use tracing::instrument;
 
+#[cfg(test)]
+#[path = "unit_tests/transaction_input_checker_tests.rs"]
+mod transaction_input_checker_tests;
+
+/// A bounded cache of the digests of transactions whose sender signature has already been
+/// verified, so that a client retrying the same signed transaction does not pay for the
+/// signature check again. The digest covers the signature, so a resubmission with a different
+/// signature misses the cache. Entries are only valid for the epoch they were verified in.
+pub struct VerifiedSignatureCache {
+    inner: Mutex<VerifiedSignatures>,
+    verifications: AtomicU64,
+}
+
+struct VerifiedSignatures {
+    epoch: EpochId,
+    digests: LruCache<TransactionDigest, ()>,
+}
+
+impl VerifiedSignatureCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Mutex::new(VerifiedSignatures {
+                epoch: 0,
+                digests: LruCache::new(capacity),
+            }),
+            verifications: AtomicU64::new(0),
+        }
+    }
+
+    /// The number of signatures actually verified, i.e. the cache misses.
+    pub fn verifications(&self) -> u64 {
+        self.verifications.load(Ordering::Relaxed)
+    }
+
+    /// Drop the entries verified in `epoch` or earlier, and stop caching verifications for
+    /// those epochs. Called during reconfiguration with the epoch that ended, so that a
+    /// verification finishing late in the old epoch cannot be cached past the boundary.
+    pub fn invalidate_epoch(&self, epoch: EpochId) {
+        let mut inner = self.inner.lock();
+        if inner.epoch <= epoch {
+            inner.epoch = epoch + 1;
+            inner.digests.clear();
+        }
+    }
+
+    /// A lookup for a newer epoch moves the cache to it, while one for an older epoch misses
+    /// without touching the current entries.
+    fn is_verified(&self, epoch: EpochId, digest: &TransactionDigest) -> bool {
+        let mut inner = self.inner.lock();
+        if epoch < inner.epoch {
+            return false;
+        }
+        if epoch > inner.epoch {
+            inner.epoch = epoch;
+            inner.digests.clear();
+        }
+        inner.digests.get(digest).is_some()
+    }
+
+    fn insert(&self, epoch: EpochId, digest: TransactionDigest) {
+        let mut inner = self.inner.lock();
+        if inner.epoch == epoch {
+            inner.digests.put(digest, ());
+        }
+    }
+}
+
+/// Keep the first `max_errors` errors of an `ObjectErrors`, followed by a `TooManyObjectErrors`
+/// summary if any were dropped, so that a transaction with many bad inputs does not produce an
+/// arbitrarily large error. Other errors are returned unchanged.
+pub fn limit_object_errors(error: SuiError, max_errors: usize) -> SuiError {
+    match error {
+        SuiError::ObjectErrors { mut errors } if errors.len() > max_errors => {
+            let total = errors.len();
+            errors.truncate(max_errors);
+            errors.push(SuiError::TooManyObjectErrors { total });
+            SuiError::ObjectErrors { errors }
+        }
+        error => error,
+    }
+}
+
+/// The gas parameters of the current epoch, so that checking a transaction does not have to read
+/// and deserialize the system state object each time. The parameters only change with the
+/// epoch, so a lookup for a new epoch replaces the cached entry.
+pub struct EpochGasParamsCache {
+    params: Mutex<Option<(EpochId, EpochGasParams)>>,
+    /// Applied to the parameters loaded from the system state.
+    kind_gas_multipliers: KindGasMultipliers,
+    hits: IntCounter,
+    misses: IntCounter,
+}
+
+impl EpochGasParamsCache {
+    /// A cache counting its hits and misses in `metrics`.
+    pub fn new(kind_gas_multipliers: KindGasMultipliers, metrics: &AuthorityMetrics) -> Self {
+        Self {
+            params: Mutex::new(None),
+            kind_gas_multipliers,
+            hits: metrics.gas_price_cache_hits.clone(),
+            misses: metrics.gas_price_cache_misses.clone(),
+        }
+    }
+
+    fn get_or_load(
+        &self,
+        epoch: EpochId,
+        load: impl FnOnce() -> SuiResult<EpochGasParams>,
+    ) -> SuiResult<EpochGasParams> {
+        let mut params = self.params.lock();
+        match *params {
+            Some((cached_epoch, cached)) if cached_epoch == epoch => {
+                self.hits.inc();
+                Ok(cached)
+            }
+            _ => {
+                self.misses.inc();
+                let loaded = EpochGasParams {
+                    kind_gas_multipliers: self.kind_gas_multipliers,
+                    ..load()?
+                };
+                *params = Some((epoch, loaded));
+                Ok(loaded)
+            }
+        }
+    }
+}
+
+/// Verify the sender signature of `transaction`, on `verifier` if given and inline otherwise.
+/// With a `signature_cache`, the check is skipped for transactions already verified in
+/// `epoch`, and successful checks are cached.
+pub async fn check_sender_signature<T>(
+    transaction: &TransactionEnvelope<T>,
+    epoch: EpochId,
+    signature_cache: Option<&VerifiedSignatureCache>,
+    verifier: Option<&SignatureVerificationPool>,
+) -> SuiResult {
+    let digest = transaction.digest();
+    if let Some(cache) = signature_cache {
+        if cache.is_verified(epoch, digest) {
+            return Ok(());
+        }
+        cache.verifications.fetch_add(1, Ordering::Relaxed);
+    }
+    match verifier {
+        Some(verifier) => verifier.verify_sender_signature(transaction).await?,
+        None => transaction.verify_sender_signature()?,
+    }
+    if let Some(cache) = signature_cache {
+        cache.insert(epoch, *digest);
+    }
+    Ok(())
+}
+
+/// Runs signature verifications on blocking threads, at most `max_concurrent` at a time, and
+/// fails those that do not finish within `budget` of starting with
+/// `SuiError::VerificationTimeout`. A verification cannot be interrupted once started, so it
+/// keeps its permit until it really completes: pathological signatures can tie up at most
+/// `max_concurrent` threads, while further requests wait for a permit instead of piling up on
+/// the blocking pool.
+pub struct SignatureVerificationPool {
+    permits: Arc<Semaphore>,
+    budget: Duration,
+}
+
+impl From<&SignatureVerificationPoolConfig> for SignatureVerificationPool {
+    fn from(config: &SignatureVerificationPoolConfig) -> Self {
+        Self::new(
+            config.max_concurrent,
+            Duration::from_millis(config.budget_ms),
+        )
+    }
+}
+
+impl SignatureVerificationPool {
+    pub fn new(max_concurrent: usize, budget: Duration) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(max_concurrent)),
+            budget,
+        }
+    }
+
+    /// Verify the sender signature of `transaction`, like
+    /// `TransactionEnvelope::verify_sender_signature`.
+    pub async fn verify_sender_signature<T>(
+        &self,
+        transaction: &TransactionEnvelope<T>,
+    ) -> SuiResult {
+        if transaction.is_verified || transaction.signed_data.data.kind.is_system_tx() {
+            return Ok(());
+        }
+        self.verify(
+            transaction.signed_data.tx_signature.clone(),
+            transaction.signed_data.data.clone(),
+            transaction.signed_data.data.sender,
+            Arc::new(FastCryptoVerifier),
+        )
+        .await
+    }
+
+    /// Verify `signature` by `author` over `value` with `verifier`.
+    pub async fn verify<T, V>(
+        &self,
+        signature: Signature,
+        value: T,
+        author: SuiAddress,
+        verifier: Arc<V>,
+    ) -> SuiResult
+    where
+        T: Signable<Vec<u8>> + Send + 'static,
+        V: SignatureVerifier + Send + Sync + 'static,
+    {
+        let permit = self.permits.clone().acquire_owned().await.map_err(|e| {
+            SuiError::SignatureVerificationFailed {
+                error: e.to_string(),
+            }
+        })?;
+        // The budget only starts once the verification does, so waiting for a permit behind
+        // slow verifications does not fail valid signatures.
+        let verification = tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            signature.verify_with(&value, author, &*verifier)
+        });
+        let budget = self.budget;
+        tokio::time::timeout(budget, verification)
+            .await
+            .map_err(|_| SuiError::VerificationTimeout { budget })?
+            .map_err(|e| SuiError::SignatureVerificationFailed {
+                error: e.to_string(),
+            })?
+    }
+}
+
 async fn get_gas_status<S, T>(
     store: &SuiDataStore<S>,
     transaction: &TransactionEnvelope<T>,
+    gas_params_cache: Option<(EpochId, &EpochGasParamsCache)>,
 ) -> SuiResult<SuiGasStatus<'static>>
 where
     S: Eq + Debug + Serialize + for<'de> Deserialize<'de>,
@@ -32,6 +277,7 @@ where
         transaction.gas_payment_object_ref(),
         transaction.signed_data.data.gas_budget,
         transaction.signed_data.data.gas_price,
+        gas_params_cache,
         &transaction.signed_data.data.kind,
     )
     .await?;
@@ -45,42 +291,170 @@ where
     Ok(gas_status)
 }
 
+/// The gas coin is mutated by every transaction it pays for, so it cannot also be one of the
+/// transaction's own inputs. Without this check the duplicate would only surface later as a
+/// confusing batch error from `check_objects`.
+fn check_gas_not_used_as_input(transaction: &TransactionData) -> SuiResult {
+    if transaction.kind.is_system_tx() {
+        return Ok(());
+    }
+    let gas_id = transaction.gas_payment_object_ref().0;
+    for kind in transaction.kind.input_objects()? {
+        fp_ensure!(
+            kind.object_id() != gas_id,
+            SuiError::GasObjectUsedAsInput { object_id: gas_id }
+        );
+    }
+    Ok(())
+}
+
+/// Each object must be referenced at a single version throughout the transaction. Within a
+/// batch, the same object can otherwise appear at different versions in different single
+/// transactions, which at most one of can be current.
+fn check_consistent_versions(input_objects: &[InputObjectKind]) -> SuiResult {
+    let mut versions = HashMap::new();
+    for kind in input_objects {
+        if let InputObjectKind::ImmOrOwnedMoveObject((object_id, version, _)) = kind {
+            let first = versions.entry(*object_id).or_insert(*version);
+            fp_ensure!(
+                first == version,
+                SuiError::InconsistentObjectVersion {
+                    object_id: *object_id
+                }
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Check the inputs and gas of `transaction` against the store. With a `gas_params_cache`, the
+/// gas parameters are read from the system state at most once per epoch. At most
+/// `max_object_errors` errors about input objects are reported.
 #[instrument(level = "trace", skip_all)]
 pub async fn check_transaction_input<S, T>(
     store: &SuiDataStore<S>,
     transaction: &TransactionEnvelope<T>,
+    gas_params_cache: Option<(EpochId, &EpochGasParamsCache)>,
+    max_object_errors: usize,
 ) -> SuiResult<(SuiGasStatus<'static>, InputObjects)>
 where
     S: Eq + Debug + Serialize + for<'de> Deserialize<'de>,
 {
     transaction.signed_data.data.kind.validity_check()?;
-    let gas_status = get_gas_status(store, transaction).await?;
+    check_gas_not_used_as_input(&transaction.signed_data.data)?;
     let input_objects = transaction.signed_data.data.input_objects()?;
-    let objects = store.get_input_objects(&input_objects)?;
-    let input_objects =
-        check_objects(&transaction.signed_data.data, input_objects, objects).await?;
+    // Reject an empty input set before doing any reads from the store.
+    fp_ensure!(
+        !input_objects.is_empty(),
+        SuiError::ObjectInputArityViolation
+    );
+    check_consistent_versions(&input_objects)?;
+    let gas_status = get_gas_status(store, transaction, gas_params_cache).await?;
+    let objects = store
+        .get_input_objects(&input_objects)
+        .map_err(|e| limit_object_errors(e, max_object_errors))?;
+    let input_objects = check_objects(&transaction.signed_data.data, input_objects, objects)
+        .map_err(|e| limit_object_errors(e, max_object_errors))?;
     Ok((gas_status, input_objects))
 }
 
+/// Run the checks of `check_transaction_input` that do not need the authority store, plus
+/// the sender signature check, against objects and gas parameters supplied by the caller.
+/// This lets clients pre-flight a transaction before submitting it. `objects` must contain the
+/// gas object and all input objects, at the versions the transaction refers to.
+pub fn check_transaction_input_offline<T>(
+    transaction: &TransactionEnvelope<T>,
+    objects: &[Object],
+    gas_params: &EpochGasParams,
+) -> SuiResult {
+    let tx_data = &transaction.signed_data.data;
+    tx_data.kind.validity_check()?;
+    check_gas_not_used_as_input(tx_data)?;
+    transaction.verify_sender_signature()?;
+
+    let input_objects = tx_data.input_objects()?;
+    fp_ensure!(
+        !input_objects.is_empty(),
+        SuiError::ObjectInputArityViolation
+    );
+    check_consistent_versions(&input_objects)?;
+    let objects_by_id: HashMap<_, _> = objects.iter().map(|object| (object.id(), object)).collect();
+
+    let mut gas_status = if tx_data.kind.is_system_tx() {
+        SuiGasStatus::new_unmetered()
+    } else {
+        let (gas_id, gas_version, _) = *tx_data.gas_payment_object_ref();
+        let gas_object = objects_by_id
+            .get(&gas_id)
+            .filter(|object| object.version() == gas_version)
+            .ok_or(SuiError::ObjectErrors {
+                errors: vec![SuiError::ObjectNotFound { object_id: gas_id }],
+            })?;
+        check_gas_object(
+            gas_object,
+            tx_data.gas_budget,
+            tx_data.gas_price,
+            gas_params,
+            &tx_data.kind,
+        )?
+    };
+    if transaction.contains_shared_object() {
+        gas_status.charge_consensus()?;
+    }
+
+    // Mirror `SuiDataStore::get_input_objects`: owned objects are looked up at the
+    // referenced version, packages and shared objects at whatever version we were given.
+    let mut found = Vec::with_capacity(input_objects.len());
+    let mut errors = Vec::new();
+    for kind in &input_objects {
+        let object = objects_by_id
+            .get(&kind.object_id())
+            .filter(|object| match kind {
+                InputObjectKind::ImmOrOwnedMoveObject((_, version, _)) => {
+                    object.version() == *version
+                }
+                _ => true,
+            });
+        match object {
+            Some(object) => found.push((*object).clone()),
+            None => errors.push(kind.object_not_found_error()),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(limit_object_errors(
+            SuiError::ObjectErrors { errors },
+            DEFAULT_MAX_OBJECT_ERRORS,
+        ));
+    }
+
+    check_objects(tx_data, input_objects, found)
+        .map_err(|e| limit_object_errors(e, DEFAULT_MAX_OBJECT_ERRORS))?;
+    Ok(())
+}
+
 pub async fn check_certificate_input<S>(
     store: &SuiDataStore<S>,
     cert: &CertifiedTransaction,
+    gas_params_cache: Option<(EpochId, &EpochGasParamsCache)>,
+    max_object_errors: usize,
 ) -> SuiResult<(SuiGasStatus<'static>, InputObjects)>
 where
     S: Eq + Debug + Serialize + for<'de> Deserialize<'de>,
 {
-    let gas_status = get_gas_status(store, cert).await?;
     let input_objects = cert.signed_data.data.input_objects()?;
+    let gas_status = get_gas_status(store, cert, gas_params_cache).await?;
 
     let tx_data = &cert.signed_data.data;
     let objects = if tx_data.kind.is_change_epoch_tx() {
         // When changing the epoch, we update a the system object, which is shared, without going
         // through sequencing, so we must bypass the sequence checks here.
-        store.get_input_objects(&input_objects)?
+        store.get_input_objects(&input_objects)
     } else {
-        store.get_sequenced_input_objects(cert.digest(), &input_objects)?
-    };
-    let input_objects = check_objects(&cert.signed_data.data, input_objects, objects).await?;
+        store.get_sequenced_input_objects(cert.digest(), &input_objects)
+    }
+    .map_err(|e| limit_object_errors(e, max_object_errors))?;
+    let input_objects = check_objects(&cert.signed_data.data, input_objects, objects)
+        .map_err(|e| limit_object_errors(e, max_object_errors))?;
     Ok((gas_status, input_objects))
 }
 
@@ -94,6 +468,7 @@ async fn check_gas<S>(
     gas_payment: &ObjectRef,
     gas_budget: u64,
     computation_gas_price: u64,
+    gas_params_cache: Option<(EpochId, &EpochGasParamsCache)>,
     tx_kind: &TransactionKind,
 ) -> SuiResult<SuiGasStatus<'static>>
 where
@@ -102,41 +477,82 @@ where
     if tx_kind.is_system_tx() {
         Ok(SuiGasStatus::new_unmetered())
     } else {
+        let load = || Ok(EpochGasParams::from(&store.get_sui_system_state_object()?));
+        let gas_params = match gas_params_cache {
+            Some((epoch, cache)) => cache.get_or_load(epoch, load)?,
+            None => load()?,
+        };
         let gas_object = store.get_object_by_key(&gas_payment.0, gas_payment.1)?;
         let gas_object = gas_object.ok_or(SuiError::ObjectErrors {
             errors: vec![SuiError::ObjectNotFound {
                 object_id: gas_payment.0,
             }],
         })?;
+        // The version is pinned by the lookup above, but the digest is not.
+        let expected_digest = gas_object.digest();
+        fp_ensure!(
+            expected_digest == gas_payment.2,
+            SuiError::ObjectErrors {
+                errors: vec![SuiError::InvalidObjectDigest {
+                    object_id: gas_payment.0,
+                    expected_digest,
+                }],
+            }
+        );
 
-        //TODO: cache this storage_gas_price in memory
-        let storage_gas_price = store
-            .get_sui_system_state_object()?
-            .parameters
-            .storage_gas_price;
-
-        // If the transaction is TransferSui, we ensure that the gas balance is enough to cover
-        // both gas budget and the transfer amount.
-        let extra_amount =
-            if let TransactionKind::Single(SingleTransactionKind::TransferSui(t)) = tx_kind {
-                t.amount.unwrap_or_default()
-            } else {
-                0
-            };
-        // TODO: We should revisit how we compute gas price and compare to gas budget.
-        let gas_price = std::cmp::max(computation_gas_price, storage_gas_price);
-
-        gas::check_gas_balance(&gas_object, gas_budget, gas_price, extra_amount)?;
-        let gas_status =
-            gas::start_gas_metering(gas_budget, computation_gas_price, storage_gas_price)?;
-        Ok(gas_status)
+        check_gas_object(
+            &gas_object,
+            gas_budget,
+            computation_gas_price,
+            &gas_params,
+            tx_kind,
+        )
     }
 }
 
+/// Check that the balance of `gas_object` covers the gas budget (and the transferred amount
+/// for TransferSui), and start metering gas for the transaction.
+fn check_gas_object(
+    gas_object: &Object,
+    gas_budget: u64,
+    computation_gas_price: u64,
+    gas_params: &EpochGasParams,
+    tx_kind: &TransactionKind,
+) -> SuiResult<SuiGasStatus<'static>> {
+    // If the transaction is TransferSui, we ensure that the gas balance is enough to cover
+    // both gas budget and the transfer amount.
+    let extra_amount =
+        if let TransactionKind::Single(SingleTransactionKind::TransferSui(t)) = tx_kind {
+            match t.amount {
+                Some(amount) => amount,
+                // Without an amount, whatever remains of the coin after paying for gas is
+                // transferred, so the balance only has to cover the gas budget.
+                None => 0,
+            }
+        } else {
+            0
+        };
+    let gas_price = gas_params.effective_gas_price(computation_gas_price);
+
+    gas::check_gas_balance(
+        gas_object,
+        gas_budget,
+        gas_price,
+        extra_amount,
+        gas_params.kind_gas_multiplier(tx_kind),
+    )?;
+    let gas_status = gas::start_gas_metering(
+        gas_budget,
+        computation_gas_price,
+        gas_params.storage_gas_price,
+    )?;
+    Ok(gas_status)
+}
+
 /// Check all the objects used in the transaction against the database, and ensure
 /// that they are all the correct version and number.
 #[instrument(level = "trace", skip_all)]
-async fn check_objects(
+fn check_objects(
     transaction: &TransactionData,
     input_objects: Vec<InputObjectKind>,
     objects: Vec<Object>,
@@ -17,10 +17,11 @@ use crate::authority::authority_tests::*;
 use crate::authority::*;
 use crate::safe_client::SafeClient;
 
-use crate::authority_client::{AuthorityAPI, BatchInfoResponseItemStream};
+use crate::authority_client::{AuthorityAPI, BatchInfoResponseItemStream, LocalAuthorityClient};
+use crate::batch_signer::BatchSigner;
 use crate::checkpoints::CheckpointStore;
 use crate::epoch::committee_store::CommitteeStore;
-use crate::safe_client::SafeClientMetrics;
+use crate::safe_client::{BatchStreamSummary, SafeClientMetrics};
 use async_trait::async_trait;
 use futures::lock::Mutex;
 use futures::stream;
@@ -28,11 +29,13 @@ use std::collections::BTreeMap;
 use std::env;
 use std::fs;
 use std::sync::Arc;
+use sui_config::node::{AuthorityConfig, DEFAULT_MAX_BATCH_STREAM_LENGTH};
 use sui_types::messages::{
     AccountInfoRequest, AccountInfoResponse, BatchInfoRequest, BatchInfoResponseItem,
     CertifiedTransaction, CommitteeInfoRequest, CommitteeInfoResponse, ObjectInfoRequest,
     ObjectInfoResponse, Transaction, TransactionInfoRequest, TransactionInfoResponse,
 };
+use tokio_stream::wrappers::ReceiverStream;
 
 pub(crate) fn init_state_parameters_from_rng<R>(
     rng: &mut R,
@@ -55,6 +58,15 @@ pub(crate) async fn init_state(
     committee: Committee,
     authority_key: AuthorityKeyPair,
     store: Arc<AuthorityStore>,
+) -> AuthorityState {
+    init_state_with_config(committee, authority_key, store, Default::default()).await
+}
+
+pub(crate) async fn init_state_with_config(
+    committee: Committee,
+    authority_key: AuthorityKeyPair,
+    store: Arc<AuthorityStore>,
+    config: AuthorityConfig,
 ) -> AuthorityState {
     let name = authority_key.public().into();
     let secrete = Arc::pin(authority_key);
@@ -79,6 +91,7 @@ pub(crate) async fn init_state(
         &sui_config::genesis::Genesis::get_default_genesis(),
         &prometheus::Registry::new(),
         tx_reconfigure_consensus,
+        config,
     )
     .await
 }
@@ -221,6 +234,59 @@ async fn test_batch_manager_happy_path() {
     _join.await.expect("No errors in task").expect("ok");
 }
 
+#[tokio::test]
+async fn test_batch_manager_cancellation() {
+    // Create a random directory to store the DB
+    let dir = env::temp_dir();
+    let path = dir.join(format!("DB_{:?}", ObjectID::random()));
+    fs::create_dir(&path).unwrap();
+
+    // Create an authority
+    let store = Arc::new(AuthorityStore::open(&path, None));
+
+    // Make a test key pair
+    let seed = [1u8; 32];
+    let (committee, _, authority_key) =
+        init_state_parameters_from_rng(&mut StdRng::from_seed(seed));
+    let authority_state = Arc::new(init_state(committee, authority_key, store.clone()).await);
+
+    let cancel = CancellationToken::new();
+    let inner_state = authority_state.clone();
+    let inner_cancel = cancel.clone();
+    let join = tokio::task::spawn(async move {
+        inner_state
+            .run_batch_service_until(1000, Duration::from_secs(6000), &inner_cancel)
+            .await
+    });
+
+    let mut rx = authority_state.subscribe_batch();
+    {
+        let t0 = authority_state.batch_notifier.ticket().expect("ok");
+        store.side_sequence(t0.seq(), &ExecutionDigests::random());
+        t0.notify();
+    }
+    assert!(matches!(
+        rx.recv().await.unwrap(),
+        UpdateItem::Transaction((0, _))
+    ));
+
+    // Neither the batch size nor the delay is reached, so only cancelling makes the batch.
+    cancel.cancel();
+    match rx.recv().await.unwrap() {
+        UpdateItem::Batch(batch) => assert_eq!(batch.data().next_sequence_number, 1),
+        item => panic!("Expected a batch, got {:?}", item),
+    }
+
+    tokio::time::timeout(Duration::from_secs(10), join)
+        .await
+        .expect("The service stops once cancelled")
+        .expect("No errors in task")
+        .expect("ok");
+
+    // The notifier itself was left open.
+    assert!(authority_state.batch_notifier.ticket().is_ok());
+}
+
 #[tokio::test]
 async fn test_batch_manager_out_of_order() {
     // Create a random directory to store the DB
@@ -524,6 +590,424 @@ async fn test_batch_store_retrieval() {
     _join.await.expect("No errors in task").expect("ok");
 }
 
+#[tokio::test]
+async fn test_batch_stream_subscriber_metrics() {
+    // Create a random directory to store the DB
+    let dir = env::temp_dir();
+    let path = dir.join(format!("DB_{:?}", ObjectID::random()));
+    fs::create_dir(&path).unwrap();
+
+    // Create an authority
+    let store = Arc::new(AuthorityStore::open(&path, None));
+
+    // Make a test key pair
+    let seed = [1u8; 32];
+    let (committee, _, authority_key) =
+        init_state_parameters_from_rng(&mut StdRng::from_seed(seed));
+    let authority_state = Arc::new(init_state(committee, authority_key, store).await);
+    let metrics = authority_state.metrics.clone();
+
+    let request = BatchInfoRequest {
+        start: Some(0),
+        length: 1,
+        headers_only: false,
+    };
+
+    let mut stream1 = Box::pin(
+        authority_state
+            .handle_batch_streaming(request.clone())
+            .await
+            .unwrap(),
+    );
+    assert_eq!(metrics.follower_connections_concurrent.get(), 1);
+    let mut stream2 = Box::pin(
+        authority_state
+            .handle_batch_streaming(request)
+            .await
+            .unwrap(),
+    );
+    assert_eq!(metrics.follower_connections_concurrent.get(), 2);
+
+    // Both streams start with the zero batch from the database.
+    assert!(matches!(
+        stream1.next().await,
+        Some(Ok(BatchInfoResponseItem(UpdateItem::Batch(_))))
+    ));
+    assert!(matches!(
+        stream2.next().await,
+        Some(Ok(BatchInfoResponseItem(UpdateItem::Batch(_))))
+    ));
+    assert_eq!(metrics.follower_items_streamed.get(), 2);
+
+    drop(stream1);
+    assert_eq!(metrics.follower_connections_concurrent.get(), 1);
+    drop(stream2);
+    assert_eq!(metrics.follower_connections_concurrent.get(), 0);
+}
+
+#[tokio::test]
+async fn test_batch_stream_ack_drops_buffered_items() {
+    // Create a random directory to store the DB
+    let dir = env::temp_dir();
+    let path = dir.join(format!("DB_{:?}", ObjectID::random()));
+    fs::create_dir(&path).unwrap();
+
+    // Create an authority
+    let store = Arc::new(AuthorityStore::open(&path, None));
+
+    // Make a test key pair
+    let seed = [1u8; 32];
+    let (committee, _, authority_key) =
+        init_state_parameters_from_rng(&mut StdRng::from_seed(seed));
+    let authority_state =
+        Arc::new(init_state(committee.clone(), authority_key, store.clone()).await);
+    let metrics = authority_state.metrics.clone();
+
+    // Sequence transactions 0 to 9, in two batches after the initial one.
+    let mut transactions = Vec::new();
+    for _ in 0..10 {
+        let ticket = authority_state.batch_notifier.ticket().expect("ok");
+        let digest = ExecutionDigests::random();
+        store.side_sequence(ticket.seq(), &digest);
+        transactions.push((ticket.seq(), digest));
+        ticket.notify();
+    }
+    let mut last_batch = AuthorityBatch::initial();
+    for chunk in transactions.chunks(5) {
+        let batch = AuthorityBatch::make_next(&last_batch, chunk).unwrap();
+        let signed_batch = SignedBatch::new(
+            0,
+            batch.clone(),
+            &*authority_state.secret,
+            authority_state.name,
+        );
+        store
+            .tables
+            .batches
+            .insert(&batch.next_sequence_number, &signed_batch)
+            .unwrap();
+        last_batch = batch;
+    }
+    let request = BatchInfoRequest {
+        start: Some(0),
+        length: 10,
+        headers_only: false,
+    };
+
+    // The client has already processed up to transaction 7.
+    let (ack_tx, ack_rx) = tokio::sync::mpsc::channel(10);
+    ack_tx.send(7).await.unwrap();
+    let mut stream = Box::pin(
+        authority_state
+            .handle_batch_streaming_with_ack(request.clone(), ReceiverStream::new(ack_rx).boxed())
+            .await
+            .unwrap(),
+    );
+
+    // The initial batch is sent regardless.
+    assert!(matches!(
+        stream.next().await,
+        Some(Ok(BatchInfoResponseItem(UpdateItem::Batch(batch))))
+            if batch.data().next_sequence_number == 0
+    ));
+    let buffered = metrics.batch_stream_buffered_items.get();
+
+    // Then the stream resumes from the last acknowledged batch, without its transactions.
+    assert!(matches!(
+        stream.next().await,
+        Some(Ok(BatchInfoResponseItem(UpdateItem::Batch(batch))))
+            if batch.data().next_sequence_number == 5
+    ));
+    assert_eq!(metrics.batch_stream_buffered_items.get(), buffered - 6);
+    assert!(matches!(
+        stream.next().await,
+        Some(Ok(BatchInfoResponseItem(UpdateItem::Transaction((5, _)))))
+    ));
+
+    // Whatever is left is released with the stream.
+    drop(stream);
+    assert_eq!(metrics.batch_stream_buffered_items.get(), 0);
+
+    // The safe client accepts the skipped batches, since it acknowledged them.
+    let safe_client = SafeClient::new(
+        LocalAuthorityClient::new_from_authority(authority_state.clone()),
+        authority_state.committee_store().clone(),
+        authority_state.name,
+        SafeClientMetrics::new_for_tests(),
+    );
+    let (ack_tx, ack_rx) = tokio::sync::mpsc::channel(10);
+    ack_tx.send(7).await.unwrap();
+    let items = safe_client
+        .handle_batch_stream_with_ack(request.clone(), ReceiverStream::new(ack_rx).boxed())
+        .await
+        .unwrap()
+        .collect::<Vec<_>>()
+        .await;
+    let sequence_numbers: Vec<_> = items
+        .into_iter()
+        .map(|item| match item.unwrap().0 {
+            UpdateItem::Batch(batch) => batch.data().next_sequence_number,
+            UpdateItem::Transaction((seq, _)) => seq,
+        })
+        .collect();
+    assert_eq!(sequence_numbers, vec![0, 5, 5, 6, 7, 8, 9, 10]);
+}
+
+#[tokio::test]
+async fn test_batch_stream_headers_only() {
+    // Create a random directory to store the DB
+    let dir = env::temp_dir();
+    let path = dir.join(format!("DB_{:?}", ObjectID::random()));
+    fs::create_dir(&path).unwrap();
+
+    // Create an authority
+    let store = Arc::new(AuthorityStore::open(&path, None));
+
+    // Make a test key pair
+    let seed = [1u8; 32];
+    let (committee, _, authority_key) =
+        init_state_parameters_from_rng(&mut StdRng::from_seed(seed));
+    let authority_state = init_state(committee.clone(), authority_key, store.clone()).await;
+
+    // Sequence transactions 0 to 4, in a batch after the initial one.
+    let mut transactions = Vec::new();
+    for _ in 0..5 {
+        let ticket = authority_state.batch_notifier.ticket().expect("ok");
+        let digest = ExecutionDigests::random();
+        store.side_sequence(ticket.seq(), &digest);
+        transactions.push((ticket.seq(), digest));
+        ticket.notify();
+    }
+    let batch = SignedBatch::new(
+        0,
+        AuthorityBatch::make_next(&AuthorityBatch::initial(), &transactions).unwrap(),
+        &*authority_state.secret,
+        authority_state.name,
+    );
+    store.tables.batches.insert(&5, &batch).unwrap();
+
+    let request = BatchInfoRequest {
+        start: Some(0),
+        length: 5,
+        headers_only: true,
+    };
+    let items = authority_state
+        .handle_batch_streaming(request.clone())
+        .await
+        .unwrap()
+        .collect::<Vec<_>>()
+        .await;
+    // Only the initial batch and the one enclosing the transactions are sent.
+    assert_eq!(items.len(), 2);
+    assert!(items
+        .iter()
+        .all(|item| matches!(item, Ok(BatchInfoResponseItem(UpdateItem::Batch(_))))));
+
+    // The safe client still checks that the batches chain on headers alone.
+    let (_, authority_key): (_, AuthorityKeyPair) = get_key_pair();
+    let public_key_bytes: AuthorityPublicKeyBytes = authority_key.public().into();
+    let committee = Committee::new(0, BTreeMap::from([(public_key_bytes, 1)])).unwrap();
+    let (tx_reconfigure_consensus, _rx_reconfigure_consensus) = tokio::sync::mpsc::channel(10);
+    let state = AuthorityState::new_for_testing(
+        committee,
+        &authority_key,
+        None,
+        None,
+        None,
+        tx_reconfigure_consensus,
+    )
+    .await;
+    let committee_store = state.committee_store().clone();
+    let safe_client = SafeClient::new(
+        TrustworthyAuthorityClient::new(state),
+        committee_store,
+        public_key_bytes,
+        SafeClientMetrics::new_for_tests(),
+    );
+    let request = BatchInfoRequest {
+        start: Some(0),
+        length: 15,
+        headers_only: true,
+    };
+    let items = safe_client
+        .handle_batch_stream(request)
+        .await
+        .unwrap()
+        .collect::<Vec<_>>()
+        .await;
+    // The initial batch and the 5 batches enclosing the 15 transactions.
+    assert_eq!(items.len(), 6);
+    assert!(items
+        .iter()
+        .all(|item| matches!(item, Ok(BatchInfoResponseItem(UpdateItem::Batch(_))))));
+}
+
+#[tokio::test]
+async fn test_batch_request_length_cap() {
+    // Create a random directory to store the DB
+    let dir = env::temp_dir();
+    let path = dir.join(format!("DB_{:?}", ObjectID::random()));
+    fs::create_dir(&path).unwrap();
+
+    // Create an authority
+    let store = Arc::new(AuthorityStore::open(&path, None));
+
+    // Make a test key pair
+    let seed = [1u8; 32];
+    let (committee, _, authority_key) =
+        init_state_parameters_from_rng(&mut StdRng::from_seed(seed));
+    let authority_state = init_state(committee.clone(), authority_key.copy(), store).await;
+
+    // Large requests are clamped to the per-request item limit.
+    let (_, (_, start, end)) = authority_state
+        .handle_batch_info_request(BatchInfoRequest {
+            start: Some(0),
+            length: DEFAULT_MAX_BATCH_STREAM_LENGTH,
+            headers_only: false,
+        })
+        .await
+        .unwrap();
+    assert_eq!(end - start, MAX_ITEMS_LIMIT);
+
+    // Requests beyond the cap are rejected.
+    let result = authority_state
+        .handle_batch_info_request(BatchInfoRequest {
+            start: Some(0),
+            length: DEFAULT_MAX_BATCH_STREAM_LENGTH + 1,
+            headers_only: false,
+        })
+        .await;
+    assert!(matches!(
+        result,
+        Err(SuiError::BatchRequestTooLarge { requested, max })
+            if requested == DEFAULT_MAX_BATCH_STREAM_LENGTH + 1
+                && max == DEFAULT_MAX_BATCH_STREAM_LENGTH
+    ));
+
+    // A cap below the per-request item limit is enforced too.
+    let path = dir.join(format!("DB_{:?}", ObjectID::random()));
+    fs::create_dir(&path).unwrap();
+    let store = Arc::new(AuthorityStore::open(&path, None));
+    let max_batch_stream_length = MAX_ITEMS_LIMIT / 2;
+    let authority_state = init_state_with_config(
+        committee,
+        authority_key,
+        store,
+        AuthorityConfig {
+            max_batch_stream_length,
+            ..Default::default()
+        },
+    )
+    .await;
+    let (_, (_, start, end)) = authority_state
+        .handle_batch_info_request(BatchInfoRequest {
+            start: Some(0),
+            length: max_batch_stream_length,
+            headers_only: false,
+        })
+        .await
+        .unwrap();
+    assert_eq!(end - start, max_batch_stream_length);
+    assert!(matches!(
+        authority_state
+            .handle_batch_info_request(BatchInfoRequest {
+                start: Some(0),
+                length: max_batch_stream_length + 1,
+                headers_only: false,
+            })
+            .await,
+        Err(SuiError::BatchRequestTooLarge { max, .. }) if max == max_batch_stream_length
+    ));
+}
+
+#[tokio::test]
+async fn test_batch_request_range() {
+    // Create a random directory to store the DB
+    let dir = env::temp_dir();
+    let path = dir.join(format!("DB_{:?}", ObjectID::random()));
+    fs::create_dir(&path).unwrap();
+
+    // Create an authority
+    let store = Arc::new(AuthorityStore::open(&path, None));
+
+    // Make a test key pair
+    let seed = [1u8; 32];
+    let (committee, _, authority_key) =
+        init_state_parameters_from_rng(&mut StdRng::from_seed(seed));
+    let authority_state = init_state(committee, authority_key, store.clone()).await;
+
+    // Sequence transactions 0 to 4.
+    let mut transactions = Vec::new();
+    for _ in 0..5 {
+        let ticket = authority_state.batch_notifier.ticket().expect("ok");
+        let digest = ExecutionDigests::random();
+        store.side_sequence(ticket.seq(), &digest);
+        transactions.push((ticket.seq(), digest));
+        ticket.notify();
+    }
+
+    let state = &authority_state;
+    let request = move |start, length| {
+        state.handle_batch_info_request(BatchInfoRequest {
+            start,
+            length,
+            headers_only: false,
+        })
+    };
+    let is_invalid_range = |result: Result<_, SuiError>, earliest, latest| {
+        matches!(
+            result,
+            Err(SuiError::InvalidBatchRange { earliest: e, latest: l, .. })
+                if e == earliest && l == latest
+        )
+    };
+
+    assert!(request(Some(0), 10).await.is_ok());
+    assert!(is_invalid_range(request(Some(0), 0).await, 0, 5));
+    assert!(is_invalid_range(request(None, 0).await, 0, 5));
+
+    // Starting at the next transaction to be sequenced waits for it, but nothing beyond.
+    assert!(request(Some(5), 10).await.is_ok());
+    assert!(is_invalid_range(request(Some(6), 10).await, 0, 5));
+
+    // Once the initial batch is gone, the transactions it preceded cannot be served.
+    let batch = SignedBatch::new(
+        0,
+        AuthorityBatch::make_next(&AuthorityBatch::initial(), &transactions[..3]).unwrap(),
+        &*authority_state.secret,
+        authority_state.name,
+    );
+    store.tables.batches.insert(&3, &batch).unwrap();
+    store.tables.batches.remove(&0).unwrap();
+    assert!(is_invalid_range(request(Some(2), 10).await, 3, 5));
+    assert!(request(Some(3), 10).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_pooled_batch_signer() {
+    let mut rng = StdRng::from_seed([1u8; 32]);
+    let (committee, _, authority_key) = init_state_parameters_from_rng(&mut rng);
+    let name: AuthorityName = authority_key.public().into();
+    let secret: StableSyncAuthoritySigner = Arc::pin(authority_key);
+
+    let inline = BatchSigner::inline(secret.clone(), name);
+    let pooled = BatchSigner::pooled(secret, name, 2);
+    assert!(!inline.is_pooled());
+    assert!(pooled.is_pooled());
+
+    let batch = AuthorityBatch::make_next(
+        &AuthorityBatch::initial(),
+        &[(0, ExecutionDigests::random())],
+    )
+    .unwrap();
+
+    let inline_batch = inline.sign(0, batch.clone()).await.unwrap();
+    let pooled_batch = pooled.sign(0, batch).await.unwrap();
+    assert_eq!(inline_batch.data(), pooled_batch.data());
+    inline_batch.verify(&committee).unwrap();
+    pooled_batch.verify(&committee).unwrap();
+}
+
 #[derive(Clone)]
 struct TrustworthyAuthorityClient(Arc<Mutex<AuthorityState>>);
 
@@ -612,7 +1096,9 @@ impl AuthorityAPI for TrustworthyAuthorityClient {
             for _i in 0..batch_size {
                 let rnd = ExecutionDigests::random();
                 transactions.push((seq, rnd));
-                items.push(BatchInfoResponseItem(UpdateItem::Transaction((seq, rnd))));
+                if !request.headers_only {
+                    items.push(BatchInfoResponseItem(UpdateItem::Transaction((seq, rnd))));
+                }
                 seq += 1;
             }
 
@@ -646,8 +1132,22 @@ impl TrustworthyAuthorityClient {
     }
 }
 
+/// How a `ByzantineAuthorityClient` corrupts the batch streams it serves.
+#[derive(Clone, Copy)]
+enum ByzantineBehaviour {
+    /// Sign batches over different transactions than the ones streamed.
+    InconsistentBatch,
+    /// Stream the first two transactions of each batch in swapped order.
+    OutOfOrderTransactions,
+    /// Sign every batch after the initial one in the next epoch, as if the authority
+    /// reconfigured mid-stream. The batches themselves are consistent.
+    NextEpochBatches,
+    /// Leave out the initial batch, so that the stream starts with a transaction.
+    MissingInitialBatch,
+}
+
 #[derive(Clone)]
-struct ByzantineAuthorityClient(Arc<Mutex<AuthorityState>>);
+struct ByzantineAuthorityClient(Arc<Mutex<AuthorityState>>, ByzantineBehaviour);
 
 #[async_trait]
 impl AuthorityAPI for ByzantineAuthorityClient {
@@ -725,10 +1225,12 @@ impl AuthorityAPI for ByzantineAuthorityClient {
 
         let mut items = Vec::new();
         let mut last_batch = AuthorityBatch::initial();
-        items.push({
-            let item = SignedBatch::new_with_zero_epoch(last_batch.clone(), &*secret, name);
-            BatchInfoResponseItem(UpdateItem::Batch(item))
-        });
+        if !matches!(self.1, ByzantineBehaviour::MissingInitialBatch) {
+            items.push({
+                let item = SignedBatch::new_with_zero_epoch(last_batch.clone(), &*secret, name);
+                BatchInfoResponseItem(UpdateItem::Batch(item))
+            });
+        }
         let mut seq = 0;
         while last_batch.next_sequence_number < request.length {
             let mut transactions = Vec::new();
@@ -740,15 +1242,28 @@ impl AuthorityAPI for ByzantineAuthorityClient {
             }
 
             // Introduce byzantine behaviour:
-            // Pop last transaction
-            let (seq, _) = transactions.pop().unwrap();
-            // Insert a different one
-            transactions.push((seq, ExecutionDigests::random()));
+            match self.1 {
+                ByzantineBehaviour::InconsistentBatch => {
+                    // Pop last transaction
+                    let (seq, _) = transactions.pop().unwrap();
+                    // Insert a different one
+                    transactions.push((seq, ExecutionDigests::random()));
+                }
+                ByzantineBehaviour::OutOfOrderTransactions => {
+                    let len = items.len();
+                    items.swap(len - batch_size, len - batch_size + 1);
+                }
+                ByzantineBehaviour::NextEpochBatches | ByzantineBehaviour::MissingInitialBatch => {}
+            }
+            let epoch = match self.1 {
+                ByzantineBehaviour::NextEpochBatches => 1,
+                _ => 0,
+            };
 
             let new_batch = AuthorityBatch::make_next(&last_batch, &transactions).unwrap();
             last_batch = new_batch;
             items.push({
-                let item = SignedBatch::new_with_zero_epoch(last_batch.clone(), &*secret, name);
+                let item = SignedBatch::new(epoch, last_batch.clone(), &*secret, name);
                 BatchInfoResponseItem(UpdateItem::Batch(item))
             });
         }
@@ -771,7 +1286,11 @@ impl AuthorityAPI for ByzantineAuthorityClient {
 
 impl ByzantineAuthorityClient {
     fn new(state: AuthorityState) -> Self {
-        Self(Arc::new(Mutex::new(state)))
+        Self::with_behaviour(state, ByzantineBehaviour::InconsistentBatch)
+    }
+
+    fn with_behaviour(state: AuthorityState, behaviour: ByzantineBehaviour) -> Self {
+        Self(Arc::new(Mutex::new(state)), behaviour)
     }
 }
 
@@ -806,6 +1325,7 @@ async fn test_safe_batch_stream() {
     let request = BatchInfoRequest {
         start: Some(0),
         length: 15,
+        headers_only: false,
     };
     let batch_stream = safe_client.handle_batch_stream(request.clone()).await;
 
@@ -871,6 +1391,7 @@ async fn test_safe_batch_stream() {
     let request_b = BatchInfoRequest {
         start: Some(0),
         length: 10,
+        headers_only: false,
     };
     batch_stream = safe_client_from_byzantine
         .handle_batch_stream(request_b.clone())
@@ -892,3 +1413,293 @@ async fn test_safe_batch_stream() {
     }
     assert!(error_found);
 }
+
+#[tokio::test]
+async fn test_safe_batch_stream_out_of_order() {
+    let (_, authority_key): (_, AuthorityKeyPair) = get_key_pair();
+    let public_key_bytes: AuthorityPublicKeyBytes = authority_key.public().into();
+    let committee = Committee::new(0, BTreeMap::from([(public_key_bytes, 1)])).unwrap();
+    let (tx_reconfigure_consensus, _rx_reconfigure_consensus) = tokio::sync::mpsc::channel(10);
+    let state = AuthorityState::new_for_testing(
+        committee,
+        &authority_key,
+        None,
+        None,
+        None,
+        tx_reconfigure_consensus,
+    )
+    .await;
+    let committee_store = state.committee_store().clone();
+    let safe_client = SafeClient::new(
+        ByzantineAuthorityClient::with_behaviour(state, ByzantineBehaviour::OutOfOrderTransactions),
+        committee_store,
+        public_key_bytes,
+        SafeClientMetrics::new_for_tests(),
+    );
+
+    let request = BatchInfoRequest {
+        start: Some(0),
+        length: 3,
+        headers_only: false,
+    };
+    let items = safe_client
+        .handle_batch_stream(request)
+        .await
+        .unwrap()
+        .collect::<Vec<Result<BatchInfoResponseItem, SuiError>>>()
+        .await;
+
+    // The initial batch and the first transaction are fine; the second transaction
+    // goes back in sequence.
+    assert!(items[0].is_ok());
+    assert!(matches!(
+        items[1],
+        Ok(BatchInfoResponseItem(UpdateItem::Transaction((1, _))))
+    ));
+    assert!(matches!(
+        items[2],
+        Err(SuiError::BatchStreamOutOfOrder {
+            previous: 1,
+            received: 0,
+            ..
+        })
+    ));
+}
+
+#[tokio::test]
+async fn test_safe_batch_stream_next_epoch() {
+    let (_, authority_key): (_, AuthorityKeyPair) = get_key_pair();
+    let public_key_bytes: AuthorityPublicKeyBytes = authority_key.public().into();
+    let authorities = BTreeMap::from([(public_key_bytes, 1)]);
+    let committee = Committee::new(0, authorities.clone()).unwrap();
+    let (tx_reconfigure_consensus, _rx_reconfigure_consensus) = tokio::sync::mpsc::channel(10);
+    let state = AuthorityState::new_for_testing(
+        committee,
+        &authority_key,
+        None,
+        None,
+        None,
+        tx_reconfigure_consensus,
+    )
+    .await;
+    let committee_store = state.committee_store().clone();
+    let safe_client = SafeClient::new(
+        ByzantineAuthorityClient::with_behaviour(state, ByzantineBehaviour::NextEpochBatches),
+        committee_store.clone(),
+        public_key_bytes,
+        SafeClientMetrics::new_for_tests(),
+    );
+
+    let request = BatchInfoRequest {
+        start: Some(0),
+        length: 3,
+        headers_only: false,
+    };
+
+    // The client does not know the committee of epoch 1 yet.
+    let items = safe_client
+        .handle_batch_stream(request.clone())
+        .await
+        .unwrap()
+        .collect::<Vec<Result<BatchInfoResponseItem, SuiError>>>()
+        .await;
+    assert_eq!(items.len(), 3 + 2);
+    assert!(items[..4].iter().all(|item| item.is_ok()));
+    assert!(matches!(
+        items[4],
+        Err(SuiError::MissingCommitteeAtEpoch(1))
+    ));
+
+    // Once it does, the batch signed in the new epoch verifies.
+    committee_store
+        .insert_new_committee(&Committee::new(1, authorities).unwrap())
+        .unwrap();
+    let items = safe_client
+        .handle_batch_stream(request)
+        .await
+        .unwrap()
+        .collect::<Vec<Result<BatchInfoResponseItem, SuiError>>>()
+        .await;
+    assert_eq!(items.len(), 3 + 2);
+    assert!(items.iter().all(|item| item.is_ok()));
+}
+
+#[tokio::test]
+async fn test_safe_batch_stream_missing_initial_batch() {
+    let (_, authority_key): (_, AuthorityKeyPair) = get_key_pair();
+    let public_key_bytes: AuthorityPublicKeyBytes = authority_key.public().into();
+    let committee = Committee::new(0, BTreeMap::from([(public_key_bytes, 1)])).unwrap();
+    let (tx_reconfigure_consensus, _rx_reconfigure_consensus) = tokio::sync::mpsc::channel(10);
+    let state = AuthorityState::new_for_testing(
+        committee,
+        &authority_key,
+        None,
+        None,
+        None,
+        tx_reconfigure_consensus,
+    )
+    .await;
+    let committee_store = state.committee_store().clone();
+    let safe_client = SafeClient::new(
+        ByzantineAuthorityClient::with_behaviour(state, ByzantineBehaviour::MissingInitialBatch),
+        committee_store,
+        public_key_bytes,
+        SafeClientMetrics::new_for_tests(),
+    );
+
+    let request = BatchInfoRequest {
+        start: Some(0),
+        length: 3,
+        headers_only: false,
+    };
+    let items = safe_client
+        .handle_batch_stream(request)
+        .await
+        .unwrap()
+        .collect::<Vec<Result<BatchInfoResponseItem, SuiError>>>()
+        .await;
+
+    assert!(matches!(
+        items[0],
+        Err(SuiError::MissingInitialBatch { authority }) if authority == public_key_bytes
+    ));
+    // Nor is the first batch after the transactions accepted in its place.
+    assert!(matches!(
+        items.last().unwrap(),
+        Err(SuiError::MissingInitialBatch { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_verify_batch_stream() {
+    let (_, authority_key): (_, AuthorityKeyPair) = get_key_pair();
+    let public_key_bytes: AuthorityPublicKeyBytes = authority_key.public().into();
+    let committee = Committee::new(0, BTreeMap::from([(public_key_bytes, 1)])).unwrap();
+    let (tx_reconfigure_consensus, _rx_reconfigure_consensus) = tokio::sync::mpsc::channel(10);
+    let state = AuthorityState::new_for_testing(
+        committee.clone(),
+        &authority_key,
+        None,
+        None,
+        None,
+        tx_reconfigure_consensus,
+    )
+    .await;
+    let committee_store = state.committee_store().clone();
+    let safe_client = SafeClient::new(
+        TrustworthyAuthorityClient::new(state),
+        committee_store,
+        public_key_bytes,
+        SafeClientMetrics::new_for_tests(),
+    );
+
+    let request = BatchInfoRequest {
+        start: Some(0),
+        length: 15,
+        headers_only: false,
+    };
+    let summary = safe_client.verify_batch_stream(request).await.unwrap();
+    // 15 transactions, in 5 batches following the initial one.
+    assert_eq!(
+        summary,
+        BatchStreamSummary {
+            total_transactions: 15,
+            total_batches: 6,
+            first_sequence: Some(0),
+            last_sequence: Some(14),
+        }
+    );
+
+    // The same authority turning byzantine, signing batches over other transactions, is caught.
+    let (tx_reconfigure_consensus, _rx_reconfigure_consensus) = tokio::sync::mpsc::channel(10);
+    let state = AuthorityState::new_for_testing(
+        committee,
+        &authority_key,
+        None,
+        None,
+        None,
+        tx_reconfigure_consensus,
+    )
+    .await;
+    let committee_store = state.committee_store().clone();
+    let safe_client = SafeClient::new(
+        ByzantineAuthorityClient::new(state),
+        committee_store,
+        public_key_bytes,
+        SafeClientMetrics::new_for_tests(),
+    );
+
+    let request = BatchInfoRequest {
+        start: Some(0),
+        length: 10,
+        headers_only: false,
+    };
+    assert!(matches!(
+        safe_client.verify_batch_stream(request).await,
+        Err(SuiError::ByzantineAuthoritySuspicion { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_safe_client_committee_cache() {
+    let (_, authority_key): (_, AuthorityKeyPair) = get_key_pair();
+    let public_key_bytes: AuthorityPublicKeyBytes = authority_key.public().into();
+    let authorities = BTreeMap::from([(public_key_bytes, 1)]);
+    let committee = Committee::new(0, authorities.clone()).unwrap();
+    let (tx_reconfigure_consensus, _rx_reconfigure_consensus) = tokio::sync::mpsc::channel(10);
+    let state = AuthorityState::new_for_testing(
+        committee.clone(),
+        &authority_key,
+        None,
+        None,
+        None,
+        tx_reconfigure_consensus,
+    )
+    .await;
+    let committee_store = state.committee_store().clone();
+    let next_committee = Committee::new(1, authorities).unwrap();
+    committee_store
+        .insert_new_committee(&next_committee)
+        .unwrap();
+    let safe_client = SafeClient::new(
+        ByzantineAuthorityClient::with_behaviour(state, ByzantineBehaviour::NextEpochBatches),
+        committee_store,
+        public_key_bytes,
+        SafeClientMetrics::new_for_tests(),
+    );
+
+    // An empty cache is filled from the committee store.
+    let mut cached = None;
+    assert_eq!(
+        safe_client.get_cached_committee(&mut cached, 0).unwrap(),
+        &committee
+    );
+    assert_eq!(cached.as_ref(), Some(&committee));
+
+    // A cached committee of the same epoch is used as is, without going to the store.
+    let (_, other_key): (_, AuthorityKeyPair) = get_key_pair();
+    let other_committee = Committee::new(
+        0,
+        BTreeMap::from([(AuthorityPublicKeyBytes::from(other_key.public()), 1)]),
+    )
+    .unwrap();
+    let mut cached = Some(other_committee.clone());
+    assert_eq!(
+        safe_client.get_cached_committee(&mut cached, 0).unwrap(),
+        &other_committee
+    );
+
+    // A batch from another epoch replaces the cached committee.
+    assert_eq!(
+        safe_client.get_cached_committee(&mut cached, 1).unwrap(),
+        &next_committee
+    );
+    assert_eq!(cached.as_ref(), Some(&next_committee));
+
+    // An unknown epoch leaves the cache alone.
+    assert!(matches!(
+        safe_client.get_cached_committee(&mut cached, 2),
+        Err(SuiError::MissingCommitteeAtEpoch(2))
+    ));
+    assert_eq!(cached.as_ref(), Some(&next_committee));
+}
@@ -244,3 +244,62 @@ async fn test_batch_insufficient_gas_balance() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_empty_batch_transaction() -> anyhow::Result<()> {
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let gas_object_id = ObjectID::random();
+    let authority_state = init_state_with_ids([(sender, gas_object_id)]).await;
+    let data = TransactionData::new(
+        TransactionKind::Batch(vec![]),
+        sender,
+        authority_state
+            .get_object(&gas_object_id)
+            .await?
+            .unwrap()
+            .compute_object_reference(),
+        100000,
+    );
+    let signature = Signature::new(&data, &sender_key);
+    let tx = Transaction::new(data, signature);
+    let response = send_and_confirm_transaction(&authority_state, tx).await;
+    assert!(matches!(
+        response.unwrap_err(),
+        SuiError::EmptyTransactionKind
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_batch_validity_check() {
+    let random_object_ref = || (ObjectID::random(), SequenceNumber::new(), ObjectDigest::MIN);
+    let transfer = SingleTransactionKind::TransferObject(TransferObject {
+        recipient: SuiAddress::random_for_testing_only(),
+        object_ref: random_object_ref(),
+    });
+    assert!(TransactionKind::Batch(vec![transfer.clone(), transfer])
+        .validity_check()
+        .is_ok());
+
+    // Paying nobody has no effect, in a batch or on its own.
+    let pay = SingleTransactionKind::Pay(Pay {
+        coins: vec![random_object_ref()],
+        recipients: vec![],
+        amounts: vec![],
+    });
+    assert!(matches!(
+        TransactionKind::Batch(vec![pay.clone()]).validity_check(),
+        Err(SuiError::EmptyTransactionKind)
+    ));
+    assert!(matches!(
+        TransactionKind::Single(pay).validity_check(),
+        Err(SuiError::EmptyTransactionKind)
+    ));
+    assert!(matches!(
+        TransactionKind::Single(SingleTransactionKind::Publish(MoveModulePublish {
+            modules: vec![]
+        }))
+        .validity_check(),
+        Err(SuiError::EmptyTransactionKind)
+    ));
+}
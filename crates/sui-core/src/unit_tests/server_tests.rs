@@ -143,6 +143,7 @@ async fn test_subscription() {
     let req = BatchInfoRequest {
         start: Some(12),
         length: 22,
+        headers_only: false,
     };
     tokio::time::sleep(Duration::from_millis(10)).await;
 
@@ -195,6 +196,7 @@ async fn test_subscription() {
     let req = BatchInfoRequest {
         start: Some(101),
         length: 11,
+        headers_only: false,
     };
 
     tokio::time::sleep(Duration::from_millis(10)).await;
@@ -239,6 +241,7 @@ async fn test_subscription() {
     let req = BatchInfoRequest {
         start: None,
         length: 10,
+        headers_only: false,
     };
 
     // Use 17 since it is prime and unlikely to collide with the exact timing
@@ -349,6 +352,7 @@ async fn test_subscription_safe_client() {
     let req = BatchInfoRequest {
         start: Some(12),
         length: 22,
+        headers_only: false,
     };
 
     let mut stream1 = _master_safe_client
@@ -409,6 +413,7 @@ async fn test_subscription_safe_client() {
     let req = BatchInfoRequest {
         start: Some(101),
         length: 11,
+        headers_only: false,
     };
 
     let mut stream1 = _master_safe_client
@@ -448,6 +453,7 @@ async fn test_subscription_safe_client() {
     let req = BatchInfoRequest {
         start: None,
         length: 10,
+        headers_only: false,
     };
 
     let mut stream1 = _master_safe_client
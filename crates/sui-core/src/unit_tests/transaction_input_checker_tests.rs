@@ -0,0 +1,602 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::authority::authority_tests::{init_state_with_ids, init_transfer_transaction};
+use fastcrypto::traits::VerifyingKey;
+use sui_types::base_types::{dbg_addr, ObjectDigest, ObjectID};
+use sui_types::crypto::{
+    get_key_pair, AccountKeyPair, AggregateAuthoritySignature, AuthorityPublicKey,
+    FastCryptoVerifier, Signature,
+};
+use sui_types::messages::{MoveModulePublish, Transaction, TransferObject, TransferSui};
+
+#[tokio::test]
+async fn test_offline_input_check_matches_store() {
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let object_id = ObjectID::random();
+    let gas_object_id = ObjectID::random();
+    let authority_state =
+        init_state_with_ids(vec![(sender, object_id), (sender, gas_object_id)]).await;
+    let store = authority_state.db();
+
+    let object = store.get_object(&object_id).unwrap().unwrap();
+    let gas_object = store.get_object(&gas_object_id).unwrap().unwrap();
+    let gas_params = EpochGasParams::from(&store.get_sui_system_state_object().unwrap());
+
+    let transaction = init_transfer_transaction(
+        sender,
+        &sender_key,
+        dbg_addr(2),
+        object.compute_object_reference(),
+        gas_object.compute_object_reference(),
+    );
+
+    // Both paths accept a well-formed transaction.
+    check_transaction_input(&store, &transaction, None, DEFAULT_MAX_OBJECT_ERRORS)
+        .await
+        .unwrap();
+    check_transaction_input_offline(
+        &transaction,
+        &[object.clone(), gas_object.clone()],
+        &gas_params,
+    )
+    .unwrap();
+
+    // And both reject it when an input object cannot be found.
+    let missing_object = init_transfer_transaction(
+        sender,
+        &sender_key,
+        dbg_addr(2),
+        (
+            ObjectID::random(),
+            SequenceNumber::new(),
+            ObjectDigest::new([0; 32]),
+        ),
+        gas_object.compute_object_reference(),
+    );
+    let store_err =
+        check_transaction_input(&store, &missing_object, None, DEFAULT_MAX_OBJECT_ERRORS)
+            .await
+            .unwrap_err();
+    let offline_err =
+        check_transaction_input_offline(&missing_object, &[object, gas_object], &gas_params)
+            .unwrap_err();
+    assert_eq!(store_err, offline_err);
+}
+
+#[tokio::test]
+async fn test_gas_object_used_as_input() {
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let object_id = ObjectID::random();
+    let authority_state = init_state_with_ids(vec![(sender, object_id)]).await;
+    let store = authority_state.db();
+    let object = store.get_object(&object_id).unwrap().unwrap();
+    let gas_params = EpochGasParams::from(&store.get_sui_system_state_object().unwrap());
+
+    // Transfer the coin that also pays for gas.
+    let transaction = init_transfer_transaction(
+        sender,
+        &sender_key,
+        dbg_addr(2),
+        object.compute_object_reference(),
+        object.compute_object_reference(),
+    );
+
+    let expected = SuiError::GasObjectUsedAsInput { object_id };
+    assert_eq!(
+        check_transaction_input(&store, &transaction, None, DEFAULT_MAX_OBJECT_ERRORS)
+            .await
+            .unwrap_err(),
+        expected
+    );
+    assert_eq!(
+        check_transaction_input_offline(&transaction, &[object], &gas_params).unwrap_err(),
+        expected
+    );
+}
+
+#[tokio::test]
+async fn test_signature_cache_skips_reverification() {
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let object_id = ObjectID::random();
+    let gas_object_id = ObjectID::random();
+    let authority_state =
+        init_state_with_ids(vec![(sender, object_id), (sender, gas_object_id)]).await;
+    let store = authority_state.db();
+    let object = store.get_object(&object_id).unwrap().unwrap();
+    let gas_object = store.get_object(&gas_object_id).unwrap().unwrap();
+
+    let transaction = init_transfer_transaction(
+        sender,
+        &sender_key,
+        dbg_addr(2),
+        object.compute_object_reference(),
+        gas_object.compute_object_reference(),
+    );
+    let cache = VerifiedSignatureCache::new(16);
+
+    // The first submission is verified, the retry is not. Object checks run both times.
+    for _ in 0..2 {
+        check_sender_signature(&transaction, 0, Some(&cache), None)
+            .await
+            .unwrap();
+        check_transaction_input(&store, &transaction, None, DEFAULT_MAX_OBJECT_ERRORS)
+            .await
+            .unwrap();
+    }
+    assert_eq!(cache.verifications(), 1);
+
+    // A new epoch invalidates the cache.
+    check_sender_signature(&transaction, 1, Some(&cache), None)
+        .await
+        .unwrap();
+    assert_eq!(cache.verifications(), 2);
+
+    // A bad signature is never cached.
+    let (_, other_key): (_, AccountKeyPair) = get_key_pair();
+    let forged = Transaction::new(
+        transaction.signed_data.data.clone(),
+        Signature::new(&transaction.signed_data.data, &other_key),
+    );
+    for _ in 0..2 {
+        assert!(check_sender_signature(&forged, 1, Some(&cache), None)
+            .await
+            .is_err());
+    }
+    assert_eq!(cache.verifications(), 4);
+}
+
+#[tokio::test]
+async fn test_signature_cache_invalidate_epoch() {
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let transaction = init_transfer_transaction(
+        sender,
+        &sender_key,
+        dbg_addr(2),
+        (ObjectID::random(), SequenceNumber::new(), ObjectDigest::MIN),
+        (ObjectID::random(), SequenceNumber::new(), ObjectDigest::MIN),
+    );
+    let cache = VerifiedSignatureCache::new(16);
+    let verifier = SignatureVerificationPool::from(&SignatureVerificationPoolConfig::default());
+
+    check_sender_signature(&transaction, 0, Some(&cache), Some(&verifier))
+        .await
+        .unwrap();
+    check_sender_signature(&transaction, 0, Some(&cache), Some(&verifier))
+        .await
+        .unwrap();
+    assert_eq!(cache.verifications(), 1);
+
+    // Once the epoch is over, its entries no longer count as verified.
+    cache.invalidate_epoch(0);
+    check_sender_signature(&transaction, 1, Some(&cache), Some(&verifier))
+        .await
+        .unwrap();
+    assert_eq!(cache.verifications(), 2);
+    check_sender_signature(&transaction, 1, Some(&cache), Some(&verifier))
+        .await
+        .unwrap();
+    assert_eq!(cache.verifications(), 2);
+
+    // Invalidating an older epoch leaves the current entries alone.
+    cache.invalidate_epoch(0);
+    check_sender_signature(&transaction, 1, Some(&cache), Some(&verifier))
+        .await
+        .unwrap();
+    assert_eq!(cache.verifications(), 2);
+
+    // So does a late check for the old epoch, which is never served from the cache.
+    check_sender_signature(&transaction, 0, Some(&cache), Some(&verifier))
+        .await
+        .unwrap();
+    check_sender_signature(&transaction, 0, Some(&cache), Some(&verifier))
+        .await
+        .unwrap();
+    assert_eq!(cache.verifications(), 4);
+    check_sender_signature(&transaction, 1, Some(&cache), Some(&verifier))
+        .await
+        .unwrap();
+    assert_eq!(cache.verifications(), 4);
+}
+
+#[test]
+fn test_transfer_sui_gas_check_with_and_without_amount() {
+    let budget = *gas::MIN_GAS_BUDGET;
+    let remainder = 1000;
+    let gas_object =
+        Object::with_id_owner_gas_for_testing(ObjectID::random(), dbg_addr(1), budget + remainder);
+    let transfer = |amount| {
+        TransactionKind::Single(SingleTransactionKind::TransferSui(TransferSui {
+            recipient: dbg_addr(2),
+            amount,
+        }))
+    };
+    let gas_params = EpochGasParams {
+        storage_gas_price: 1,
+        consensus_surcharge: 0,
+        kind_gas_multipliers: KindGasMultipliers::default(),
+    };
+    let check = |amount| check_gas_object(&gas_object, budget, 1, &gas_params, &transfer(amount));
+
+    // Transferring everything only reserves the budget; the remainder is what gets transferred.
+    check(None).unwrap();
+    // An explicit amount has to fit next to the budget.
+    check(Some(remainder)).unwrap();
+    assert!(matches!(
+        check(Some(remainder + 1)),
+        Err(SuiError::InsufficientGas { .. })
+    ));
+
+    // Even when transferring everything, the budget itself must be covered.
+    let small_gas_object =
+        Object::with_id_owner_gas_for_testing(ObjectID::random(), dbg_addr(1), budget - 1);
+    assert!(matches!(
+        check_gas_object(&small_gas_object, budget, 1, &gas_params, &transfer(None)),
+        Err(SuiError::InsufficientGas { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_epoch_gas_params() {
+    let gas_params = EpochGasParams {
+        storage_gas_price: 5,
+        consensus_surcharge: *gas::CONSENSUS_SURCHARGE,
+        kind_gas_multipliers: KindGasMultipliers::default(),
+    };
+    // The coin has to cover whichever of the computation and storage prices is higher.
+    assert_eq!(gas_params.effective_gas_price(2), 5);
+    assert_eq!(gas_params.effective_gas_price(8), 8);
+
+    // The gas coin check charges the budget at the effective price.
+    let budget = *gas::MIN_GAS_BUDGET;
+    let transfer = TransactionKind::Single(SingleTransactionKind::TransferSui(TransferSui {
+        recipient: dbg_addr(2),
+        amount: None,
+    }));
+    let check = |balance, computation_gas_price| {
+        let gas_object =
+            Object::with_id_owner_gas_for_testing(ObjectID::random(), dbg_addr(1), balance);
+        check_gas_object(
+            &gas_object,
+            budget,
+            computation_gas_price,
+            &gas_params,
+            &transfer,
+        )
+    };
+    check(budget * 5, 2).unwrap();
+    assert!(matches!(
+        check(budget * 5 - 1, 2),
+        Err(SuiError::InsufficientGas { .. })
+    ));
+    assert!(matches!(
+        check(budget * 5, 8),
+        Err(SuiError::InsufficientGas { .. })
+    ));
+    check(budget * 8, 8).unwrap();
+
+    // Loading from the system state picks up the epoch's storage price.
+    let authority_state = init_state_with_ids(vec![]).await;
+    let system_state = authority_state.db().get_sui_system_state_object().unwrap();
+    let gas_params = EpochGasParams::from(&system_state);
+    assert_eq!(
+        gas_params.storage_gas_price,
+        system_state.parameters.storage_gas_price
+    );
+    assert_eq!(gas_params.consensus_surcharge, *gas::CONSENSUS_SURCHARGE);
+}
+
+#[tokio::test]
+async fn test_gas_object_with_wrong_digest() {
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let object_id = ObjectID::random();
+    let gas_object_id = ObjectID::random();
+    let authority_state =
+        init_state_with_ids(vec![(sender, object_id), (sender, gas_object_id)]).await;
+    let store = authority_state.db();
+    let object = store.get_object(&object_id).unwrap().unwrap();
+    let gas_object = store.get_object(&gas_object_id).unwrap().unwrap();
+
+    // Right object and version, stale digest.
+    let (_, version, _) = gas_object.compute_object_reference();
+    let transaction = init_transfer_transaction(
+        sender,
+        &sender_key,
+        dbg_addr(2),
+        object.compute_object_reference(),
+        (gas_object_id, version, ObjectDigest::MIN),
+    );
+
+    assert_eq!(
+        check_transaction_input(&store, &transaction, None, DEFAULT_MAX_OBJECT_ERRORS)
+            .await
+            .unwrap_err(),
+        SuiError::ObjectErrors {
+            errors: vec![SuiError::InvalidObjectDigest {
+                object_id: gas_object_id,
+                expected_digest: gas_object.digest(),
+            }],
+        }
+    );
+}
+
+/// Takes `delay` for every signature, as a pathological input might.
+struct SlowVerifier {
+    delay: Duration,
+}
+
+impl SignatureVerifier for SlowVerifier {
+    fn verify<P: VerifyingKey>(&self, _: &P, _: &[u8], _: &P::Sig) -> SuiResult<()> {
+        std::thread::sleep(self.delay);
+        Ok(())
+    }
+
+    fn verify_aggregates(
+        &self,
+        _: &[&AggregateAuthoritySignature],
+        _: Vec<std::slice::Iter<'_, AuthorityPublicKey>>,
+        _: &[&[u8]],
+    ) -> SuiResult<()> {
+        std::thread::sleep(self.delay);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_signature_verification_pool() {
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let data = TransactionData::new_transfer_sui(
+        dbg_addr(2),
+        sender,
+        None,
+        (ObjectID::random(), SequenceNumber::new(), ObjectDigest::MIN),
+        1000,
+    );
+    let signature = Signature::new(&data, &sender_key);
+
+    let budget = Duration::from_millis(200);
+    let pool = SignatureVerificationPool::new(1, budget);
+    pool.verify(
+        signature.clone(),
+        data.clone(),
+        sender,
+        Arc::new(FastCryptoVerifier),
+    )
+    .await
+    .unwrap();
+
+    // A slow verification times out, but keeps the only permit until it really finishes.
+    let slow = Arc::new(SlowVerifier {
+        delay: Duration::from_millis(500),
+    });
+    assert_eq!(
+        pool.verify(signature.clone(), data.clone(), sender, slow)
+            .await,
+        Err(SuiError::VerificationTimeout { budget })
+    );
+    assert_eq!(pool.permits.available_permits(), 0);
+
+    // A fast verification queued behind it waits for the permit, which does not count
+    // against its budget.
+    pool.verify(
+        signature.clone(),
+        data.clone(),
+        sender,
+        Arc::new(FastCryptoVerifier),
+    )
+    .await
+    .unwrap();
+    assert_eq!(pool.permits.available_permits(), 1);
+
+    pool.verify(signature, data, sender, Arc::new(FastCryptoVerifier))
+        .await
+        .unwrap();
+}
+
+#[test]
+fn test_kind_gas_multiplier() {
+    let budget = *gas::MIN_GAS_BUDGET;
+    let gas_object =
+        Object::with_id_owner_gas_for_testing(ObjectID::random(), dbg_addr(1), 10 * budget);
+    let publish = TransactionKind::Single(SingleTransactionKind::Publish(MoveModulePublish {
+        modules: vec![],
+    }));
+    let transfer = TransactionKind::Single(SingleTransactionKind::TransferSui(TransferSui {
+        recipient: dbg_addr(2),
+        amount: None,
+    }));
+    let default_params = EpochGasParams {
+        storage_gas_price: 1,
+        consensus_surcharge: 0,
+        kind_gas_multipliers: KindGasMultipliers::default(),
+    };
+    let publish_params = EpochGasParams {
+        kind_gas_multipliers: KindGasMultipliers {
+            publish: 2,
+            ..KindGasMultipliers::default()
+        },
+        ..default_params
+    };
+    assert_eq!(default_params.kind_gas_multiplier(&publish), 1);
+    assert_eq!(publish_params.kind_gas_multiplier(&publish), 2);
+    assert_eq!(publish_params.kind_gas_multiplier(&transfer), 1);
+
+    // The minimum budget is enough for a publish by default...
+    check_gas_object(&gas_object, budget, 1, &default_params, &publish).unwrap();
+    // ...but not once publishing is twice as expensive.
+    assert!(matches!(
+        check_gas_object(&gas_object, budget, 1, &publish_params, &publish),
+        Err(SuiError::InsufficientGas { .. })
+    ));
+    check_gas_object(&gas_object, 2 * budget, 1, &publish_params, &publish).unwrap();
+    // Other kinds are unaffected.
+    check_gas_object(&gas_object, budget, 1, &publish_params, &transfer).unwrap();
+}
+
+#[tokio::test]
+async fn test_inconsistent_object_versions_in_batch() {
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let object_id = ObjectID::random();
+    let gas_object_id = ObjectID::random();
+    let authority_state =
+        init_state_with_ids(vec![(sender, object_id), (sender, gas_object_id)]).await;
+    let store = authority_state.db();
+    let object = store.get_object(&object_id).unwrap().unwrap();
+    let gas_object = store.get_object(&gas_object_id).unwrap().unwrap();
+
+    let (_, version, digest) = object.compute_object_reference();
+    let transfer = |version| {
+        SingleTransactionKind::TransferObject(TransferObject {
+            recipient: dbg_addr(2),
+            object_ref: (object_id, version, digest),
+        })
+    };
+    let data = TransactionData::new(
+        TransactionKind::Batch(vec![transfer(version), transfer(version.increment())]),
+        sender,
+        gas_object.compute_object_reference(),
+        10000,
+    );
+    let signature = Signature::new(&data, &sender_key);
+    let transaction = Transaction::new(data, signature);
+
+    let expected = SuiError::InconsistentObjectVersion { object_id };
+    assert_eq!(
+        check_transaction_input(&store, &transaction, None, DEFAULT_MAX_OBJECT_ERRORS)
+            .await
+            .unwrap_err(),
+        expected
+    );
+    let gas_params = EpochGasParams::from(&store.get_sui_system_state_object().unwrap());
+    assert_eq!(
+        check_transaction_input_offline(&transaction, &[object, gas_object], &gas_params)
+            .unwrap_err(),
+        expected
+    );
+}
+
+#[tokio::test]
+async fn test_gas_params_cache() {
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let object_id = ObjectID::random();
+    let gas_object_id = ObjectID::random();
+    let authority_state =
+        init_state_with_ids(vec![(sender, object_id), (sender, gas_object_id)]).await;
+    let store = authority_state.db();
+    let object = store.get_object(&object_id).unwrap().unwrap();
+    let gas_object = store.get_object(&gas_object_id).unwrap().unwrap();
+
+    let transaction = init_transfer_transaction(
+        sender,
+        &sender_key,
+        dbg_addr(2),
+        object.compute_object_reference(),
+        gas_object.compute_object_reference(),
+    );
+    let metrics = AuthorityMetrics::new(&prometheus::Registry::new());
+    let cache = EpochGasParamsCache::new(KindGasMultipliers::default(), &metrics);
+
+    // Only the first check in an epoch reads the system state.
+    for _ in 0..2 {
+        check_transaction_input(
+            &store,
+            &transaction,
+            Some((0, &cache)),
+            DEFAULT_MAX_OBJECT_ERRORS,
+        )
+        .await
+        .unwrap();
+    }
+    assert_eq!(metrics.gas_price_cache_misses.get(), 1);
+    assert_eq!(metrics.gas_price_cache_hits.get(), 1);
+
+    // A new epoch reads it again.
+    check_transaction_input(
+        &store,
+        &transaction,
+        Some((1, &cache)),
+        DEFAULT_MAX_OBJECT_ERRORS,
+    )
+    .await
+    .unwrap();
+    assert_eq!(metrics.gas_price_cache_misses.get(), 2);
+    assert_eq!(metrics.gas_price_cache_hits.get(), 1);
+
+    // The configured multipliers apply to the parameters loaded from the system state.
+    let cache = EpochGasParamsCache::new(
+        KindGasMultipliers {
+            transfer: u64::MAX,
+            ..KindGasMultipliers::default()
+        },
+        &metrics,
+    );
+    assert!(matches!(
+        check_transaction_input(
+            &store,
+            &transaction,
+            Some((0, &cache)),
+            DEFAULT_MAX_OBJECT_ERRORS
+        )
+        .await,
+        Err(SuiError::InsufficientGas { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_object_errors_are_capped() {
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let gas_object_id = ObjectID::random();
+    let authority_state = init_state_with_ids(vec![(sender, gas_object_id)]).await;
+    let store = authority_state.db();
+    let gas_object = store.get_object(&gas_object_id).unwrap().unwrap();
+
+    let missing_coins: Vec<_> = (0..50)
+        .map(|_| (ObjectID::random(), SequenceNumber::new(), ObjectDigest::MIN))
+        .collect();
+    let data = TransactionData::new_pay(
+        sender,
+        missing_coins,
+        vec![dbg_addr(2)],
+        vec![1],
+        gas_object.compute_object_reference(),
+        10000,
+    );
+    let signature = Signature::new(&data, &sender_key);
+    let transaction = Transaction::new(data, signature);
+
+    let err = check_transaction_input(&store, &transaction, None, DEFAULT_MAX_OBJECT_ERRORS)
+        .await
+        .unwrap_err();
+    let errors = match err {
+        SuiError::ObjectErrors { errors } => errors,
+        err => panic!("unexpected error {err}"),
+    };
+    assert_eq!(errors.len(), DEFAULT_MAX_OBJECT_ERRORS + 1);
+    assert!(errors[..DEFAULT_MAX_OBJECT_ERRORS]
+        .iter()
+        .all(|e| matches!(e, SuiError::ObjectNotFound { .. })));
+    assert_eq!(
+        errors.last(),
+        Some(&SuiError::TooManyObjectErrors { total: 50 })
+    );
+
+    // The limit is configurable.
+    let err = check_transaction_input(&store, &transaction, None, 3)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        SuiError::ObjectErrors { errors } if errors.len() == 4
+            && errors[3] == SuiError::TooManyObjectErrors { total: 50 }
+    ));
+
+    // Errors within the limit are left alone.
+    let few = SuiError::ObjectErrors {
+        errors: vec![SuiError::TransferUnownedError; DEFAULT_MAX_OBJECT_ERRORS],
+    };
+    assert_eq!(
+        limit_object_errors(few.clone(), DEFAULT_MAX_OBJECT_ERRORS),
+        few
+    );
+}
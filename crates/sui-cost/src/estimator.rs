@@ -141,8 +141,13 @@ pub async fn estimate_transaction_computation_cost(
     let dummy_sig = SuiSignature::new(&tx_data, &keypair);
     let tx = Transaction::new(tx_data, dummy_sig);
 
-    let (_gas_status, input_objects) =
-        transaction_input_checker::check_transaction_input(&state.db(), &tx).await?;
+    let (_gas_status, input_objects) = transaction_input_checker::check_transaction_input(
+        &state.db(),
+        &tx,
+        None,
+        transaction_input_checker::DEFAULT_MAX_OBJECT_ERRORS,
+    )
+    .await?;
     let in_mem_temporary_store =
         TemporaryStore::new(state.db(), input_objects, TransactionDigest::random());
 
@@ -83,6 +83,17 @@ fn main() -> Result<()> {
                 .codec_path(codec_path)
                 .build(),
         )
+        .method(
+            Method::builder()
+                .name("batch_info_with_ack")
+                .route_name("FollowTxStreamWithAck")
+                .input_type("sui_types::messages::BatchInfoStreamMessage")
+                .output_type("sui_types::messages::BatchInfoResponseItem")
+                .client_streaming()
+                .server_streaming()
+                .codec_path(codec_path)
+                .build(),
+        )
         .method(
             Method::builder()
                 .name("committee_info")
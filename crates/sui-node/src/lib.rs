@@ -15,6 +15,7 @@ use sui_config::NodeConfig;
 use sui_core::authority_active::checkpoint_driver::CheckpointMetrics;
 use sui_core::authority_aggregator::{AuthAggMetrics, AuthorityAggregator};
 use sui_core::authority_server::ValidatorService;
+use sui_core::batch_signer::BatchSigner;
 use sui_core::safe_client::SafeClientMetrics;
 use sui_core::transaction_orchestrator::TransactiondOrchestrator;
 use sui_core::transaction_streamer::TransactionStreamer;
@@ -143,6 +144,7 @@ impl SuiNode {
                 genesis,
                 &prometheus_registry,
                 tx_reconfigure_consensus,
+                config.authority_config.clone(),
             )
             .await,
         );
@@ -206,9 +208,18 @@ impl SuiNode {
         let batch_subsystem_handle = {
             // Start batch system so that this node can be followed
             let batch_state = state.clone();
+            let signer = match config.batch_signing_workers {
+                Some(workers) => BatchSigner::pooled(state.secret.clone(), state.name, workers),
+                None => BatchSigner::inline(state.secret.clone(), state.name),
+            };
             tokio::task::spawn(async move {
                 batch_state
-                    .run_batch_service(1000, Duration::from_secs(1))
+                    .run_batch_service_with_signer(
+                        1000,
+                        Duration::from_secs(1),
+                        &signer,
+                        &Default::default(),
+                    )
                     .await
                     .map_err(Into::into)
             })
@@ -394,10 +394,7 @@ impl TransactionBuilder {
     ) -> anyhow::Result<TransactionData> {
         fp_ensure!(
             !single_transaction_params.is_empty(),
-            SuiError::InvalidBatchTransaction {
-                error: "Batch Transaction cannot be empty".to_owned(),
-            }
-            .into()
+            SuiError::EmptyTransactionKind.into()
         );
         let mut tx_kinds = Vec::new();
         for param in single_transaction_params {
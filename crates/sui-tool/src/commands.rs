@@ -437,6 +437,7 @@ impl ToolCommand {
                                 .map(|start| BatchInfoRequest {
                                     start: Some(start),
                                     length: min(MAX_ITEMS_LIMIT, seq + len - start),
+                                    headers_only: false,
                                 });
                         for request in requests {
                             handle_batch(*c, &request).await;
@@ -445,6 +446,7 @@ impl ToolCommand {
                         let req = BatchInfoRequest {
                             start: seq,
                             length: len,
+                            headers_only: false,
                         };
                         handle_batch(*c, &req).await;
                     }
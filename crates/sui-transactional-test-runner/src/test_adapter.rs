@@ -475,7 +475,7 @@ impl<'a> SuiTestAdapter<'a> {
             .collect::<Vec<_>>();
         let input_objects = InputObjects::new(objects_by_kind);
         let transaction_dependencies = input_objects.transaction_dependencies();
-        let shared_object_refs: Vec<_> = input_objects.filter_shared_objects();
+        let shared_object_refs = input_objects.classify().shared;
         let temporary_store =
             TemporaryStore::new(self.storage.clone(), input_objects, transaction_digest);
         let (
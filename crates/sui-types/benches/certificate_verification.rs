@@ -0,0 +1,64 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use fastcrypto::traits::KeyPair;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use sui_types::base_types::AuthorityName;
+use sui_types::committee::Committee;
+use sui_types::crypto::bcs_signable_test::Foo;
+use sui_types::crypto::{
+    deterministic_key_pairs, AuthorityKeyPair, AuthoritySignature, AuthorityStrongQuorumSignInfo,
+    SuiAuthoritySignature, VerificationObligation,
+};
+
+const COMMITTEE_SIZE: usize = 100;
+const ITERATIONS: u32 = 100;
+
+/// Fully verifies the quorum ITERATIONS times and returns the mean time per verification.
+fn run(quorum: &AuthorityStrongQuorumSignInfo, committee: &Committee, message: &Foo) -> Duration {
+    let begin = Instant::now();
+    for _ in 0..ITERATIONS {
+        let mut obligation = VerificationObligation::default();
+        let idx = obligation.add_message(message);
+        quorum
+            .add_to_verification_obligation(committee, &mut obligation, idx)
+            .unwrap();
+        obligation.verify_all().unwrap();
+    }
+    begin.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let keys: Vec<AuthorityKeyPair> = deterministic_key_pairs(COMMITTEE_SIZE, [0; 32]);
+    let committee = Committee::new(
+        0,
+        keys.iter()
+            .map(|key| (AuthorityName::from(key.public()), 1))
+            .collect::<BTreeMap<_, _>>(),
+    )
+    .unwrap();
+    // As received from another node or read back from the committee store.
+    let deserialized: Committee = bcs::from_bytes(&bcs::to_bytes(&committee).unwrap()).unwrap();
+
+    let message = Foo("some data".to_string());
+    let signatures = keys
+        .iter()
+        .take(committee.quorum_threshold() as usize)
+        .map(|key| {
+            (
+                AuthorityName::from(key.public()),
+                AuthoritySignature::new(&message, key),
+            )
+        })
+        .collect();
+    let quorum =
+        AuthorityStrongQuorumSignInfo::new_with_signatures(signatures, &committee).unwrap();
+
+    let latency = run(&quorum, &committee, &message);
+    println!("certificate verification ({COMMITTEE_SIZE} members): mean {latency:?}");
+    let latency = run(&quorum, &deserialized, &message);
+    println!(
+        "certificate verification, deserialized committee ({COMMITTEE_SIZE} members): mean {latency:?}"
+    );
+}
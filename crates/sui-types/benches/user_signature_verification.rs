@@ -0,0 +1,55 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use fastcrypto::traits::KeyPair;
+use signature::Signer;
+use std::time::{Duration, Instant};
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{
+    deterministic_key_pairs, verify_user_signatures_parallel, AccountKeyPair, Signature,
+    SuiSignatureInner,
+};
+
+const SIGNATURES: usize = 10_000;
+const ITERATIONS: u32 = 10;
+
+/// Runs `verify` ITERATIONS times and returns the mean time per call.
+fn run(verify: impl Fn()) -> Duration {
+    let begin = Instant::now();
+    for _ in 0..ITERATIONS {
+        verify();
+    }
+    begin.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let keys: Vec<AccountKeyPair> = deterministic_key_pairs(SIGNATURES, [0; 32]);
+    let items: Vec<(Signature, Vec<u8>, SuiAddress)> = keys
+        .iter()
+        .enumerate()
+        .map(|(i, key)| {
+            let message = format!("transaction {i}").into_bytes();
+            let signature = Signer::<Signature>::sign(key, &message);
+            (signature, message, SuiAddress::from(key.public()))
+        })
+        .collect();
+
+    let latency = run(|| {
+        for (signature, message, author) in &items {
+            let (sig, pk) = match signature {
+                Signature::Ed25519SuiSignature(sig) => sig.get_verification_inputs(*author),
+                Signature::Secp256k1SuiSignature(_) => unreachable!(),
+            }
+            .unwrap();
+            fastcrypto::Verifier::verify(&pk, message, &sig).unwrap();
+        }
+    });
+    println!("serial verification ({SIGNATURES} ed25519 signatures): mean {latency:?}");
+
+    let latency = run(|| {
+        assert!(verify_user_signatures_parallel(&items)
+            .iter()
+            .all(|result| result.is_ok()));
+    });
+    println!("parallel verification ({SIGNATURES} ed25519 signatures): mean {latency:?}");
+}
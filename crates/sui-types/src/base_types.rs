@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::borrow::Borrow;
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::convert::{TryFrom, TryInto};
 use std::fmt;
 use std::str::FromStr;
@@ -169,6 +169,81 @@ impl SuiAddress {
     pub fn to_inner(self) -> [u8; SUI_ADDRESS_LENGTH] {
         self.0
     }
+
+    /// Returns true if the hex encoding of this address starts with `prefix_hex`.
+    /// The prefix may carry a `0x` prefix and is matched case-insensitively; an
+    /// empty prefix matches every address.
+    pub fn matches_prefix(&self, prefix_hex: &str) -> bool {
+        let prefix = prefix_hex.strip_prefix("0x").unwrap_or(prefix_hex);
+        encode_bytes_hex(self).starts_with(&prefix.to_ascii_lowercase())
+    }
+
+    fn nibbles(&self) -> impl Iterator<Item = u8> + '_ {
+        self.0.iter().flat_map(|byte| [byte >> 4, byte & 0x0f])
+    }
+}
+
+/// A set of hex address prefixes, e.g. for validator allow/deny lists. Prefixes are
+/// stored in a trie over hex nibbles, so membership checks cost at most one step per
+/// nibble of the address regardless of how many prefixes are in the set.
+#[derive(Clone, Debug, Default)]
+pub struct SuiAddressPrefixSet {
+    root: PrefixTrieNode,
+}
+
+#[derive(Clone, Debug, Default)]
+struct PrefixTrieNode {
+    terminal: bool,
+    children: BTreeMap<u8, PrefixTrieNode>,
+}
+
+impl SuiAddressPrefixSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a hex prefix (optionally starting with `0x`) to the set.
+    pub fn insert(&mut self, prefix_hex: &str) -> Result<(), anyhow::Error> {
+        let prefix = prefix_hex.strip_prefix("0x").unwrap_or(prefix_hex);
+        fp_ensure!(
+            prefix.len() <= SUI_ADDRESS_LENGTH * 2,
+            anyhow!("Address prefix {prefix_hex} is longer than an address")
+        );
+        let nibbles = prefix
+            .chars()
+            .map(|c| {
+                c.to_digit(16)
+                    .map(|d| d as u8)
+                    .ok_or_else(|| anyhow!("Invalid hex character {c:?} in {prefix_hex}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut node = &mut self.root;
+        for nibble in nibbles {
+            node = node.children.entry(nibble).or_default();
+        }
+        node.terminal = true;
+        Ok(())
+    }
+
+    /// Returns true if any prefix in the set matches `addr`.
+    pub fn contains(&self, addr: &SuiAddress) -> bool {
+        let mut node = &self.root;
+        for nibble in addr.nibbles() {
+            if node.terminal {
+                return true;
+            }
+            match node.children.get(&nibble) {
+                Some(child) => node = child,
+                None => return false,
+            }
+        }
+        node.terminal
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.root.terminal && self.root.children.is_empty()
+    }
 }
 
 impl From<ObjectID> for SuiAddress {
@@ -2,10 +2,28 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::base_types::{AuthorityName, ExecutionDigests};
-use crate::crypto::{sha3_hash, AuthoritySignInfo, AuthoritySignature, VerificationObligation};
+use crate::crypto::{
+    sha3_hash, AuthoritySignInfo, AuthoritySignature, NetworkPrivateKey, NetworkPublicKey,
+    VerificationObligation,
+};
 use crate::error::{SuiError, SuiResult};
 use crate::message_envelope::{Envelope, Message};
+use curve25519_dalek::constants::X25519_BASEPOINT;
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use curve25519_dalek::montgomery::MontgomeryPoint;
+use curve25519_dalek::scalar::Scalar;
+use digest::generic_array::typenum::{U12, U32};
+use fastcrypto::aes::{Aes256Gcm, AesKey, AuthenticatedCipher, InitializationVector};
+use fastcrypto::traits::{Generate, ToFromBytes};
+use hkdf::Hkdf;
+use rand::rngs::OsRng;
+use rand::RngCore;
 use serde::{Deserialize, Serialize};
+use sha3::Sha3_256;
+
+#[cfg(test)]
+#[path = "unit_tests/batch_tests.rs"]
+mod batch_tests;
 
 pub type TxSequenceNumber = u64;
 
@@ -90,6 +108,35 @@ impl AuthorityBatch {
             transactions_digest,
         })
     }
+
+    /// Check that this batch header is consistent with the given transactions, by
+    /// recomputing the transactions digest, sequence number range and size from them.
+    /// This is independent of (and does not replace) checking the authority signature.
+    pub fn verify_contents(
+        &self,
+        transactions: &[(TxSequenceNumber, ExecutionDigests)],
+    ) -> SuiResult {
+        fp_ensure!(
+            self.size == transactions.len() as u64,
+            SuiError::from("AuthorityBatch size mismatch")
+        );
+        if let (Some((first, _)), Some((last, _))) = (transactions.first(), transactions.last()) {
+            fp_ensure!(
+                self.initial_sequence_number == *first,
+                SuiError::from("AuthorityBatch initial sequence number mismatch")
+            );
+            fp_ensure!(
+                self.next_sequence_number == *last + 1,
+                SuiError::from("AuthorityBatch next sequence number mismatch")
+            );
+        }
+        let transaction_batch = TransactionBatch(transactions.to_vec());
+        fp_ensure!(
+            self.transactions_digest == sha3_hash(&transaction_batch),
+            SuiError::from("AuthorityBatch transactions digest mismatch")
+        );
+        Ok(())
+    }
 }
 
 impl Message for AuthorityBatch {
@@ -119,6 +166,8 @@ impl Message for AuthorityBatch {
 pub type SignedBatch = Envelope<AuthorityBatch, AuthoritySignInfo>;
 
 impl SignedBatch {
+    /// Sign `batch` for epoch 0. Batches signed during a live epoch should use
+    /// `SignedBatch::new`, which takes the epoch explicitly.
     pub fn new_with_zero_epoch(
         batch: AuthorityBatch,
         secret: &dyn signature::Signer<AuthoritySignature>,
@@ -126,4 +175,129 @@ impl SignedBatch {
     ) -> SignedBatch {
         Self::new(0, batch, secret, authority)
     }
+
+    /// Encrypt this batch to the network key of `recipient`. The authority signature is part
+    /// of the sealed payload, so it still verifies once the recipient opens it.
+    pub fn seal_to(&self, recipient: &NetworkPublicKey) -> SuiResult<SealedBatch> {
+        let recipient = x25519_public_key(recipient)?;
+        let mut ephemeral_secret = [0u8; 32];
+        OsRng.fill_bytes(&mut ephemeral_secret);
+        let ephemeral_secret = clamp_scalar(ephemeral_secret);
+        let ephemeral = &X25519_BASEPOINT * &ephemeral_secret;
+
+        let cipher = sealing_cipher(&(&recipient * &ephemeral_secret), &ephemeral, &recipient)?;
+        let nonce = InitializationVector::<U12>::generate(&mut OsRng);
+        let plaintext = bcs::to_bytes(self).map_err(|error| SuiError::InvalidSealedBatch {
+            error: error.to_string(),
+        })?;
+        let ciphertext = cipher.encrypt_authenticated(&nonce, &[], &plaintext);
+
+        let mut nonce_bytes = [0u8; 12];
+        nonce_bytes.copy_from_slice(nonce.as_bytes());
+        Ok(SealedBatch {
+            ephemeral_key: ephemeral.to_bytes(),
+            nonce: nonce_bytes,
+            ciphertext,
+        })
+    }
+}
+
+/// A `SignedBatch` encrypted to the network key of a single validator, for privacy in transit.
+///
+/// The sender derives an X25519 shared secret between a fresh ephemeral key and the
+/// recipient's Ed25519 network key, converted to X25519 the same way as libsodium's
+/// `crypto_sign_ed25519_pk_to_curve25519`. An AES-256-GCM key is derived from the shared
+/// secret with HKDF-SHA3-256, salted with both public keys, and seals the payload under a
+/// random nonce.
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct SealedBatch {
+    /// The sender's ephemeral X25519 public key.
+    ephemeral_key: [u8; 32],
+    /// The AES-GCM nonce the batch was sealed under.
+    nonce: [u8; 12],
+    /// The BCS encoded `SignedBatch`, encrypted and followed by the GCM tag.
+    ciphertext: Vec<u8>,
+}
+
+impl SealedBatch {
+    /// Decrypt the batch with the recipient's network private key. Fails if the batch was
+    /// sealed to another key or was tampered with. The authority signature of the returned
+    /// batch still needs to be verified.
+    pub fn open(&self, recipient_secret: &NetworkPrivateKey) -> SuiResult<SignedBatch> {
+        let secret = x25519_secret(recipient_secret);
+        let recipient = &X25519_BASEPOINT * &secret;
+        let ephemeral = MontgomeryPoint(self.ephemeral_key);
+
+        let cipher = sealing_cipher(&(&ephemeral * &secret), &ephemeral, &recipient)?;
+        let nonce = InitializationVector::<U12>::from_bytes(&self.nonce).map_err(|error| {
+            SuiError::InvalidSealedBatch {
+                error: error.to_string(),
+            }
+        })?;
+        let plaintext = cipher
+            .decrypt_authenticated(&nonce, &[], &self.ciphertext)
+            .map_err(|_| SuiError::InvalidSealedBatch {
+                error: "Authentication failed".to_string(),
+            })?;
+        bcs::from_bytes(&plaintext).map_err(|error| SuiError::InvalidSealedBatch {
+            error: error.to_string(),
+        })
+    }
+}
+
+const SEALED_BATCH_DOMAIN: &[u8] = b"sui-sealed-batch";
+
+fn clamp_scalar(mut bits: [u8; 32]) -> Scalar {
+    bits[0] &= 248;
+    bits[31] &= 127;
+    bits[31] |= 64;
+    Scalar::from_bits(bits)
+}
+
+/// The X25519 secret matching an Ed25519 private key, as derived by Ed25519 itself (see
+/// libsodium's `crypto_sign_ed25519_sk_to_curve25519`).
+fn x25519_secret(key: &NetworkPrivateKey) -> Scalar {
+    let hash = <sha2::Sha512 as sha2::Digest>::digest(key.as_ref());
+    let mut bits = [0u8; 32];
+    bits.copy_from_slice(&hash[..32]);
+    clamp_scalar(bits)
+}
+
+/// The Montgomery form of an Ed25519 public key.
+fn x25519_public_key(key: &NetworkPublicKey) -> SuiResult<MontgomeryPoint> {
+    let bytes: [u8; 32] = key
+        .as_ref()
+        .try_into()
+        .map_err(|_| SuiError::InvalidSealedBatch {
+            error: "Invalid network public key length".to_string(),
+        })?;
+    CompressedEdwardsY(bytes)
+        .decompress()
+        .map(|point| point.to_montgomery())
+        .ok_or_else(|| SuiError::InvalidSealedBatch {
+            error: "Invalid network public key".to_string(),
+        })
+}
+
+/// Derive the AES-256-GCM key from the X25519 shared secret with HKDF-SHA3-256.
+fn sealing_cipher(
+    shared: &MontgomeryPoint,
+    ephemeral: &MontgomeryPoint,
+    recipient: &MontgomeryPoint,
+) -> SuiResult<Aes256Gcm<U12>> {
+    // A low order key yields a known shared secret.
+    fp_ensure!(
+        shared.as_bytes() != &[0u8; 32],
+        SuiError::InvalidSealedBatch {
+            error: "Low order key".to_string()
+        }
+    );
+    let salt = [&ephemeral.as_bytes()[..], &recipient.as_bytes()[..]].concat();
+    let mut okm = [0u8; 32];
+    Hkdf::<Sha3_256>::new(Some(&salt), shared.as_bytes())
+        .expand(SEALED_BATCH_DOMAIN, &mut okm)
+        .map_err(|_| SuiError::HkdfError("Cannot derive the sealing key".to_string()))?;
+    let key =
+        AesKey::<U32>::from_bytes(&okm).map_err(|error| SuiError::HkdfError(error.to_string()))?;
+    Ok(Aes256Gcm::new(key))
 }
@@ -3,33 +3,64 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::base_types::*;
-use crate::crypto::AuthorityPublicKey;
+use crate::crypto::{
+    AuthorityPublicKey, AuthoritySignature, AuthorityStrongQuorumSignInfo, Signable,
+    VerificationObligation,
+};
 use crate::error::{SuiError, SuiResult};
+use fastcrypto::Verifier;
 use itertools::Itertools;
+use once_cell::sync::OnceCell;
 use rand::rngs::OsRng;
 use rand::seq::SliceRandom;
+use roaring::RoaringBitmap;
 use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
 use std::borrow::Borrow;
 use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt::{Display, Formatter};
+use std::time::Duration;
 
 pub type EpochId = u64;
 
 pub type StakeUnit = u64;
 
+/// The stake needed for a quorum (2f + 1) out of `total_stake`.
+pub fn quorum_threshold(total_stake: StakeUnit) -> StakeUnit {
+    // If N = 3f + 1 + k (0 <= k < 3)
+    // then (2 N + 3) / 3 = 2f + 1 + (2k + 2)/3 = 2f + 1 + k = N - f
+    2 * total_stake / 3 + 1
+}
+
+/// The stake needed to include at least one honest authority (f + 1) out of `total_stake`.
+pub fn validity_threshold(total_stake: StakeUnit) -> StakeUnit {
+    // If N = 3f + 1 + k (0 <= k < 3)
+    // then (N + 2) / 3 = f + 1 + k/3 = f + 1
+    (total_stake + 2) / 3
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Committee {
     pub epoch: EpochId,
     pub voting_rights: Vec<(AuthorityName, StakeUnit)>,
     pub total_votes: StakeUnit,
+    // Computed at construction, or on first use for a deserialized committee, so that
+    // verification does not deserialize a member's public key each time.
     #[serde(skip)]
-    expanded_keys: HashMap<AuthorityName, AuthorityPublicKey>,
+    expanded_keys: OnceCell<HashMap<AuthorityName, AuthorityPublicKey>>,
     #[serde(skip)]
     index_map: HashMap<AuthorityName, usize>,
     #[serde(skip)]
     loaded: bool,
 }
 
+/// The stake and public keys of a set of signers of the committee.
+#[derive(Debug)]
+pub struct SignerSet<'a> {
+    pub weight: StakeUnit,
+    pub public_keys: Vec<&'a AuthorityPublicKey>,
+}
+
 impl Committee {
     pub fn new(
         epoch: EpochId,
@@ -66,12 +97,67 @@ impl Committee {
             epoch,
             voting_rights,
             total_votes,
-            expanded_keys,
+            expanded_keys: OnceCell::from(expanded_keys),
             index_map,
             loaded: true,
         })
     }
 
+    /// Build the committee of `epoch` from a validator set, e.g. read from chain, given as a
+    /// list of names and stakes. Unlike `new`, which takes a map, a name listed twice is an
+    /// error rather than silently overwriting the earlier entry.
+    pub fn from_validator_set(
+        epoch: EpochId,
+        validators: &[(AuthorityName, StakeUnit)],
+    ) -> SuiResult<Self> {
+        let mut voting_rights = BTreeMap::new();
+        let mut total_stake: StakeUnit = 0;
+        for (name, stake) in validators {
+            fp_ensure!(
+                voting_rights.insert(*name, *stake).is_none(),
+                SuiError::InvalidCommittee(format!("validator {name} is listed more than once"))
+            );
+            total_stake = total_stake.checked_add(*stake).ok_or_else(|| {
+                SuiError::InvalidCommittee("total stake of the committee overflows".into())
+            })?;
+        }
+        fp_ensure!(
+            total_stake > 0,
+            SuiError::InvalidCommittee("total stake of the committee is zero".into())
+        );
+        Self::new(epoch, voting_rights)
+    }
+
+    /// Same as `new`, with `observers` added as non-voting members. Observers can be resolved
+    /// like any other member, e.g. for networking, but have no stake: they never count towards
+    /// a threshold and are rejected as signers. An observer cannot also hold stake.
+    pub fn new_with_observers(
+        epoch: EpochId,
+        mut voting_rights: BTreeMap<AuthorityName, StakeUnit>,
+        observers: BTreeSet<AuthorityName>,
+    ) -> SuiResult<Self> {
+        for observer in observers {
+            fp_ensure!(
+                voting_rights.insert(observer, 0).is_none(),
+                SuiError::InvalidCommittee(format!("observer {observer} also has voting rights"))
+            );
+        }
+        Self::new(epoch, voting_rights)
+    }
+
+    /// Whether `name` is a non-voting member of the committee. Members listed with zero stake
+    /// in `new` are observers too.
+    pub fn is_observer(&self, name: &AuthorityName) -> bool {
+        self.authority_exists(name) && self.weight(name) == 0
+    }
+
+    pub fn observers(&self) -> impl Iterator<Item = &AuthorityName> {
+        self.voting_rights
+            .iter()
+            .filter(|(_, stake)| *stake == 0)
+            .map(|(name, _)| name)
+    }
+
     // We call this if these have not yet been computed
     pub fn load_inner(
         voting_rights: &[(AuthorityName, StakeUnit)],
@@ -96,7 +182,7 @@ impl Committee {
 
     pub fn reload_fields(&mut self) {
         let (expanded_keys, index_map) = Committee::load_inner(&self.voting_rights);
-        self.expanded_keys = expanded_keys;
+        self.expanded_keys = OnceCell::from(expanded_keys);
         self.index_map = index_map;
         self.loaded = true;
     }
@@ -120,14 +206,56 @@ impl Committee {
         self.epoch
     }
 
-    pub fn public_key(&self, authority: &AuthorityName) -> SuiResult<AuthorityPublicKey> {
-        match self.expanded_keys.get(authority) {
-            // TODO: Check if this is unnecessary copying.
-            Some(v) => Ok(v.clone()),
-            None => (*authority).try_into().map_err(|_| {
-                SuiError::InvalidCommittee(format!("Authority #{} not found", authority))
-            }),
+    /// The public key of `authority`, which must be a member of the committee.
+    pub fn public_key(&self, authority: &AuthorityName) -> SuiResult<&AuthorityPublicKey> {
+        self.expanded_keys().get(authority).ok_or_else(|| {
+            SuiError::InvalidCommittee(format!("Authority #{} not found", authority))
+        })
+    }
+
+    /// The total stake and public keys of the authorities in `signers_map`, which must all be
+    /// members with stake. The signers are looked up by index, and their keys are borrowed from
+    /// the committee.
+    pub fn signer_set(&self, signers_map: &RoaringBitmap) -> SuiResult<SignerSet<'_>> {
+        let mut weight = 0;
+        let mut public_keys = Vec::with_capacity(signers_map.len() as usize);
+        for authority_index in signers_map.iter() {
+            let (authority, voting_rights) = self
+                .voting_rights
+                .get(authority_index as usize)
+                .ok_or(SuiError::UnknownSigner)?;
+
+            // Update weight.
+            fp_ensure!(*voting_rights > 0, SuiError::UnknownSigner);
+            weight += voting_rights;
+
+            public_keys.push(self.public_key(authority)?);
         }
+        Ok(SignerSet {
+            weight,
+            public_keys,
+        })
+    }
+
+    fn expanded_keys(&self) -> &HashMap<AuthorityName, AuthorityPublicKey> {
+        self.expanded_keys.get_or_init(|| {
+            // Members whose key does not deserialize are left out, and fail the lookup.
+            self.voting_rights
+                .iter()
+                .filter_map(|(name, _)| Some((*name, (*name).try_into().ok()?)))
+                .collect()
+        })
+    }
+
+    /// A short, stable identifier of the committee for logs: a truncated hex hash of its epoch
+    /// and its authorities with their stake. Committees of the same epoch with the same
+    /// members and stake have the same fingerprint.
+    pub fn fingerprint(&self) -> String {
+        // The voting rights are sorted by name on construction.
+        let bytes = bcs::to_bytes(&(self.epoch, &self.voting_rights))
+            .expect("Committee serialization cannot fail");
+        let digest = Sha3_256::digest(&bytes);
+        hex::encode(&digest[..8])
     }
 
     /// Samples authorities by weight
@@ -190,15 +318,18 @@ impl Committee {
     }
 
     pub fn quorum_threshold(&self) -> StakeUnit {
-        // If N = 3f + 1 + k (0 <= k < 3)
-        // then (2 N + 3) / 3 = 2f + 1 + (2k + 2)/3 = 2f + 1 + k = N - f
-        2 * self.total_votes / 3 + 1
+        quorum_threshold(self.total_votes)
     }
 
     pub fn validity_threshold(&self) -> StakeUnit {
-        // If N = 3f + 1 + k (0 <= k < 3)
-        // then (N + 2) / 3 = f + 1 + k/3 = f + 1
-        (self.total_votes + 2) / 3
+        validity_threshold(self.total_votes)
+    }
+
+    /// The stake by which `signers` exceed the quorum threshold, negative if they fall short.
+    /// Signers outside the committee and repeated signers add no stake.
+    pub fn safety_margin(&self, signers: &[AuthorityName]) -> i64 {
+        let signed_stake: StakeUnit = signers.iter().unique().map(|name| self.weight(name)).sum();
+        signed_stake as i64 - self.quorum_threshold() as i64
     }
 
     /// Given a sequence of (AuthorityName, value) for values, provide the
@@ -238,6 +369,31 @@ impl Committee {
         unreachable!();
     }
 
+    /// Suggest how long to wait for a response from `authority`, scaling `base` up by the
+    /// authority's fraction of the total stake. The result ranges from `base`, for authorities
+    /// with no stake or not in the committee, to twice `base`.
+    pub fn suggested_timeout(&self, base: Duration, authority: &AuthorityName) -> Duration {
+        let stake_fraction = self.weight(authority) as f64 / self.total_votes as f64;
+        base + base.mul_f64(stake_fraction)
+    }
+
+    /// The smallest number of authorities whose combined stake reaches `quorum_threshold()`,
+    /// found by taking authorities in decreasing order of stake.
+    pub fn min_authorities_for_quorum(&self) -> usize {
+        let mut stakes: Vec<_> = self.voting_rights.iter().map(|(_, stake)| *stake).collect();
+        stakes.sort_unstable_by(|a, b| b.cmp(a));
+
+        let threshold = self.quorum_threshold();
+        let mut total = 0;
+        for (count, stake) in stakes.into_iter().enumerate() {
+            total += stake;
+            if total >= threshold {
+                return count + 1;
+            }
+        }
+        unreachable!("the total stake always reaches the quorum threshold");
+    }
+
     pub fn num_members(&self) -> usize {
         self.voting_rights.len()
     }
@@ -259,6 +415,114 @@ impl Committee {
             .binary_search_by_key(name, |(a, _)| *a)
             .is_ok()
     }
+
+    /// Verify each signature individually against the same `message` and return the names of
+    /// the authorities whose signature is valid. Signatures from authorities that are not part
+    /// of this committee, or that are over a different message, are left out of the result, so
+    /// that they can be rejected before aggregating the rest into a quorum.
+    pub fn verify_individual_signatures(
+        &self,
+        message: &[u8],
+        sigs: &[(AuthorityName, AuthoritySignature)],
+    ) -> SuiResult<Vec<AuthorityName>> {
+        let mut valid_signers = Vec::new();
+        for (name, signature) in sigs {
+            if !self.authority_exists(name) {
+                continue;
+            }
+            let public_key = self.public_key(name)?;
+            if public_key.verify(message, signature).is_ok() {
+                valid_signers.push(*name);
+            }
+        }
+        Ok(valid_signers)
+    }
+
+    /// Verify the quorum signatures of many certificates at once, e.g. when replaying a
+    /// checkpoint, where each certificate is given as the signed message and its quorum. All
+    /// signatures are checked in a single batch, so a failure does not tell which certificate
+    /// is invalid; see `batch_verify_certificates_collect`.
+    pub fn batch_verify_certificates<T>(
+        &self,
+        certs: &[(&T, &AuthorityStrongQuorumSignInfo)],
+    ) -> SuiResult<()>
+    where
+        T: Signable<Vec<u8>>,
+    {
+        if certs.is_empty() {
+            return Ok(());
+        }
+        let mut obligation = VerificationObligation::default();
+        for (message, quorum) in certs {
+            let idx = obligation.add_message(*message);
+            quorum.add_to_verification_obligation(self, &mut obligation, idx)?;
+        }
+        obligation.verify_all()
+    }
+
+    /// Same as `batch_verify_certificates`, but if the batch fails, each certificate is verified
+    /// on its own to return the index and error of every invalid one. Returns nothing if all
+    /// certificates are valid.
+    pub fn batch_verify_certificates_collect<T>(
+        &self,
+        certs: &[(&T, &AuthorityStrongQuorumSignInfo)],
+    ) -> Vec<(usize, SuiError)>
+    where
+        T: Signable<Vec<u8>>,
+    {
+        if self.batch_verify_certificates(certs).is_ok() {
+            return Vec::new();
+        }
+        certs
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, cert)| {
+                self.batch_verify_certificates(std::slice::from_ref(cert))
+                    .err()
+                    .map(|e| (idx, e))
+            })
+            .collect()
+    }
+
+    /// Compare this committee against `other` (typically the committee of a later epoch),
+    /// listing the authorities that joined, left, or had their stake changed.
+    pub fn diff(&self, other: &Committee) -> CommitteeDiff {
+        let mut diff = CommitteeDiff::default();
+        for (name, stake) in self.members() {
+            match other.voting_rights.binary_search_by_key(name, |(a, _)| *a) {
+                Err(_) => diff.removed.push(*name),
+                Ok(idx) => {
+                    let new_stake = other.voting_rights[idx].1;
+                    if new_stake != *stake {
+                        diff.stake_changed.push((*name, *stake, new_stake));
+                    }
+                }
+            }
+        }
+        diff.added = other
+            .members()
+            .filter(|(name, _)| !self.authority_exists(name))
+            .map(|(name, _)| *name)
+            .collect();
+        diff
+    }
+}
+
+/// The changes in membership between two committees, as returned by `Committee::diff`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommitteeDiff {
+    /// Authorities only in the new committee.
+    pub added: Vec<AuthorityName>,
+    /// Authorities only in the old committee.
+    pub removed: Vec<AuthorityName>,
+    /// Authorities in both committees with a different stake, as (name, old, new).
+    pub stake_changed: Vec<(AuthorityName, StakeUnit, StakeUnit)>,
+}
+
+impl CommitteeDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.stake_changed.is_empty()
+    }
 }
 
 impl PartialEq for Committee {
@@ -282,9 +546,31 @@ impl Display for Committee {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::crypto::{get_key_pair, AuthorityKeyPair};
+    use crate::crypto::bcs_signable_test::Foo;
+    use crate::crypto::{get_key_pair, AuthorityKeyPair, Signable, SuiAuthoritySignature};
     use fastcrypto::traits::KeyPair;
 
+    #[test]
+    fn test_thresholds() {
+        // (total stake, quorum threshold, validity threshold)
+        let cases = [
+            (1, 1, 1),
+            (3, 3, 1),
+            (4, 3, 2),
+            (5, 4, 2),
+            (6, 5, 2),
+            (7, 5, 3),
+            (10, 7, 4),
+            (10_000, 6_667, 3_334),
+        ];
+        for (total, quorum, validity) in cases {
+            assert_eq!(quorum_threshold(total), quorum, "quorum of {total}");
+            assert_eq!(validity_threshold(total), validity, "validity of {total}");
+            // A quorum always leaves less than a validity threshold of stake outside it.
+            assert!(total - quorum < validity);
+        }
+    }
+
     #[test]
     fn test_shuffle_by_weight() {
         let (_, sec1): (_, AuthorityKeyPair) = get_key_pair();
@@ -363,4 +649,380 @@ mod test {
             (a3, "c")
         );
     }
+
+    #[test]
+    fn test_verify_individual_signatures() {
+        let message = Foo("some data".to_string());
+        let other_message = Foo("some other data".to_string());
+
+        let mut authorities = BTreeMap::new();
+        let mut sigs = Vec::new();
+        for i in 0..4 {
+            let (_, sec): (_, AuthorityKeyPair) = get_key_pair();
+            let name: AuthorityName = sec.public().into();
+            authorities.insert(name, 1);
+            // The first authority signs a different message.
+            let sig = if i == 0 {
+                AuthoritySignature::new(&other_message, &sec)
+            } else {
+                AuthoritySignature::new(&message, &sec)
+            };
+            sigs.push((name, sig));
+        }
+        let committee = Committee::new(0, authorities).unwrap();
+
+        let mut message_bytes = Vec::new();
+        message.write(&mut message_bytes);
+
+        let valid_signers = committee
+            .verify_individual_signatures(&message_bytes, &sigs)
+            .unwrap();
+        assert_eq!(valid_signers.len(), 3);
+        assert!(!valid_signers.contains(&sigs[0].0));
+        for (name, _) in &sigs[1..] {
+            assert!(valid_signers.contains(name));
+        }
+    }
+
+    #[test]
+    fn test_from_validator_set() {
+        let names: Vec<AuthorityName> = (0..3)
+            .map(|_| {
+                let (_, sec): (_, AuthorityKeyPair) = get_key_pair();
+                sec.public().into()
+            })
+            .collect();
+
+        let committee =
+            Committee::from_validator_set(1, &[(names[0], 1), (names[1], 2), (names[2], 3)])
+                .unwrap();
+        assert_eq!(committee.epoch(), 1);
+        assert_eq!(committee.total_votes, 6);
+        assert_eq!(committee.weight(&names[2]), 3);
+
+        // A validator listed twice is rejected, even with the same stake.
+        assert!(matches!(
+            Committee::from_validator_set(1, &[(names[0], 1), (names[1], 2), (names[0], 1)]),
+            Err(SuiError::InvalidCommittee(_))
+        ));
+        // So is a committee without stake.
+        assert!(matches!(
+            Committee::from_validator_set(1, &[(names[0], 0), (names[1], 0)]),
+            Err(SuiError::InvalidCommittee(_))
+        ));
+        assert!(matches!(
+            Committee::from_validator_set(1, &[(names[0], u64::MAX), (names[1], 1)]),
+            Err(SuiError::InvalidCommittee(_))
+        ));
+    }
+
+    #[test]
+    fn test_committee_diff() {
+        let names: Vec<AuthorityName> = (0..4)
+            .map(|_| {
+                let (_, sec): (_, AuthorityKeyPair) = get_key_pair();
+                sec.public().into()
+            })
+            .collect();
+
+        let old = Committee::new(
+            0,
+            BTreeMap::from([(names[0], 1), (names[1], 1), (names[2], 1)]),
+        )
+        .unwrap();
+        // names[0] leaves, names[2] doubles its stake and names[3] joins.
+        let new = Committee::new(
+            1,
+            BTreeMap::from([(names[1], 1), (names[2], 2), (names[3], 1)]),
+        )
+        .unwrap();
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.added, vec![names[3]]);
+        assert_eq!(diff.removed, vec![names[0]]);
+        assert_eq!(diff.stake_changed, vec![(names[2], 1, 2)]);
+
+        let reverse = new.diff(&old);
+        assert_eq!(reverse.added, vec![names[0]]);
+        assert_eq!(reverse.removed, vec![names[3]]);
+        assert_eq!(reverse.stake_changed, vec![(names[2], 2, 1)]);
+
+        assert!(old.diff(&old).is_empty());
+    }
+
+    #[test]
+    fn test_suggested_timeout() {
+        let names: Vec<AuthorityName> = (0..4)
+            .map(|_| {
+                let (_, sec): (_, AuthorityKeyPair) = get_key_pair();
+                sec.public().into()
+            })
+            .collect();
+        let committee = Committee::new(
+            0,
+            BTreeMap::from([(names[0], 1), (names[1], 2), (names[2], 5)]),
+        )
+        .unwrap();
+
+        let base = Duration::from_secs(8);
+        let timeouts: Vec<_> = names[..3]
+            .iter()
+            .map(|name| committee.suggested_timeout(base, name))
+            .collect();
+        assert_eq!(timeouts[0], Duration::from_secs(9));
+        assert_eq!(timeouts[1], Duration::from_secs(10));
+        assert_eq!(timeouts[2], Duration::from_secs(13));
+        // The highest-stake validator gets the longest timeout.
+        assert_eq!(timeouts.iter().max(), Some(&timeouts[2]));
+
+        // Authorities outside the committee get the base timeout.
+        assert_eq!(committee.suggested_timeout(base, &names[3]), base);
+    }
+
+    #[test]
+    fn test_min_authorities_for_quorum() {
+        let names: Vec<AuthorityName> = (0..5)
+            .map(|_| {
+                let (_, sec): (_, AuthorityKeyPair) = get_key_pair();
+                sec.public().into()
+            })
+            .collect();
+
+        // Total stake is 20, so the quorum threshold is 14: the two largest authorities
+        // (10 + 3) fall short, the third one (+ 3) gets there.
+        let committee = Committee::new(
+            0,
+            BTreeMap::from([
+                (names[0], 1),
+                (names[1], 3),
+                (names[2], 10),
+                (names[3], 3),
+                (names[4], 3),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(committee.quorum_threshold(), 14);
+        assert_eq!(committee.min_authorities_for_quorum(), 3);
+
+        // A single authority with a quorum of stake is enough on its own.
+        let committee = Committee::new(0, BTreeMap::from([(names[0], 1), (names[1], 10)])).unwrap();
+        assert_eq!(committee.min_authorities_for_quorum(), 1);
+
+        // With equal stake, it takes 2f + 1 authorities.
+        let committee =
+            Committee::new(0, names[..4].iter().map(|name| (*name, 1)).collect()).unwrap();
+        assert_eq!(committee.min_authorities_for_quorum(), 3);
+    }
+
+    #[test]
+    fn test_cached_public_keys() {
+        let keys: Vec<AuthorityKeyPair> = (0..4).map(|_| get_key_pair().1).collect();
+        let names: Vec<AuthorityName> = keys.iter().map(|key| key.public().into()).collect();
+        let committee = Committee::new(0, names.iter().map(|name| (*name, 1)).collect()).unwrap();
+        // A deserialized committee fills its cache on first use instead.
+        let deserialized: Committee = bcs::from_bytes(&bcs::to_bytes(&committee).unwrap()).unwrap();
+
+        for (name, key) in names.iter().zip(&keys) {
+            let fresh = AuthorityPublicKey::try_from(*name).unwrap();
+            assert_eq!(committee.public_key(name).unwrap().as_ref(), fresh.as_ref());
+            assert_eq!(
+                deserialized.public_key(name).unwrap().as_ref(),
+                fresh.as_ref()
+            );
+            assert_eq!(
+                committee.public_key(name).unwrap().as_ref(),
+                key.public().as_ref()
+            );
+        }
+
+        let (_, outsider): (_, AuthorityKeyPair) = get_key_pair();
+        assert!(matches!(
+            committee.public_key(&outsider.public().into()),
+            Err(SuiError::InvalidCommittee(_))
+        ));
+    }
+
+    #[test]
+    fn test_safety_margin() {
+        let names: Vec<AuthorityName> = (0..4)
+            .map(|_| get_key_pair::<AuthorityKeyPair>().1.public().into())
+            .collect();
+        // Total stake 10, quorum threshold 7.
+        let committee = Committee::new(
+            0,
+            names
+                .iter()
+                .zip([1, 2, 3, 4])
+                .map(|(n, s)| (*n, s))
+                .collect(),
+        )
+        .unwrap();
+
+        // Stake 2 + 3 + 4 = 9 is just above the quorum, 1 + 2 + 3 = 6 just below.
+        assert_eq!(committee.safety_margin(&names[1..]), 2);
+        assert_eq!(committee.safety_margin(&names[..3]), -1);
+        assert_eq!(committee.safety_margin(&names[..]), 3);
+        assert_eq!(committee.safety_margin(&[]), -7);
+
+        // Repeated signers and outsiders do not count.
+        let (_, outsider): (_, AuthorityKeyPair) = get_key_pair();
+        let signers = [
+            names[0],
+            names[1],
+            names[2],
+            names[2],
+            outsider.public().into(),
+        ];
+        assert_eq!(committee.safety_margin(&signers), -1);
+    }
+
+    #[test]
+    fn test_batch_verify_certificates() {
+        let keys: Vec<AuthorityKeyPair> = (0..4).map(|_| get_key_pair().1).collect();
+        let committee = Committee::new(
+            0,
+            keys.iter()
+                .map(|key| (AuthorityName::from(key.public()), 1))
+                .collect(),
+        )
+        .unwrap();
+        let sign = |message: &Foo| {
+            let signatures = keys[..3]
+                .iter()
+                .map(|key| {
+                    (
+                        AuthorityName::from(key.public()),
+                        AuthoritySignature::new(message, key),
+                    )
+                })
+                .collect();
+            AuthorityStrongQuorumSignInfo::new_with_signatures(signatures, &committee).unwrap()
+        };
+
+        let messages: Vec<Foo> = (0..5).map(|i| Foo(format!("certificate {i}"))).collect();
+        let mut quorums: Vec<_> = messages.iter().map(sign).collect();
+        let certs: Vec<_> = messages.iter().zip(&quorums).collect();
+        committee.batch_verify_certificates(&certs).unwrap();
+        assert!(committee
+            .batch_verify_certificates_collect(&certs)
+            .is_empty());
+
+        // The quorum of the fourth certificate is over another message.
+        quorums[3] = sign(&Foo("something else".to_string()));
+        let certs: Vec<_> = messages.iter().zip(&quorums).collect();
+        assert!(committee.batch_verify_certificates(&certs).is_err());
+        let invalid = committee.batch_verify_certificates_collect(&certs);
+        assert_eq!(invalid.len(), 1);
+        assert_eq!(invalid[0].0, 3);
+    }
+
+    #[test]
+    fn test_observers_do_not_vote() {
+        let keys: Vec<AuthorityKeyPair> = (0..4).map(|_| get_key_pair().1).collect();
+        let names: Vec<AuthorityName> = keys.iter().map(|key| key.public().into()).collect();
+        let observer = names[3];
+        let committee = Committee::new_with_observers(
+            0,
+            names[..3].iter().map(|name| (*name, 1)).collect(),
+            BTreeSet::from([observer]),
+        )
+        .unwrap();
+
+        assert!(committee.is_observer(&observer));
+        assert_eq!(committee.observers().collect::<Vec<_>>(), vec![&observer]);
+        assert_eq!(committee.weight(&observer), 0);
+        assert_eq!(committee.total_votes, 3);
+        assert_eq!(committee.quorum_threshold(), 3);
+        let index = committee.authority_index(&observer).unwrap();
+        assert_eq!(committee.authority_by_index(index), Some(&observer));
+
+        // Two voters and the observer do not make a quorum.
+        let message = Foo("some data".to_string());
+        let verify = |signers: &[&AuthorityKeyPair]| {
+            let signatures = signers
+                .iter()
+                .map(|key| {
+                    (
+                        AuthorityName::from(key.public()),
+                        AuthoritySignature::new(&message, *key),
+                    )
+                })
+                .collect();
+            let quorum =
+                AuthorityStrongQuorumSignInfo::new_with_signatures(signatures, &committee)?;
+            committee.batch_verify_certificates(&[(&message, &quorum)])
+        };
+        let voter_keys: Vec<_> = keys[..3].iter().collect();
+        assert!(verify(&[voter_keys[0], voter_keys[1], &keys[3]]).is_err());
+        assert_eq!(committee.safety_margin(&[names[0], names[1], observer]), -1);
+        verify(&voter_keys).unwrap();
+
+        // An observer cannot also vote.
+        assert!(Committee::new_with_observers(
+            0,
+            names[..3].iter().map(|name| (*name, 1)).collect(),
+            BTreeSet::from([names[0]]),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_committee_fingerprint() {
+        let names: Vec<AuthorityName> = (0..4)
+            .map(|_| get_key_pair::<AuthorityKeyPair>().1.public().into())
+            .collect();
+        let stakes = [1, 2, 3, 4];
+        let committee = Committee::new(0, names.iter().copied().zip(stakes).collect()).unwrap();
+        let fingerprint = committee.fingerprint();
+        assert_eq!(fingerprint.len(), 16);
+
+        // The same membership, given in another order.
+        let reordered =
+            Committee::new(0, names.iter().copied().zip(stakes).rev().collect()).unwrap();
+        assert_eq!(reordered.fingerprint(), fingerprint);
+        let deserialized: Committee = bcs::from_bytes(&bcs::to_bytes(&committee).unwrap()).unwrap();
+        assert_eq!(deserialized.fingerprint(), fingerprint);
+
+        // Any change of epoch, stake or membership changes it.
+        let next_epoch = Committee::new(1, names.iter().copied().zip(stakes).collect()).unwrap();
+        assert_ne!(next_epoch.fingerprint(), fingerprint);
+        let restaked =
+            Committee::new(0, names.iter().copied().zip([1, 2, 3, 5]).collect()).unwrap();
+        assert_ne!(restaked.fingerprint(), fingerprint);
+        let smaller = Committee::new(0, names[..3].iter().copied().zip(stakes).collect()).unwrap();
+        assert_ne!(smaller.fingerprint(), fingerprint);
+    }
+
+    #[test]
+    fn test_signer_set() {
+        let names: Vec<AuthorityName> = (0..4)
+            .map(|_| get_key_pair::<AuthorityKeyPair>().1.public().into())
+            .collect();
+        let committee =
+            Committee::new(0, names.iter().copied().zip([1, 2, 3, 4]).collect()).unwrap();
+
+        let signers_map: RoaringBitmap = [0, 2].into_iter().collect();
+        let signers = committee.signer_set(&signers_map).unwrap();
+        let expected_weight: StakeUnit = signers_map
+            .iter()
+            .map(|i| committee.weight(committee.authority_by_index(i).unwrap()))
+            .sum();
+        assert_eq!(signers.weight, expected_weight);
+        let expected_keys: Vec<_> = signers_map
+            .iter()
+            .map(|i| {
+                committee
+                    .public_key(committee.authority_by_index(i).unwrap())
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(signers.public_keys, expected_keys);
+
+        // Signers outside the committee are rejected.
+        let unknown: RoaringBitmap = [0, 4].into_iter().collect();
+        assert!(matches!(
+            committee.signer_set(&unknown),
+            Err(SuiError::UnknownSigner)
+        ));
+    }
 }
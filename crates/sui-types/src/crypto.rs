@@ -1,8 +1,10 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
 use std::hash::{Hash, Hasher};
 use std::str::FromStr;
+use std::sync::Arc;
 
 use anyhow::{anyhow, Error};
 use base64ct::Encoding;
@@ -22,7 +24,9 @@ pub use fastcrypto::traits::{
     VerifyingKey,
 };
 use fastcrypto::Verifier;
-use rand::rngs::OsRng;
+use rand::rngs::{OsRng, StdRng};
+use rand::SeedableRng;
+use rayon::prelude::*;
 use roaring::RoaringBitmap;
 use schemars::JsonSchema;
 use serde::ser::Serializer;
@@ -38,6 +42,10 @@ use crate::error::{SuiError, SuiResult};
 use crate::sui_serde::{AggrAuthSignature, Base64, Readable, SuiBitmap};
 pub use enum_dispatch::enum_dispatch;
 
+#[cfg(test)]
+#[path = "unit_tests/crypto_tests.rs"]
+mod crypto_tests;
+
 // Authority Objects
 pub type AuthorityKeyPair = BLS12381KeyPair;
 pub type AuthorityPublicKey = BLS12381PublicKey;
@@ -72,6 +80,37 @@ pub fn generate_proof_of_possession<K: KeypairTraits>(
     keypair.sign(&domain_with_pk[..])
 }
 
+/// Verify a proof of possession made by `generate_proof_of_possession` for `public_key` and
+/// `address`.
+pub fn verify_proof_of_possession(
+    pop: &AuthoritySignature,
+    public_key: &AuthorityPublicKey,
+    address: SuiAddress,
+) -> SuiResult<()> {
+    let mut domain_with_pk: Vec<u8> = Vec::new();
+    domain_with_pk.extend_from_slice(PROOF_OF_POSSESSION_DOMAIN);
+    domain_with_pk.extend_from_slice(public_key.as_bytes());
+    domain_with_pk.extend_from_slice(address.as_ref());
+    public_key
+        .verify(&domain_with_pk[..], pop)
+        .map_err(|e| SuiError::InvalidSignature {
+            error: format!("Invalid proof of possession: {e}"),
+        })
+}
+
+/// Check the public key and proof of possession submitted by a validator registering with
+/// `address`, returning its authority name.
+pub fn register_authority(
+    pubkey_bytes: &[u8],
+    pop: &AuthoritySignature,
+    address: SuiAddress,
+) -> SuiResult<AuthorityName> {
+    let public_key = AuthorityPublicKey::from_bytes(pubkey_bytes)
+        .map_err(|_| SuiError::KeyConversionError("Invalid authority public key".to_string()))?;
+    verify_proof_of_possession(pop, &public_key, address)?;
+    Ok(AuthorityPublicKeyBytes::from(&public_key))
+}
+
 ///////////////////////////////////////////////
 /// Account Keys
 ///
@@ -105,6 +144,22 @@ impl PartialEq for PublicKey {
 
 impl Eq for PublicKey {}
 
+impl PartialOrd for PublicKey {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Public keys are ordered first by scheme flag, then by their raw bytes. This is the
+/// canonical ordering used wherever a set of account keys must be ordered deterministically.
+impl Ord for PublicKey {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.flag()
+            .cmp(&other.flag())
+            .then_with(|| self.as_ref().cmp(other.as_ref()))
+    }
+}
+
 impl SuiKeyPair {
     pub fn public(&self) -> PublicKey {
         match self {
@@ -112,6 +167,56 @@ impl SuiKeyPair {
             SuiKeyPair::Secp256k1SuiKeyPair(kp) => PublicKey::Secp256k1KeyPair(kp.public().clone()),
         }
     }
+
+    /// Whether `pk` is the public key of this key pair, e.g. to confirm an imported key.
+    pub fn owns_public_key(&self, pk: &PublicKey) -> bool {
+        match (self, pk) {
+            (SuiKeyPair::Ed25519SuiKeyPair(kp), PublicKey::Ed25519KeyPair(pk)) => kp.public() == pk,
+            (SuiKeyPair::Secp256k1SuiKeyPair(kp), PublicKey::Secp256k1KeyPair(pk)) => {
+                kp.public() == pk
+            }
+            _ => false,
+        }
+    }
+
+    /// Build an Ed25519 key pair from its raw 32-byte secret seed, as exported by tools that
+    /// do not use the flagged base64 encoding of `EncodeDecodeBase64`.
+    pub fn from_ed25519_seed(seed: &[u8; 32]) -> SuiResult<SuiKeyPair> {
+        let sk = Ed25519PrivateKey::from_bytes(seed)
+            .map_err(|e| SuiError::SignatureKeyGenError(e.to_string()))?;
+        Ok(SuiKeyPair::Ed25519SuiKeyPair(Ed25519KeyPair::from(sk)))
+    }
+
+    /// Derive the key pair at the address index following `current_index` on the default
+    /// derivation path of `key_scheme` (see `derive_key_pair_from_path`), returning it along
+    /// with its address and the new index.
+    pub fn rotate(
+        seed: &[u8],
+        key_scheme: &SignatureScheme,
+        current_index: u32,
+    ) -> SuiResult<(SuiAddress, SuiKeyPair, u32)> {
+        let index = current_index
+            .checked_add(1)
+            .ok_or_else(|| SuiError::SignatureKeyGenError("Invalid address index".to_string()))?;
+        let path = match key_scheme {
+            SignatureScheme::ED25519 => format!(
+                "m/{DERVIATION_PATH_PURPOSE_ED25519}'/{DERIVATION_PATH_COIN_TYPE}'/0'/0'/{index}'"
+            ),
+            SignatureScheme::Secp256k1 => format!(
+                "m/{DERVIATION_PATH_PURPOSE_SECP256K1}'/{DERIVATION_PATH_COIN_TYPE}'/0'/0/{index}"
+            ),
+            SignatureScheme::BLS12381 => {
+                return Err(SuiError::UnsupportedFeatureError {
+                    error: "BLS is not supported for user key derivation".to_string(),
+                })
+            }
+        };
+        let path = path
+            .parse()
+            .map_err(|_| SuiError::SignatureKeyGenError("Invalid address index".to_string()))?;
+        let (address, kp) = derive_key_pair_from_path(seed, Some(path), key_scheme)?;
+        Ok((address, kp, index))
+    }
 }
 
 impl signature::Signer<Signature> for SuiKeyPair {
@@ -245,20 +350,7 @@ impl EncodeDecodeBase64 for PublicKey {
     fn decode_base64(value: &str) -> Result<Self, eyre::Report> {
         let bytes =
             base64ct::Base64::decode_vec(value).map_err(|e| eyre::eyre!("{}", e.to_string()))?;
-        match bytes.first() {
-            Some(x) => {
-                if x == &<Ed25519PublicKey as SuiPublicKey>::SIGNATURE_SCHEME.flag() {
-                    let pk = Ed25519PublicKey::from_bytes(&bytes[1..])?;
-                    Ok(PublicKey::Ed25519KeyPair(pk))
-                } else if x == &<Secp256k1PublicKey as SuiPublicKey>::SIGNATURE_SCHEME.flag() {
-                    let pk = Secp256k1PublicKey::from_bytes(&bytes[1..])?;
-                    Ok(PublicKey::Secp256k1KeyPair(pk))
-                } else {
-                    Err(eyre::eyre!("Invalid flag byte"))
-                }
-            }
-            _ => Err(eyre::eyre!("Invalid bytes")),
-        }
+        PublicKey::from_flagged_bytes(&bytes).map_err(|e| eyre::eyre!("{}", e))
     }
 }
 
@@ -308,6 +400,27 @@ impl PublicKey {
             }
         })
     }
+
+    /// Parse a public key prefixed with its scheme flag, as in the decoded output of
+    /// `encode_base64`.
+    pub fn from_flagged_bytes(bytes: &[u8]) -> SuiResult<PublicKey> {
+        let (flag, key_bytes) = bytes
+            .split_first()
+            .ok_or_else(|| SuiError::KeyConversionError("Invalid bytes".to_string()))?;
+        let key_error = |e: signature::Error| SuiError::KeyConversionError(e.to_string());
+        if *flag == <Ed25519PublicKey as SuiPublicKey>::SIGNATURE_SCHEME.flag() {
+            let pk = Ed25519PublicKey::from_bytes(key_bytes).map_err(key_error)?;
+            Ok(PublicKey::Ed25519KeyPair(pk))
+        } else if *flag == <Secp256k1PublicKey as SuiPublicKey>::SIGNATURE_SCHEME.flag() {
+            let pk = Secp256k1PublicKey::from_bytes(key_bytes).map_err(key_error)?;
+            Ok(PublicKey::Secp256k1KeyPair(pk))
+        } else {
+            Err(SuiError::KeyConversionError(
+                "Invalid flag byte".to_string(),
+            ))
+        }
+    }
+
     pub fn scheme(&self) -> SignatureScheme {
         match self {
             PublicKey::Ed25519KeyPair(_) => Ed25519SuiSignature::SCHEME,
@@ -349,6 +462,33 @@ impl From<&AuthorityPublicKey> for AuthorityPublicKeyBytes {
     }
 }
 
+impl From<AuthorityPublicKey> for AuthorityName {
+    fn from(pk: AuthorityPublicKey) -> AuthorityName {
+        AuthorityName::from(&pk)
+    }
+}
+
+/// An `AuthorityName` is the compressed bytes of the authority's public key, i.e. an
+/// `AuthorityPublicKeyBytes`. These spell out the conversion in both directions: naming a
+/// key never fails, while recovering the key from a name checks that the bytes are a valid
+/// public key.
+pub trait AuthorityKeyName: Sized {
+    fn to_name(&self) -> AuthorityName;
+    fn from_name(name: &AuthorityName) -> SuiResult<Self>;
+}
+
+impl AuthorityKeyName for AuthorityPublicKey {
+    fn to_name(&self) -> AuthorityName {
+        AuthorityName::from(self)
+    }
+
+    fn from_name(name: &AuthorityName) -> SuiResult<Self> {
+        AuthorityPublicKey::try_from(*name).map_err(|_| {
+            SuiError::KeyConversionError(format!("{name} is not a valid authority public key"))
+        })
+    }
+}
+
 impl AsRef<[u8]> for AuthorityPublicKeyBytes {
     fn as_ref(&self) -> &[u8] {
         &self.0[..]
@@ -414,6 +554,10 @@ pub trait SuiAuthoritySignature {
     fn verify<T>(&self, value: &T, author: AuthorityPublicKeyBytes) -> Result<(), SuiError>
     where
         T: Signable<Vec<u8>>;
+
+    /// Same as `verify`, for a message that is already serialized, e.g. as received over the
+    /// wire.
+    fn verify_bytes(&self, message: &[u8], author: AuthorityPublicKeyBytes) -> SuiResult<()>;
 }
 
 impl SuiAuthoritySignature for AuthoritySignature {
@@ -421,8 +565,7 @@ impl SuiAuthoritySignature for AuthoritySignature {
     where
         T: Signable<Vec<u8>>,
     {
-        let mut message = Vec::new();
-        value.write(&mut message);
+        let message = signable_bytes(value);
         secret.sign(&message)
     }
 
@@ -430,19 +573,22 @@ impl SuiAuthoritySignature for AuthoritySignature {
     where
         T: Signable<Vec<u8>>,
     {
+        // serialize the message (see BCS serialization for determinism)
+        let message = signable_bytes(value);
+        self.verify_bytes(&message, author)
+    }
+
+    fn verify_bytes(&self, message: &[u8], author: AuthorityPublicKeyBytes) -> SuiResult<()> {
         // is this a cryptographically valid public Key?
         let public_key = AuthorityPublicKey::try_from(author).map_err(|_| {
             SuiError::KeyConversionError(
                 "Failed to serialize public key bytes to valid public key".to_string(),
             )
         })?;
-        // serialize the message (see BCS serialization for determinism)
-        let mut message = Vec::new();
-        value.write(&mut message);
 
         // perform cryptographic signature check
         public_key
-            .verify(&message, self)
+            .verify(message, self)
             .map_err(|error| SuiError::InvalidSignature {
                 error: error.to_string(),
             })
@@ -467,6 +613,18 @@ where
     .collect::<Vec<_>>()
 }
 
+/// Generate `num` key pairs from `seed`. The same seed always gives the same key pairs, which
+/// makes benchmarks reproducible. Never use this for keys that hold value.
+pub fn deterministic_key_pairs<KP: KeypairTraits>(num: usize, seed: [u8; 32]) -> Vec<KP>
+where
+    <KP as KeypairTraits>::PubKey: SuiPublicKey,
+{
+    let mut rng = StdRng::from_seed(seed);
+    (0..num)
+        .map(|_| get_key_pair_from_rng(&mut rng).1)
+        .collect()
+}
+
 // TODO: get_key_pair() and get_key_pair_from_bytes() should return KeyPair only.
 // TODO: rename to random_key_pair
 pub fn get_key_pair<KP: KeypairTraits>() -> (SuiAddress, KP)
@@ -501,6 +659,15 @@ pub fn get_authority_key_pair() -> (SuiAddress, AuthorityKeyPair) {
     get_key_pair()
 }
 
+/// Generate an authority keypair along with its proof of possession, bound to `address`.
+pub fn generate_authority_key_with_pop(
+    address: SuiAddress,
+) -> (AuthorityKeyPair, AuthoritySignature) {
+    let (_, keypair) = get_authority_key_pair();
+    let pop = generate_proof_of_possession(&keypair, address);
+    (keypair, pop)
+}
+
 /// Generate a keypair from the specified RNG (useful for testing with seedable rngs).
 pub fn get_key_pair_from_rng<KP: KeypairTraits, R>(csprng: &mut R) -> (SuiAddress, KP)
 where
@@ -695,15 +862,185 @@ impl<'de> Deserialize<'de> for Signature {
 }
 
 // Can refactor this with a library
+/// Byte layouts of a serialized `Signature`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SignatureVersion {
+    /// `flag || signature || public key`. This is the layout `Signature` holds in memory.
+    V1,
+    /// `signature || public key || flag`.
+    V2,
+}
+
 impl Signature {
     pub fn new<T>(value: &T, secret: &dyn signature::Signer<Signature>) -> Signature
     where
         T: Signable<Vec<u8>>,
     {
-        let mut message = Vec::new();
-        value.write(&mut message);
+        let message = signable_bytes(value);
         secret.sign(&message)
     }
+
+    /// Assemble a signature from its components, the inverse of `signature_bytes()` and
+    /// `public_key_bytes()`. Both components must have the lengths `scheme` expects.
+    pub fn from_parts(
+        scheme: SignatureScheme,
+        signature_bytes: &[u8],
+        public_key_bytes: &[u8],
+    ) -> SuiResult<Signature> {
+        let mut bytes = Vec::with_capacity(1 + signature_bytes.len() + public_key_bytes.len());
+        bytes.push(scheme.flag());
+        bytes.extend_from_slice(signature_bytes);
+        bytes.extend_from_slice(public_key_bytes);
+        // Checks the scheme is a user scheme and the lengths of both components.
+        split_signature_bytes(&bytes)?;
+        Ok(<Signature as signature::Signature>::from_bytes(&bytes)?)
+    }
+
+    /// Check that the public key embedded in this signature derives `author`. This only checks
+    /// that the (signature, address) pair is self-consistent; the signature itself is not
+    /// verified.
+    pub fn matches_address(&self, author: SuiAddress) -> bool {
+        self.embedded_address() == Some(author)
+    }
+
+    /// Verify this signature over `value` for whichever of the `allowed` addresses its public
+    /// key derives, and return that address.
+    pub fn verify_any<T>(&self, value: &T, allowed: &[SuiAddress]) -> SuiResult<SuiAddress>
+    where
+        T: Signable<Vec<u8>>,
+    {
+        let author = self
+            .embedded_address()
+            .filter(|address| allowed.contains(address))
+            .ok_or_else(|| SuiError::IncorrectSigner {
+                error: "Signature is not from any of the allowed signers".to_string(),
+            })?;
+        self.verify(value, author)?;
+        Ok(author)
+    }
+
+    /// Same as `verify`, for a caller that already has the expected public key rather than
+    /// the address. The embedded key is compared to `expected` as bytes, so neither the key
+    /// nor the address has to be derived from the signature.
+    pub fn verify_with_public_key<T>(&self, value: &T, expected: &PublicKey) -> SuiResult<()>
+    where
+        T: Signable<Vec<u8>>,
+    {
+        fn verify_inner<S: SuiSignatureInner>(
+            signature: &S,
+            message: &[u8],
+            pk: &S::PubKey,
+        ) -> SuiResult<()> {
+            fp_ensure!(
+                signature.public_key_bytes() == pk.as_ref(),
+                SuiError::IncorrectSigner {
+                    error: "Signature was not made with the expected public key".to_string(),
+                }
+            );
+            signature.check_canonical()?;
+            let sig = S::Sig::from_bytes(signature.signature_bytes()).map_err(|err| {
+                SuiError::InvalidSignature {
+                    error: err.to_string(),
+                }
+            })?;
+            pk.verify(message, &sig)
+                .map_err(|err| SuiError::InvalidSignature {
+                    error: err.to_string(),
+                })
+        }
+
+        let message = signable_bytes(value);
+        match (self, expected) {
+            (Signature::Ed25519SuiSignature(sig), PublicKey::Ed25519KeyPair(pk)) => {
+                verify_inner(sig, &message, pk)
+            }
+            (Signature::Secp256k1SuiSignature(sig), PublicKey::Secp256k1KeyPair(pk)) => {
+                verify_inner(sig, &message, pk)
+            }
+            _ => Err(SuiError::IncorrectSigner {
+                error: format!(
+                    "Signature scheme {} does not match the expected public key",
+                    self.scheme().to_string()
+                ),
+            }),
+        }
+    }
+
+    /// The address derived from the public key embedded in this signature, if it is valid.
+    fn embedded_address(&self) -> Option<SuiAddress> {
+        fn embedded_address<S: SuiSignatureInner>(sig: &S) -> Option<SuiAddress> {
+            let pk = S::PubKey::from_bytes(sig.public_key_bytes()).ok()?;
+            Some(SuiAddress::from(&pk))
+        }
+
+        match self {
+            Signature::Ed25519SuiSignature(sig) => embedded_address(sig),
+            Signature::Secp256k1SuiSignature(sig) => embedded_address(sig),
+        }
+    }
+
+    /// Serialize this signature in the byte layout of `to_version`. This only moves bytes
+    /// around, the signature itself is not recomputed.
+    pub fn reencode(&self, to_version: SignatureVersion) -> SuiResult<Vec<u8>> {
+        Self::transform_layout(self.as_ref(), SignatureVersion::V1, to_version)
+    }
+
+    /// Parse a signature serialized in the byte layout of `version`.
+    pub fn from_versioned_bytes(bytes: &[u8], version: SignatureVersion) -> SuiResult<Signature> {
+        let bytes = Self::transform_layout(bytes, version, SignatureVersion::V1)?;
+        <Signature as signature::Signature>::from_bytes(&bytes).map_err(|err| {
+            SuiError::InvalidSignature {
+                error: err.to_string(),
+            }
+        })
+    }
+
+    fn transform_layout(
+        bytes: &[u8],
+        from: SignatureVersion,
+        to: SignatureVersion,
+    ) -> SuiResult<Vec<u8>> {
+        let (flag, body) = match from {
+            SignatureVersion::V1 => bytes.split_first(),
+            SignatureVersion::V2 => bytes.split_last(),
+        }
+        .ok_or_else(|| SuiError::InvalidSignature {
+            error: "Empty signature".to_string(),
+        })?;
+
+        // Only transform bytes we can fully account for, so the result is lossless.
+        let expected_length = if *flag == Ed25519SuiSignature::SCHEME.flag() {
+            Ed25519SuiSignature::LENGTH
+        } else if *flag == Secp256k1SuiSignature::SCHEME.flag() {
+            Secp256k1SuiSignature::LENGTH
+        } else {
+            fp_bail!(SuiError::InvalidSignature {
+                error: format!("Unknown signature scheme flag {flag}"),
+            });
+        };
+        fp_ensure!(
+            bytes.len() == expected_length,
+            SuiError::InvalidSignature {
+                error: format!(
+                    "Expected a signature of {expected_length} bytes, got {}",
+                    bytes.len()
+                ),
+            }
+        );
+
+        let mut result = Vec::with_capacity(bytes.len());
+        match to {
+            SignatureVersion::V1 => {
+                result.push(*flag);
+                result.extend_from_slice(body);
+            }
+            SignatureVersion::V2 => {
+                result.extend_from_slice(body);
+                result.push(*flag);
+            }
+        }
+        Ok(result)
+    }
 }
 
 impl AsRef<[u8]> for Signature {
@@ -720,13 +1057,9 @@ impl signature::Signature for Signature {
         match bytes.first() {
             Some(x) => {
                 if x == &Ed25519SuiSignature::SCHEME.flag() {
-                    Ok(<Ed25519SuiSignature as ToFromBytes>::from_bytes(bytes)
-                        .map_err(|_| signature::Error::new())?
-                        .into())
+                    Ok(<Ed25519SuiSignature as ToFromBytes>::from_bytes(bytes)?.into())
                 } else if x == &Secp256k1SuiSignature::SCHEME.flag() {
-                    Ok(<Secp256k1SuiSignature as ToFromBytes>::from_bytes(bytes)
-                        .map_err(|_| signature::Error::new())?
-                        .into())
+                    Ok(<Secp256k1SuiSignature as ToFromBytes>::from_bytes(bytes)?.into())
                 } else {
                     Err(signature::Error::new())
                 }
@@ -746,6 +1079,266 @@ impl std::fmt::Debug for Signature {
     }
 }
 
+/// A concise form for logs, `<scheme>:<signer address>`, that leaves out the signature and key
+/// bytes printed by `Debug`.
+impl std::fmt::Display for Signature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let scheme = self.scheme().to_string();
+        match self.embedded_address() {
+            Some(address) => write!(f, "{scheme}:{address}"),
+            None => write!(f, "{scheme}:<invalid public key>"),
+        }
+    }
+}
+
+/// Count how many signatures of each scheme appear in `sigs`, e.g. to enforce a policy that
+/// requires at least one signer of a particular scheme.
+pub fn count_schemes(sigs: &[Signature]) -> BTreeMap<SignatureScheme, usize> {
+    let mut counts = BTreeMap::new();
+    for sig in sigs {
+        *counts.entry(sig.scheme()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// A set of independent account signatures, e.g. collected from several senders, kept in a
+/// form that is compact to store. Signatures are grouped by scheme, and each is serialized
+/// without its scheme flag or a length prefix.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "GroupedSignatures", into = "GroupedSignatures")]
+pub struct SignatureSet {
+    // Ordered by scheme, then by insertion, so that the order survives serialization.
+    signatures: Vec<Signature>,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+struct GroupedSignatures {
+    #[serde_as(as = "Readable<Base64, Bytes>")]
+    ed25519: Vec<u8>,
+    #[serde_as(as = "Readable<Base64, Bytes>")]
+    secp256k1: Vec<u8>,
+}
+
+impl SignatureSet {
+    pub fn new(signatures: impl IntoIterator<Item = Signature>) -> Self {
+        let mut set = Self::default();
+        for signature in signatures {
+            set.insert(signature);
+        }
+        set
+    }
+
+    /// Add `signature` to the set, returning whether it was not already in it.
+    pub fn insert(&mut self, signature: Signature) -> bool {
+        if self.signatures.contains(&signature) {
+            return false;
+        }
+        let flag = signature.scheme().flag();
+        let position = self
+            .signatures
+            .partition_point(|existing| existing.scheme().flag() <= flag);
+        self.signatures.insert(position, signature);
+        true
+    }
+
+    pub fn signatures(&self) -> &[Signature] {
+        &self.signatures
+    }
+
+    pub fn len(&self) -> usize {
+        self.signatures.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.signatures.is_empty()
+    }
+
+    /// Verify that for each `(message, author)` pair, the set holds a valid signature by
+    /// `author` over the raw `message` bytes. An author may sign several messages, so every
+    /// signature by `author` is tried against the message.
+    pub fn verify_all(&self, messages: &[(&[u8], SuiAddress)]) -> SuiResult<()> {
+        for (message, author) in messages {
+            let mut result = Err(SuiError::IncorrectSigner {
+                error: format!("No signature by {author} in the set"),
+            });
+            for signature in self
+                .signatures
+                .iter()
+                .filter(|signature| signature.matches_address(*author))
+            {
+                result = verify_signature_bytes(signature, message, *author);
+                if result.is_ok() {
+                    break;
+                }
+            }
+            result?;
+        }
+        Ok(())
+    }
+}
+
+/// Verify `signature` by `author` over the raw `message` bytes.
+fn verify_signature_bytes(
+    signature: &Signature,
+    message: &[u8],
+    author: SuiAddress,
+) -> SuiResult<()> {
+    fn verify_bytes<S: SuiSignatureInner>(
+        signature: &S,
+        message: &[u8],
+        author: SuiAddress,
+    ) -> SuiResult<()> {
+        let (sig, pk) = signature.get_verification_inputs(author)?;
+        pk.verify(message, &sig)
+            .map_err(|e| SuiError::InvalidSignature {
+                error: e.to_string(),
+            })
+    }
+
+    match signature {
+        Signature::Ed25519SuiSignature(sig) => verify_bytes(sig, message, author),
+        Signature::Secp256k1SuiSignature(sig) => verify_bytes(sig, message, author),
+    }
+}
+
+/// Verify independent account signatures, each by its author over raw message bytes, across
+/// the rayon thread pool. Items are grouped by scheme so that each pass runs a single
+/// verification routine, and the result of each item is returned at its index.
+pub fn verify_user_signatures_parallel(
+    items: &[(Signature, Vec<u8>, SuiAddress)],
+) -> Vec<SuiResult<()>> {
+    let mut by_scheme: BTreeMap<u8, Vec<usize>> = BTreeMap::new();
+    for (idx, (signature, _, _)) in items.iter().enumerate() {
+        by_scheme
+            .entry(signature.scheme().flag())
+            .or_default()
+            .push(idx);
+    }
+
+    let mut results: Vec<SuiResult<()>> = vec![Ok(()); items.len()];
+    for indices in by_scheme.values() {
+        let verified: Vec<_> = indices
+            .par_iter()
+            .map(|idx| {
+                let (signature, message, author) = &items[*idx];
+                verify_signature_bytes(signature, message, *author)
+            })
+            .collect();
+        for (idx, result) in indices.iter().zip(verified) {
+            results[*idx] = result;
+        }
+    }
+    results
+}
+
+impl From<SignatureSet> for GroupedSignatures {
+    fn from(set: SignatureSet) -> Self {
+        let mut grouped = GroupedSignatures {
+            ed25519: Vec::new(),
+            secp256k1: Vec::new(),
+        };
+        for signature in &set.signatures {
+            let group = match signature {
+                Signature::Ed25519SuiSignature(_) => &mut grouped.ed25519,
+                Signature::Secp256k1SuiSignature(_) => &mut grouped.secp256k1,
+            };
+            // The flag is implied by the group.
+            group.extend_from_slice(&signature.as_ref()[1..]);
+        }
+        grouped
+    }
+}
+
+impl TryFrom<GroupedSignatures> for SignatureSet {
+    type Error = SuiError;
+
+    fn try_from(grouped: GroupedSignatures) -> Result<Self, Self::Error> {
+        let groups = [
+            (
+                Ed25519SuiSignature::SCHEME,
+                Ed25519SuiSignature::LENGTH,
+                grouped.ed25519,
+            ),
+            (
+                Secp256k1SuiSignature::SCHEME,
+                Secp256k1SuiSignature::LENGTH,
+                grouped.secp256k1,
+            ),
+        ];
+        let mut set = SignatureSet::default();
+        for (scheme, length, bytes) in groups {
+            let chunks = bytes.chunks_exact(length - 1);
+            fp_ensure!(
+                chunks.remainder().is_empty(),
+                SuiError::InvalidSignature {
+                    error: format!("Truncated {} signature", scheme.to_string()),
+                }
+            );
+            for chunk in chunks {
+                let mut flagged = Vec::with_capacity(length);
+                flagged.push(scheme.flag());
+                flagged.extend_from_slice(chunk);
+                let signature =
+                    <Signature as signature::Signature>::from_bytes(&flagged).map_err(|e| {
+                        SuiError::InvalidSignature {
+                            error: e.to_string(),
+                        }
+                    })?;
+                set.insert(signature);
+            }
+        }
+        Ok(set)
+    }
+}
+
+/// Split a flagged signature, laid out as `flag || signature || public key`, into its
+/// scheme, signature bytes and public key bytes, checking the lengths expected by the scheme.
+pub fn split_signature_bytes(bytes: &[u8]) -> SuiResult<(SignatureScheme, &[u8], &[u8])> {
+    let invalid = |error: &str| SuiError::InvalidSignature {
+        error: error.to_string(),
+    };
+    let (scheme, sig_len, pk_len) = match bytes.first() {
+        Some(x) if x == &Ed25519SuiSignature::SCHEME.flag() => (
+            Ed25519SuiSignature::SCHEME,
+            Ed25519Signature::LENGTH,
+            Ed25519PublicKey::LENGTH,
+        ),
+        Some(x) if x == &Secp256k1SuiSignature::SCHEME.flag() => (
+            Secp256k1SuiSignature::SCHEME,
+            Secp256k1Signature::LENGTH,
+            Secp256k1PublicKey::LENGTH,
+        ),
+        Some(_) => return Err(invalid("Invalid signature scheme flag")),
+        None => return Err(invalid("Empty signature bytes")),
+    };
+    fp_ensure!(
+        bytes.len() == 1 + sig_len + pk_len,
+        SuiError::InvalidSignatureLength {
+            scheme,
+            expected: 1 + sig_len + pk_len,
+            got: bytes.len(),
+        }
+    );
+    let (sig, pk) = split_flagged_bytes(bytes, sig_len);
+    Ok((scheme, sig, pk))
+}
+
+/// The error for `got` bytes given to the `from_bytes` of a flagged signature, carrying a
+/// `SuiError::InvalidSignatureLength` that converting it back to a `SuiError` recovers.
+fn invalid_length<S: SuiSignatureInner>(got: usize) -> signature::Error {
+    signature::Error::from_source(SuiError::InvalidSignatureLength {
+        scheme: S::SCHEME,
+        expected: S::LENGTH,
+        got,
+    })
+}
+
+/// The offsets shared by all flagged signatures; `bytes` must already have the right length.
+fn split_flagged_bytes(bytes: &[u8], sig_len: usize) -> (&[u8], &[u8]) {
+    bytes[1..].split_at(sig_len)
+}
+
 //
 // BLS Port
 //
@@ -786,7 +1379,7 @@ impl AsRef<[u8]> for Ed25519SuiSignature {
 impl signature::Signature for Ed25519SuiSignature {
     fn from_bytes(bytes: &[u8]) -> Result<Self, signature::Error> {
         if bytes.len() != Self::LENGTH {
-            return Err(signature::Error::new());
+            return Err(invalid_length::<Self>(bytes.len()));
         }
         let mut sig_bytes = [0; Self::LENGTH];
         sig_bytes.copy_from_slice(bytes);
@@ -818,8 +1411,27 @@ impl SuiSignatureInner for Secp256k1SuiSignature {
     type PubKey = Secp256k1PublicKey;
     type KeyPair = Secp256k1KeyPair;
     const LENGTH: usize = Secp256k1PublicKey::LENGTH + Secp256k1Signature::LENGTH + 1;
+
+    fn check_canonical(&self) -> SuiResult<()> {
+        // The signature is laid out as r || s || recovery id, with s big-endian.
+        let s = &self.signature_bytes()[32..64];
+        fp_ensure!(
+            s <= &SECP256K1_HALF_ORDER[..],
+            SuiError::NonCanonicalSignature {
+                error: "secp256k1 signature has a high S value".to_string(),
+            }
+        );
+        Ok(())
+    }
 }
 
+/// Half the order of the secp256k1 curve, big-endian. For every valid signature (r, s), the
+/// signature (r, n - s) is valid too, so only the one with s at most this value is accepted.
+const SECP256K1_HALF_ORDER: [u8; 32] = [
+    0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0x5d, 0x57, 0x6e, 0x73, 0x57, 0xa4, 0x50, 0x1d, 0xdf, 0xe9, 0x2f, 0x46, 0x68, 0x1b, 0x20, 0xa0,
+];
+
 // impl Default for Secp256k1SuiSignature {
 //     []
 // }
@@ -837,7 +1449,7 @@ impl AsRef<[u8]> for Secp256k1SuiSignature {
 impl signature::Signature for Secp256k1SuiSignature {
     fn from_bytes(bytes: &[u8]) -> Result<Self, signature::Error> {
         if bytes.len() != Self::LENGTH {
-            return Err(signature::Error::new());
+            return Err(invalid_length::<Self>(bytes.len()));
         }
         let mut sig_bytes = [0; Self::LENGTH];
         sig_bytes.copy_from_slice(bytes);
@@ -863,6 +1475,12 @@ pub trait SuiSignatureInner: Sized + signature::Signature + PartialEq + Eq + Has
     const LENGTH: usize = Self::Sig::LENGTH + Self::PubKey::LENGTH + 1;
     const SCHEME: SignatureScheme = Self::PubKey::SIGNATURE_SCHEME;
 
+    /// Reject signatures that are valid but not in the canonical form of their scheme, so that a
+    /// signature cannot be changed without invalidating it.
+    fn check_canonical(&self) -> SuiResult<()> {
+        Ok(())
+    }
+
     fn get_verification_inputs(&self, author: SuiAddress) -> SuiResult<(Self::Sig, Self::PubKey)> {
         // Is this signature emitted by the expected author?
         let bytes = self.public_key_bytes();
@@ -876,6 +1494,8 @@ pub trait SuiSignatureInner: Sized + signature::Signature + PartialEq + Eq + Has
             });
         }
 
+        self.check_canonical()?;
+
         // deserialize the signature
         let signature = Self::Sig::from_bytes(self.signature_bytes()).map_err(|err| {
             SuiError::InvalidSignature {
@@ -918,6 +1538,38 @@ pub trait SuiSignature: Sized + signature::Signature {
     where
         T: Signable<Vec<u8>>;
 
+    /// Same as `verify`, but the cryptographic check itself is done by `verifier`.
+    fn verify_with<T, V>(&self, value: &T, author: SuiAddress, verifier: &V) -> SuiResult<()>
+    where
+        T: Signable<Vec<u8>>,
+        V: SignatureVerifier;
+
+    /// Same as `verify`, but first rejects signatures whose scheme is not in `allowed`,
+    /// without doing any cryptography. This is for protocol versions deprecating a scheme.
+    fn verify_with_allowed_schemes<T>(
+        &self,
+        value: &T,
+        author: SuiAddress,
+        allowed: &[SignatureScheme],
+    ) -> SuiResult<()>
+    where
+        T: Signable<Vec<u8>>;
+
+    /// Verify a signature over the plain BCS bytes of `value`, without the `TypeName::` tag
+    /// that `Signable::write` prepends. This is only for compatibility with external tools
+    /// that sign raw BCS; signatures produced by Sui must always be checked with `verify`,
+    /// since the tag is what keeps a signature over one type from being replayed as another.
+    fn verify_untagged<T>(&self, value: &T, author: SuiAddress) -> SuiResult<()>
+    where
+        T: Serialize;
+
+    /// Verify a signature by `author` over the SHA3-256 digest of everything `reader` yields.
+    /// The message is fed to the hash as it is read, so it never has to be held in memory;
+    /// this is why the signature must be made over the digest rather than the message itself.
+    fn verify_reader<R>(&self, reader: R, author: SuiAddress) -> SuiResult<()>
+    where
+        R: std::io::Read;
+
     fn add_to_verification_obligation_or_verify(
         &self,
         author: SuiAddress,
@@ -930,14 +1582,64 @@ impl<S: SuiSignatureInner + Sized> SuiSignature for S {
     fn verify<T>(&self, value: &T, author: SuiAddress) -> SuiResult<()>
     where
         T: Signable<Vec<u8>>,
+    {
+        self.verify_with(value, author, &FastCryptoVerifier)
+    }
+
+    fn verify_with<T, V>(&self, value: &T, author: SuiAddress, verifier: &V) -> SuiResult<()>
+    where
+        T: Signable<Vec<u8>>,
+        V: SignatureVerifier,
     {
         // Currently done twice - can we improve on this?;
         let (sig, pk) = &self.get_verification_inputs(author)?;
-        let mut message = Vec::new();
-        value.write(&mut message);
+        let message = signable_bytes(value);
+        verifier.verify(pk, &message[..], sig)
+    }
+
+    fn verify_with_allowed_schemes<T>(
+        &self,
+        value: &T,
+        author: SuiAddress,
+        allowed: &[SignatureScheme],
+    ) -> SuiResult<()>
+    where
+        T: Signable<Vec<u8>>,
+    {
+        let scheme = self.scheme();
+        fp_ensure!(
+            allowed.contains(&scheme),
+            SuiError::DisallowedSignatureScheme { scheme }
+        );
+        self.verify(value, author)
+    }
+
+    fn verify_untagged<T>(&self, value: &T, author: SuiAddress) -> SuiResult<()>
+    where
+        T: Serialize,
+    {
+        let (sig, pk) = &self.get_verification_inputs(author)?;
+        let message = bcs::to_bytes(value).map_err(|err| SuiError::InvalidSignature {
+            error: err.to_string(),
+        })?;
         pk.verify(&message[..], sig)
-            .map_err(|_| SuiError::InvalidSignature {
-                error: "hello".to_string(),
+            .map_err(|err| SuiError::InvalidSignature {
+                error: err.to_string(),
+            })
+    }
+
+    fn verify_reader<R>(&self, mut reader: R, author: SuiAddress) -> SuiResult<()>
+    where
+        R: std::io::Read,
+    {
+        let (sig, pk) = &self.get_verification_inputs(author)?;
+        let mut digest = Sha3_256::default();
+        std::io::copy(&mut reader, &mut digest).map_err(|err| SuiError::InvalidSignature {
+            error: format!("Failed to read the signed message: {err}"),
+        })?;
+        pk.verify(&digest.finalize()[..], sig)
+            .map_err(|err| SuiError::InvalidSignature {
+                error: err.to_string(),
             })
     }
 
@@ -952,20 +1654,24 @@ impl<S: SuiSignatureInner + Sized> SuiSignature for S {
             Ok(_) => Ok(()),
             Err(err) => {
                 let msg = &obligation.messages[idx][..];
-                pk.verify(msg, &sig)
-                    .map_err(|_| SuiError::InvalidSignature {
+                pk.verify(msg, &sig).map_err(|_| {
+                    if let Some(recorder) = &obligation.fault_recorder {
+                        recorder.record(author);
+                    }
+                    SuiError::InvalidSignature {
                         error: err.to_string(),
-                    })
+                    }
+                })
             }
         }
     }
 
     fn signature_bytes(&self) -> &[u8] {
-        &self.as_ref()[1..1 + S::Sig::LENGTH]
+        split_flagged_bytes(self.as_ref(), S::Sig::LENGTH).0
     }
 
     fn public_key_bytes(&self) -> &[u8] {
-        &self.as_ref()[S::Sig::LENGTH + 1..]
+        split_flagged_bytes(self.as_ref(), S::Sig::LENGTH).1
     }
 
     fn scheme(&self) -> SignatureScheme {
@@ -1042,11 +1748,19 @@ impl AuthoritySignInfoTrait for AuthoritySignInfo {
         let weight = committee.weight(&self.authority);
         fp_ensure!(weight > 0, SuiError::UnknownSigner);
 
+        let public_key = committee.public_key(&self.authority)?;
+        if obligation.fault_recorder.is_some() {
+            obligation
+                .authority_signatures
+                .get_mut(message_index)
+                .ok_or(SuiError::InvalidAddress)?
+                .push((self.authority, public_key.clone(), self.signature.clone()));
+        }
         obligation
             .public_keys
             .get_mut(message_index)
             .ok_or(SuiError::InvalidAddress)?
-            .push(committee.public_key(&self.authority)?);
+            .push(public_key.clone());
         obligation
             .signatures
             .get_mut(message_index)
@@ -1059,6 +1773,53 @@ impl AuthoritySignInfoTrait for AuthoritySignInfo {
     }
 }
 
+impl AuthoritySignInfo {
+    /// Build a sign info from signature bytes produced outside of this process (e.g. by an
+    /// HSM), rather than by signing with an in-process `Signer`.
+    pub fn new_from_raw(
+        epoch: EpochId,
+        authority: AuthorityName,
+        sig_bytes: &[u8],
+    ) -> SuiResult<AuthoritySignInfo> {
+        fp_ensure!(
+            sig_bytes.len() == <AuthoritySignature as Authenticator>::LENGTH,
+            SuiError::InvalidSignature {
+                error: format!(
+                    "Expected {} signature bytes, got {}",
+                    <AuthoritySignature as Authenticator>::LENGTH,
+                    sig_bytes.len()
+                ),
+            }
+        );
+        let signature =
+            <AuthoritySignature as ToFromBytes>::from_bytes(sig_bytes).map_err(|error| {
+                SuiError::InvalidSignature {
+                    error: error.to_string(),
+                }
+            })?;
+        Ok(AuthoritySignInfo {
+            epoch,
+            authority,
+            signature,
+        })
+    }
+
+    /// Check the signature against `public_key` directly, without consulting a committee
+    /// for the authority's key or voting weight (e.g. while bootstrapping a committee).
+    pub fn verify_against_key<T: Signable<Vec<u8>>>(
+        &self,
+        data: &T,
+        public_key: &AuthorityPublicKey,
+    ) -> SuiResult<()> {
+        let message = signable_bytes(data);
+        public_key
+            .verify(&message, &self.signature)
+            .map_err(|error| SuiError::InvalidSignature {
+                error: error.to_string(),
+            })
+    }
+}
+
 impl Hash for AuthoritySignInfo {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.epoch.hash(state);
@@ -1102,6 +1863,18 @@ pub struct AuthorityQuorumSignInfo<const STRONG_THRESHOLD: bool> {
     pub signers_map: RoaringBitmap,
 }
 
+/// The debug representation of an `AuthorityQuorumSignInfo`, see
+/// `AuthorityQuorumSignInfo::to_debug`. This is never used on the wire, where the signers are
+/// always sent as a bitmap.
+#[serde_as]
+#[derive(Clone, Debug, Serialize)]
+pub struct AuthorityQuorumSignInfoDebug {
+    pub epoch: EpochId,
+    #[serde_as(as = "AggrAuthSignature")]
+    pub signature: AggregateAuthoritySignature,
+    pub signers: Vec<AuthorityName>,
+}
+
 pub type AuthorityStrongQuorumSignInfo = AuthorityQuorumSignInfo<true>;
 pub type AuthorityWeakQuorumSignInfo = AuthorityQuorumSignInfo<false>;
 
@@ -1139,8 +1912,6 @@ impl<const STRONG_THRESHOLD: bool> AuthoritySignInfoTrait
             }
         );
 
-        let mut weight = 0;
-
         // Create obligations for the committee signatures
         obligation
             .signatures
@@ -1151,23 +1922,13 @@ impl<const STRONG_THRESHOLD: bool> AuthoritySignInfoTrait
                 error: "Signature Aggregation failed".to_string(),
             })?;
 
-        let selected_public_keys = obligation
+        let signers = committee.signer_set(&self.signers_map)?;
+        obligation
             .public_keys
             .get_mut(message_index)
-            .ok_or(SuiError::InvalidAuthenticator)?;
-
-        for authority_index in self.signers_map.iter() {
-            let authority = committee
-                .authority_by_index(authority_index)
-                .ok_or(SuiError::UnknownSigner)?;
-
-            // Update weight.
-            let voting_rights = committee.weight(authority);
-            fp_ensure!(voting_rights > 0, SuiError::UnknownSigner);
-            weight += voting_rights;
-
-            selected_public_keys.push(committee.public_key(authority)?);
-        }
+            .ok_or(SuiError::InvalidAuthenticator)?
+            .extend(signers.public_keys.into_iter().cloned());
+        let weight = signers.weight;
 
         let threshold = if STRONG_THRESHOLD {
             committee.quorum_threshold()
@@ -1227,6 +1988,59 @@ impl<const STRONG_THRESHOLD: bool> AuthorityQuorumSignInfo<STRONG_THRESHOLD> {
         })
     }
 
+    /// The names of the signers, in committee order.
+    pub fn signers_as_names(&self, committee: &Committee) -> SuiResult<Vec<AuthorityName>> {
+        self.authorities(committee)
+            .map(|authority| authority.copied())
+            .collect()
+    }
+
+    /// A copy of this quorum signature listing the signers by name, which is easier to read
+    /// than the bitmap of committee indices when serialized for debugging.
+    pub fn to_debug(&self, committee: &Committee) -> SuiResult<AuthorityQuorumSignInfoDebug> {
+        Ok(AuthorityQuorumSignInfoDebug {
+            epoch: self.epoch,
+            signature: self.signature.clone(),
+            signers: self.signers_as_names(committee)?,
+        })
+    }
+
+    /// Ensure none of the `forbidden` authorities is among the signers, for a node that must
+    /// not accept certificates carrying the signature of a sanctioned subset of the committee.
+    pub fn excludes(&self, forbidden: &[AuthorityName], committee: &Committee) -> SuiResult<()> {
+        for signer in self.authorities(committee) {
+            let signer = signer?;
+            fp_ensure!(
+                !forbidden.contains(signer),
+                SuiError::ForbiddenSigner { signer: *signer }
+            );
+        }
+        Ok(())
+    }
+
+    /// A weaker form of `excludes`: the `forbidden` authorities may have signed, as long as
+    /// the remaining signers meet the threshold without them.
+    pub fn holds_without(
+        &self,
+        forbidden: &[AuthorityName],
+        committee: &Committee,
+    ) -> SuiResult<()> {
+        let mut weight = 0;
+        for signer in self.authorities(committee) {
+            let signer = signer?;
+            if !forbidden.contains(signer) {
+                weight += committee.weight(signer);
+            }
+        }
+        let threshold = if STRONG_THRESHOLD {
+            committee.quorum_threshold()
+        } else {
+            committee.validity_threshold()
+        };
+        fp_ensure!(weight >= threshold, SuiError::CertificateRequiresQuorum);
+        Ok(())
+    }
+
     pub fn len(&self) -> u64 {
         self.signers_map.len()
     }
@@ -1264,6 +2078,15 @@ mod private {
 pub trait Signable<W> {
     fn write(&self, writer: &mut W);
 }
+
+/// The exact bytes that get signed for `value`: the type name tag followed by its BCS
+/// serialization. Useful to compare against what a third party signed when debugging
+/// signature failures.
+pub fn signable_bytes<T: Signable<Vec<u8>>>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.write(&mut bytes);
+    bytes
+}
 pub trait SignableBytes
 where
     Self: Sized,
@@ -1326,11 +2149,20 @@ where
     }
 }
 
-pub fn sha3_hash<S: Signable<Sha3_256>>(signable: &S) -> [u8; 32] {
-    let mut digest = Sha3_256::default();
+/// Hash a signable value with the digest algorithm `H`. This lets alternative digests be
+/// tried out without touching the call sites, which use `sha3_hash` for the current default.
+pub fn hash<H, S>(signable: &S) -> digest::Output<H>
+where
+    H: Digest + std::io::Write,
+    S: Signable<H>,
+{
+    let mut digest = H::new();
     signable.write(&mut digest);
-    let hash = digest.finalize();
-    hash.into()
+    digest.finalize()
+}
+
+pub fn sha3_hash<S: Signable<Sha3_256>>(signable: &S) -> [u8; 32] {
+    hash::<Sha3_256, S>(signable).into()
 }
 
 //
@@ -1364,11 +2196,98 @@ impl ToObligationSignature for AuthoritySignature {
 impl ToObligationSignature for Secp256k1Signature {}
 impl ToObligationSignature for Ed25519Signature {}
 
+/// The backend that performs the cryptographic part of signature verification. Parsing the
+/// signature and checking that it comes from the expected author stay outside of it, so an
+/// implementation only has to decide whether a signature is valid for a message.
+pub trait SignatureVerifier {
+    /// Verify a single signature by `public_key` over `message`.
+    fn verify<P: VerifyingKey>(
+        &self,
+        public_key: &P,
+        message: &[u8],
+        signature: &P::Sig,
+    ) -> SuiResult<()>;
+
+    /// Verify a batch of aggregated authority signatures, where `signatures[i]` is signed by
+    /// `public_keys[i]` over `messages[i]`.
+    fn verify_aggregates(
+        &self,
+        signatures: &[&AggregateAuthoritySignature],
+        public_keys: Vec<std::slice::Iter<'_, AuthorityPublicKey>>,
+        messages: &[&[u8]],
+    ) -> SuiResult<()>;
+}
+
+/// The default `SignatureVerifier`, backed by fastcrypto.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FastCryptoVerifier;
+
+impl SignatureVerifier for FastCryptoVerifier {
+    fn verify<P: VerifyingKey>(
+        &self,
+        public_key: &P,
+        message: &[u8],
+        signature: &P::Sig,
+    ) -> SuiResult<()> {
+        public_key
+            .verify(message, signature)
+            .map_err(|e| SuiError::InvalidSignature {
+                error: e.to_string(),
+            })
+    }
+
+    fn verify_aggregates(
+        &self,
+        signatures: &[&AggregateAuthoritySignature],
+        public_keys: Vec<std::slice::Iter<'_, AuthorityPublicKey>>,
+        messages: &[&[u8]],
+    ) -> SuiResult<()> {
+        AggregateAuthoritySignature::batch_verify(signatures, public_keys, messages).map_err(
+            |error| SuiError::InvalidSignature {
+                error: format!("{error}"),
+            },
+        )
+    }
+}
+
+/// Counts, per signer, the individual signatures that failed verification, as input for a
+/// future slashing mechanism. Authorities are keyed by the address of their public key.
+#[derive(Debug, Default)]
+pub struct SignatureFaultRecorder {
+    faults: parking_lot::Mutex<BTreeMap<SuiAddress, u64>>,
+}
+
+impl SignatureFaultRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an invalid signature produced by `signer`.
+    pub fn record(&self, signer: SuiAddress) {
+        *self.faults.lock().entry(signer).or_default() += 1;
+    }
+
+    /// Record an invalid signature produced by the authority `name`.
+    pub fn record_authority(&self, name: &AuthorityName) {
+        self.record(SuiAddress::from(name));
+    }
+
+    /// The number of faults recorded so far for each signer.
+    pub fn snapshot(&self) -> BTreeMap<SuiAddress, u64> {
+        self.faults.lock().clone()
+    }
+}
+
 #[derive(Default)]
 pub struct VerificationObligation {
     pub messages: Vec<Vec<u8>>,
     pub signatures: Vec<AggregateAuthoritySignature>,
     pub public_keys: Vec<Vec<AuthorityPublicKey>>,
+    /// Where to report signatures that fail individual verification, if anywhere.
+    pub fault_recorder: Option<Arc<SignatureFaultRecorder>>,
+    /// The individual authority signatures added for each message, kept only with a fault
+    /// recorder to find the authorities at fault when the aggregated check fails.
+    pub authority_signatures: Vec<Vec<(AuthorityName, AuthorityPublicKey, AuthoritySignature)>>,
 }
 
 impl VerificationObligation {
@@ -1378,17 +2297,23 @@ impl VerificationObligation {
         }
     }
 
+    /// Report signatures of this obligation that fail verification to `recorder`.
+    pub fn with_fault_recorder(mut self, recorder: Arc<SignatureFaultRecorder>) -> Self {
+        self.fault_recorder = Some(recorder);
+        self
+    }
+
     /// Add a new message to the list of messages to be verified.
     /// Returns the index of the message.
     pub fn add_message<T>(&mut self, message_value: &T) -> usize
     where
         T: Signable<Vec<u8>>,
     {
-        let mut message = Vec::new();
-        message_value.write(&mut message);
+        let message = signable_bytes(message_value);
 
         self.signatures.push(AggregateAuthoritySignature::default());
         self.public_keys.push(Vec::new());
+        self.authority_signatures.push(Vec::new());
         self.messages.push(message);
         self.messages.len() - 1
     }
@@ -1423,19 +2348,42 @@ impl VerificationObligation {
     }
 
     pub fn verify_all(self) -> SuiResult<()> {
-        AggregateAuthoritySignature::batch_verify(
+        self.verify_all_with(&FastCryptoVerifier)
+    }
+
+    pub fn verify_all_with<V: SignatureVerifier>(self, verifier: &V) -> SuiResult<()> {
+        let result = verifier.verify_aggregates(
             &self.signatures.iter().collect::<Vec<_>>()[..],
             self.public_keys
                 .iter()
                 .map(|x| x.iter())
                 .collect::<Vec<_>>(),
             &self.messages.iter().map(|x| &x[..]).collect::<Vec<_>>()[..],
-        )
-        .map_err(|error| SuiError::InvalidSignature {
-            error: format!("{error}"),
-        })?;
+        );
+        if result.is_err() {
+            self.record_authority_faults(verifier);
+        }
+        result
+    }
 
-        Ok(())
+    /// Verify the individual authority signatures one by one, and record the authorities whose
+    /// signature is invalid. Signatures that were already aggregated, as in certificates,
+    /// cannot be attributed.
+    fn record_authority_faults<V: SignatureVerifier>(&self, verifier: &V) {
+        let recorder = match &self.fault_recorder {
+            Some(recorder) => recorder,
+            None => return,
+        };
+        for (message, signers) in self.messages.iter().zip(&self.authority_signatures) {
+            for (name, public_key, signature) in signers {
+                if verifier
+                    .verify(public_key, &message[..], signature)
+                    .is_err()
+                {
+                    recorder.record_authority(name);
+                }
+            }
+        }
     }
 }
 
@@ -1464,7 +2412,9 @@ pub mod bcs_signable_test {
     }
 }
 
-#[derive(Deserialize, Serialize, JsonSchema, Debug)]
+#[derive(
+    Clone, Copy, Deserialize, Serialize, JsonSchema, Debug, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
 pub enum SignatureScheme {
     ED25519,
     Secp256k1,
@@ -1472,6 +2422,20 @@ pub enum SignatureScheme {
 }
 
 impl SignatureScheme {
+    /// Every signature scheme supported by the crate.
+    pub fn all() -> &'static [SignatureScheme] {
+        &[
+            SignatureScheme::ED25519,
+            SignatureScheme::Secp256k1,
+            SignatureScheme::BLS12381,
+        ]
+    }
+
+    /// The schemes users can sign transactions with. BLS12381 is only used by authorities.
+    pub fn user_schemes() -> &'static [SignatureScheme] {
+        &[SignatureScheme::ED25519, SignatureScheme::Secp256k1]
+    }
+
     pub fn flag(&self) -> u8 {
         match self {
             SignatureScheme::ED25519 => 0x00,
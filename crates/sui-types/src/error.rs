@@ -48,6 +48,8 @@ pub enum SuiError {
     // Object misuse issues
     #[error("Error checking transaction input objects: {:?}", errors)]
     ObjectErrors { errors: Vec<SuiError> },
+    #[error("{total} errors checking transaction input objects, only the first are reported")]
+    TooManyObjectErrors { total: usize },
     #[error("Attempt to transfer an object that's not owned.")]
     TransferUnownedError,
     #[error("Attempt to transfer an object that does not have public transfer. Object transfer must be done instead using a distinct Move function call.")]
@@ -70,6 +72,10 @@ pub enum SuiError {
     SharedObjectLockNotSetError,
     #[error("Invalid Batch Transaction: {}", error)]
     InvalidBatchTransaction { error: String },
+    #[error("Transaction has no effect")]
+    EmptyTransactionKind,
+    #[error("Object {object_id:?} is referenced at more than one version")]
+    InconsistentObjectVersion { object_id: ObjectID },
     #[error("Object {child_id:?} is owned by object {parent_id:?}, which is not in the input")]
     MissingObjectOwner {
         child_id: ObjectID,
@@ -79,10 +85,28 @@ pub enum SuiError {
     // Signature verification
     #[error("Signature is not valid: {}", error)]
     InvalidSignature { error: String },
+    #[error("Signature scheme {scheme:?} is not allowed")]
+    DisallowedSignatureScheme {
+        scheme: crate::crypto::SignatureScheme,
+    },
+    #[error("Signature is not in canonical form: {}", error)]
+    NonCanonicalSignature { error: String },
+    #[error("A {scheme:?} signature is {expected} bytes long, got {got} bytes")]
+    InvalidSignatureLength {
+        scheme: crate::crypto::SignatureScheme,
+        expected: usize,
+        got: usize,
+    },
+    #[error("Signature verification did not finish within {budget:?}")]
+    VerificationTimeout { budget: std::time::Duration },
+    #[error("Signature verification did not complete: {}", error)]
+    SignatureVerificationFailed { error: String },
     #[error("Sender Signature must be verified separately from Authority Signature")]
     SenderSigUnbatchable,
     #[error("Value was not signed by the correct sender: {}", error)]
     IncorrectSigner { error: String },
+    #[error("Bytes to sign do not match the transaction: {}", error)]
+    TransactionBytesMismatch { error: String },
     #[error("Value was not signed by a known authority")]
     UnknownSigner,
     // Certificate verification
@@ -90,6 +114,8 @@ pub enum SuiError {
     WrongEpoch { expected_epoch: EpochId },
     #[error("Signatures in a certificate must form a quorum")]
     CertificateRequiresQuorum,
+    #[error("Certificate is signed by forbidden authority {signer}")]
+    ForbiddenSigner { signer: AuthorityName },
     #[error("Authority {authority_name:?} could not sync certificate: {err:?}")]
     CertificateSyncError { authority_name: String, err: String },
     #[error(
@@ -133,6 +159,8 @@ pub enum SuiError {
     ConcurrentIteratorError,
     #[error("The notifier subsystem is closed.")]
     ClosedNotifierError,
+    #[error("Sequence number {received} regressed, expected at least {expected}.")]
+    SequenceRegression { expected: u64, received: u64 },
 
     // Account access
     #[error("No certificate with digest: {certificate_digest:?}")]
@@ -187,6 +215,15 @@ pub enum SuiError {
     InvalidSequenceRangeError,
     #[error("No batches matched the range requested.")]
     NoBatchesFoundError,
+    #[error("Requested {requested} batch stream items, more than the maximum of {max}.")]
+    BatchRequestTooLarge { requested: u64, max: u64 },
+    #[error("Cannot serve a batch stream of {length} items from {start:?}, the available range is {earliest} to {latest}.")]
+    InvalidBatchRange {
+        start: Option<u64>,
+        length: u64,
+        earliest: u64,
+        latest: u64,
+    },
     #[error("The channel to repond to the client returned an error.")]
     CannotSendClientMessageError,
     #[error("Subscription service had to drop {0} items")]
@@ -258,6 +295,8 @@ pub enum SuiError {
     GasBudgetTooHigh { error: String },
     #[error("Insufficient gas: {error:?}.")]
     InsufficientGas { error: String },
+    #[error("Gas object {object_id:?} is also used as an input object of the transaction")]
+    GasObjectUsedAsInput { object_id: ObjectID },
 
     // Internal state errors
     #[error("Attempt to update state of TxContext from a different instance than original.")]
@@ -317,6 +356,20 @@ pub enum SuiError {
         authority: AuthorityName,
         reason: String,
     },
+    #[error("Batch stream from {authority:?} is out of order: sequence number {received} after {previous}")]
+    BatchStreamOutOfOrder {
+        authority: AuthorityName,
+        previous: u64,
+        received: u64,
+    },
+    #[error("Batch stream from {authority:?} has a gap: expected sequence number {expected}, received {received}")]
+    BatchStreamGap {
+        authority: AuthorityName,
+        expected: u64,
+        received: u64,
+    },
+    #[error("Batch stream from {authority:?} does not start with a batch at or before the requested sequence number")]
+    MissingInitialBatch { authority: AuthorityName },
     #[error(
         "Sync from authority failed. From {xsource:?} to {destination:?}, digest {tx_digest:?}: {error:?}",
     )]
@@ -409,6 +462,8 @@ pub enum SuiError {
     KeyConversionError(String),
     #[error("Invalid Private Key provided")]
     InvalidPrivateKey,
+    #[error("Cannot seal or open batch: {error}")]
+    InvalidSealedBatch { error: String },
 
     // Epoch related errors.
     #[error("Validator temporarily stopped processing transactions due to epoch change")]
@@ -483,6 +538,20 @@ impl From<ExecutionErrorKind> for SuiError {
     }
 }
 
+/// Signature parsing reports failures as `signature::Error`; recover the `SuiError` it was
+/// built from, if there is one.
+impl From<signature::Error> for SuiError {
+    fn from(error: signature::Error) -> Self {
+        match std::error::Error::source(&error).and_then(|source| source.downcast_ref::<SuiError>())
+        {
+            Some(error) => SuiError::clone(error),
+            None => SuiError::InvalidSignature {
+                error: error.to_string(),
+            },
+        }
+    }
+}
+
 impl std::convert::From<&str> for SuiError {
     fn from(error: &str) -> Self {
         SuiError::GenericAuthorityError {
@@ -6,7 +6,9 @@ use crate::{
     error::{ExecutionError, ExecutionErrorKind},
     error::{SuiError, SuiResult},
     gas_coin::GasCoin,
+    messages::{SingleTransactionKind, TransactionKind},
     object::{Object, Owner},
+    sui_system_state::SuiSystemState,
 };
 use move_core_types::{
     gas_algebra::{GasQuantity, InternalGas, InternalGasPerByte, NumBytes, UnitDiv},
@@ -163,6 +165,92 @@ pub static MAX_GAS_BUDGET: Lazy<u64> = Lazy::new(|| u64::from(to_external(MAXIMU
 pub static MIN_GAS_BUDGET: Lazy<u64> =
     Lazy::new(|| to_external(*INIT_SUI_COST_TABLE.min_transaction_cost).into());
 
+/// The gas units charged on top for transactions that have to go through consensus.
+pub static CONSENSUS_SURCHARGE: Lazy<u64> =
+    Lazy::new(|| to_external(*INIT_SUI_COST_TABLE.consensus_cost).into());
+
+/// A lower bound on the computation gas units charged for reading `input_bytes` bytes of input
+/// objects and publishing `module_bytes` bytes of modules, on top of `MIN_GAS_BUDGET`.
+pub fn min_input_gas(input_bytes: u64, module_bytes: u64) -> u64 {
+    let read = NumBytes::new(input_bytes).mul(*INIT_SUI_COST_TABLE.object_read_per_byte_cost);
+    let publish =
+        NumBytes::new(module_bytes).mul(*INIT_SUI_COST_TABLE.package_publish_per_byte_cost);
+    to_external(InternalGas::new(
+        u64::from(read).saturating_add(u64::from(publish)),
+    ))
+    .into()
+}
+
+/// Factors applied to the minimum gas budget per kind of transaction, for kinds whose baseline
+/// cost is well above the minimum transaction cost. A factor of 1 leaves the minimum as is.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct KindGasMultipliers {
+    /// Object and SUI transfers, and payments.
+    pub transfer: u64,
+    /// Module publishing.
+    pub publish: u64,
+    /// Move calls.
+    pub call: u64,
+}
+
+impl Default for KindGasMultipliers {
+    fn default() -> Self {
+        Self {
+            transfer: 1,
+            publish: 1,
+            call: 1,
+        }
+    }
+}
+
+/// The gas parameters fixed for the duration of an epoch, as set in the system state.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EpochGasParams {
+    /// The price of a unit of storage.
+    pub storage_gas_price: u64,
+    /// The gas units charged on top for transactions that go through consensus.
+    pub consensus_surcharge: u64,
+    /// How the minimum gas budget scales with the kind of transaction.
+    pub kind_gas_multipliers: KindGasMultipliers,
+}
+
+impl EpochGasParams {
+    /// The price per gas unit the gas coin of a transaction bidding `computation_gas_price`
+    /// has to cover.
+    // TODO: We should revisit how we compute gas price and compare to gas budget.
+    pub fn effective_gas_price(&self, computation_gas_price: u64) -> u64 {
+        std::cmp::max(computation_gas_price, self.storage_gas_price)
+    }
+
+    /// The factor applied to the minimum gas budget for a transaction of `kind`. A batch is
+    /// held to the highest factor among its transactions.
+    pub fn kind_gas_multiplier(&self, kind: &TransactionKind) -> u64 {
+        let multipliers = &self.kind_gas_multipliers;
+        kind.single_transactions()
+            .map(|single| match single {
+                SingleTransactionKind::TransferObject(_)
+                | SingleTransactionKind::TransferSui(_)
+                | SingleTransactionKind::Pay(_) => multipliers.transfer,
+                SingleTransactionKind::Publish(_) => multipliers.publish,
+                SingleTransactionKind::Call(_) => multipliers.call,
+                SingleTransactionKind::ChangeEpoch(_) => 1,
+            })
+            .max()
+            .unwrap_or(1)
+    }
+}
+
+impl From<&SuiSystemState> for EpochGasParams {
+    fn from(system_state: &SuiSystemState) -> Self {
+        Self {
+            storage_gas_price: system_state.parameters.storage_gas_price,
+            consensus_surcharge: *CONSENSUS_SURCHARGE,
+            kind_gas_multipliers: KindGasMultipliers::default(),
+        }
+    }
+}
+
 fn to_external(internal_units: InternalGas) -> GasUnits {
     InternalGas::to_unit_round_down(internal_units)
 }
@@ -355,7 +443,7 @@ impl<'a> SuiGasStatus<'a> {
 
 /// Check whether the given gas_object and gas_budget is legit:
 /// 1. If the gas object has an address owner.
-/// 2. If it's enough to pay the flat minimum transaction fee
+/// 2. If it's enough to pay the flat minimum transaction fee, scaled by `min_budget_multiplier`
 /// 3. If it's less than the max gas budget allowed
 /// 4. If the gas_object actually has enough balance to pay for the budget.
 pub fn check_gas_balance(
@@ -363,6 +451,7 @@ pub fn check_gas_balance(
     gas_budget: u64,
     gas_price: u64,
     extra_amount: u64,
+    min_budget_multiplier: u64,
 ) -> SuiResult {
     ok_or_gas_error!(
         matches!(gas_object.owner, Owner::AddressOwner(_)),
@@ -372,11 +461,12 @@ pub fn check_gas_balance(
         gas_budget <= *MAX_GAS_BUDGET,
         format!("Gas budget set too high; maximum is {}", *MAX_GAS_BUDGET)
     )?;
+    let min_gas_budget = MIN_GAS_BUDGET.saturating_mul(min_budget_multiplier);
     ok_or_gas_error!(
-        gas_budget >= *MIN_GAS_BUDGET,
+        gas_budget >= min_gas_budget,
         format!(
             "Gas budget is {}, smaller than minimum requirement {}",
-            gas_budget, *MIN_GAS_BUDGET
+            gas_budget, min_gas_budget
         )
     )?;
 
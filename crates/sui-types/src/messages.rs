@@ -4,12 +4,12 @@
 use super::{base_types::*, batch::*, committee::Committee, error::*, event::Event};
 use crate::committee::{EpochId, StakeUnit};
 use crate::crypto::{
-    sha3_hash, AuthoritySignInfo, AuthoritySignInfoTrait, AuthoritySignature,
+    sha3_hash, signable_bytes, AuthoritySignInfo, AuthoritySignInfoTrait, AuthoritySignature,
     AuthorityStrongQuorumSignInfo, Ed25519SuiSignature, EmptySignInfo, Signable, Signature,
     SignatureScheme, SuiAuthoritySignature, SuiSignature, SuiSignatureInner, ToFromBytes,
     VerificationObligation,
 };
-use crate::gas::GasCostSummary;
+use crate::gas::{self, EpochGasParams, GasCostSummary};
 use crate::messages_checkpoint::CheckpointFragment;
 use crate::object::{Object, ObjectFormatOptions, Owner, OBJECT_START_VERSION};
 use crate::storage::{DeleteKind, WriteKind};
@@ -390,15 +390,12 @@ impl TransactionKind {
         )
     }
 
+    /// Reject transactions that cannot be valid whatever the state: batches that are empty or
+    /// hold transactions that cannot be batched, and transactions that would have no effect.
     pub fn validity_check(&self) -> SuiResult {
         match self {
             Self::Batch(b) => {
-                fp_ensure!(
-                    !b.is_empty(),
-                    SuiError::InvalidBatchTransaction {
-                        error: "Batch Transaction cannot be empty".to_string(),
-                    }
-                );
+                fp_ensure!(!b.is_empty(), SuiError::EmptyTransactionKind);
                 // Check that all transaction kinds can be in a batch.
                 let valid = self.single_transactions().all(|s| match s {
                     SingleTransactionKind::Call(_)
@@ -424,6 +421,15 @@ impl TransactionKind {
                 | SingleTransactionKind::ChangeEpoch(_) => (),
             },
         }
+        // A payment to nobody, or a publish without modules, does nothing.
+        for single in self.single_transactions() {
+            let no_effect = match single {
+                SingleTransactionKind::Pay(Pay { recipients, .. }) => recipients.is_empty(),
+                SingleTransactionKind::Publish(MoveModulePublish { modules }) => modules.is_empty(),
+                _ => false,
+            };
+            fp_ensure!(!no_effect, SuiError::EmptyTransactionKind);
+        }
         Ok(())
     }
 }
@@ -599,6 +605,64 @@ impl TransactionData {
             .collect()
     }
 
+    /// Whether the transaction only takes owned or immutable objects as input, and so can
+    /// take the fast path that does not go through consensus.
+    pub fn is_owned_only(&self) -> bool {
+        self.kind.shared_input_objects().next().is_none()
+    }
+
+    /// Like `is_owned_only`, but returns the ids of the shared objects that keep the
+    /// transaction off the fast path.
+    pub fn fast_path_eligible(&self) -> Result<(), Vec<ObjectID>> {
+        let shared: Vec<_> = self.kind.shared_input_objects().copied().collect();
+        if shared.is_empty() {
+            Ok(())
+        } else {
+            Err(shared)
+        }
+    }
+
+    /// Check that `intended_bytes`, e.g. as built by a wallet from the JSON form of the
+    /// transaction, are exactly the bytes a signature over this transaction covers.
+    pub fn verify_matches_bytes(&self, intended_bytes: &[u8]) -> SuiResult<()> {
+        let bytes = signable_bytes(self);
+        if bytes == intended_bytes {
+            return Ok(());
+        }
+        let error = match bytes
+            .iter()
+            .zip(intended_bytes)
+            .position(|(expected, actual)| expected != actual)
+        {
+            Some(offset) => format!(
+                "first difference at byte {offset}: expected {:#04x}, got {:#04x}",
+                bytes[offset], intended_bytes[offset]
+            ),
+            None => format!(
+                "expected {} bytes, got {}, with a common prefix",
+                bytes.len(),
+                intended_bytes.len()
+            ),
+        };
+        Err(SuiError::TransactionBytesMismatch { error })
+    }
+
+    /// The positions, in `self.kind.single_transactions()`, of the transactions that take the
+    /// object `id` as an input. A transaction whose inputs cannot be determined is skipped.
+    pub fn sub_transactions_touching(&self, id: &ObjectID) -> Vec<usize> {
+        self.kind
+            .single_transactions()
+            .enumerate()
+            .filter(|(_, single)| {
+                single
+                    .input_objects()
+                    .map(|inputs| inputs.iter().any(|input| input.object_id() == *id))
+                    .unwrap_or(false)
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
     pub fn input_objects(&self) -> SuiResult<Vec<InputObjectKind>> {
         let mut inputs = self.kind.input_objects()?;
 
@@ -609,8 +673,63 @@ impl TransactionData {
         }
         Ok(inputs)
     }
+    /// The ids of the objects this transaction will mutate, given its resolved `inputs`:
+    /// the owned objects that are not immutable, the shared objects and the gas coin. This is
+    /// computed from the inputs alone, without executing the transaction.
+    pub fn mutated_object_ids(&self, inputs: &InputObjects) -> Vec<ObjectID> {
+        let mut ids: Vec<_> = inputs
+            .mutable_inputs()
+            .into_iter()
+            .map(|(id, _, _)| id)
+            .collect();
+        // The gas coin is always charged, even if it was left out of the resolved inputs.
+        let gas_id = self.gas_payment_object_ref().0;
+        if !self.kind.is_system_tx() && !inputs.contains_id(&gas_id) {
+            ids.push(gas_id);
+        }
+        ids
+    }
+
+    /// A floor on the gas budget of this transaction, estimated without a store from its
+    /// commands and inputs: the minimum budget for its kind of transaction, the cost of reading
+    /// each input object (assuming the smallest possible objects) and of publishing its modules,
+    /// and the consensus surcharge when it takes a shared object. A budget below this is bound
+    /// to run out of gas, but one above it is not guaranteed to suffice.
+    pub fn minimum_gas_budget(&self, gas_params: &EpochGasParams) -> u64 {
+        if self.kind.is_system_tx() {
+            return 0;
+        }
+        // Transactions with malformed inputs fail validation regardless of their budget.
+        let input_objects = self.input_objects().unwrap_or_default();
+        let module_bytes: usize = self
+            .kind
+            .single_transactions()
+            .map(|single| match single {
+                SingleTransactionKind::Publish(publish) => {
+                    publish.modules.iter().map(Vec::len).sum()
+                }
+                _ => 0,
+            })
+            .sum();
+        let consensus_surcharge = if self.kind.shared_input_objects().next().is_some() {
+            gas_params.consensus_surcharge
+        } else {
+            0
+        };
+
+        gas::MIN_GAS_BUDGET
+            .saturating_mul(gas_params.kind_gas_multiplier(&self.kind))
+            .saturating_add(gas::min_input_gas(
+                (input_objects.len() * MIN_OBJECT_SIZE) as u64,
+                module_bytes as u64,
+            ))
+            .saturating_add(consensus_surcharge)
+    }
 }
 
+/// The fewest bytes an object can take, its ID, used to bound the cost of reading inputs.
+const MIN_OBJECT_SIZE: usize = ObjectID::LENGTH;
+
 /// A transaction signed by a client, optionally signed by an authority (depending on `S`).
 /// `S` indicates the authority signing state. It can be either empty or signed.
 /// We make the authority signature templated so that `TransactionEnvelope<S>` can be used
@@ -926,6 +1045,16 @@ pub struct BatchInfoRequest {
     pub start: Option<TxSequenceNumber>,
     // The total number of items to receive. Could receive a bit more or a bit less.
     pub length: u64,
+    // Only send the signed batches, omitting the transactions they contain.
+    pub headers_only: bool,
+}
+
+/// A message from the client of an acknowledged batch stream. The first message opens the
+/// stream, and the following ones report the highest sequence number the client has processed.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+pub enum BatchInfoStreamMessage {
+    Request(BatchInfoRequest),
+    Ack(TxSequenceNumber),
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
@@ -1731,13 +1860,32 @@ impl InputObjectKind {
     }
 }
 
+/// The input objects of a transaction, split by how they are accessed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InputObjectClassification {
+    /// Packages and immutable objects, which are only read.
+    pub immutable: Vec<ObjectRef>,
+    /// Objects owned by an address or another object, which the transaction locks.
+    pub owned: Vec<ObjectRef>,
+    /// Shared objects, whose versions are assigned by consensus.
+    pub shared: Vec<ObjectRef>,
+}
+
 pub struct InputObjects {
     objects: Vec<(InputObjectKind, Object)>,
+    /// The ids of `objects`, for constant time membership checks.
+    ids: HashSet<ObjectID>,
 }
 
 impl InputObjects {
     pub fn new(objects: Vec<(InputObjectKind, Object)>) -> Self {
-        Self { objects }
+        let ids = objects.iter().map(|(kind, _)| kind.object_id()).collect();
+        Self { objects, ids }
+    }
+
+    /// Whether the object `id` is one of the inputs.
+    pub fn contains_id(&self, id: &ObjectID) -> bool {
+        self.ids.contains(id)
     }
 
     pub fn len(&self) -> usize {
@@ -1748,37 +1896,34 @@ impl InputObjects {
         self.objects.is_empty()
     }
 
-    pub fn filter_owned_objects(&self) -> Vec<ObjectRef> {
-        let owned_objects: Vec<_> = self
-            .objects
-            .iter()
-            .filter_map(|(object_kind, object)| match object_kind {
-                InputObjectKind::MovePackage(_) => None,
+    /// Split the inputs into immutable, owned and shared objects in a single pass, keeping
+    /// their order within each group.
+    pub fn classify(&self) -> InputObjectClassification {
+        let mut classification = InputObjectClassification::default();
+        for (kind, object) in &self.objects {
+            match kind {
+                InputObjectKind::MovePackage(_) => classification
+                    .immutable
+                    .push(object.compute_object_reference()),
                 InputObjectKind::ImmOrOwnedMoveObject(object_ref) => {
                     if object.is_immutable() {
-                        None
+                        classification.immutable.push(*object_ref)
                     } else {
-                        Some(*object_ref)
+                        classification.owned.push(*object_ref)
                     }
                 }
-                InputObjectKind::SharedMoveObject(_) => None,
-            })
-            .collect();
+                InputObjectKind::SharedMoveObject(_) => classification
+                    .shared
+                    .push(object.compute_object_reference()),
+            }
+        }
 
         debug!(
-            num_mutable_objects = owned_objects.len(),
+            num_mutable_objects = classification.owned.len(),
             "Checked locks and found mutable objects"
         );
 
-        owned_objects
-    }
-
-    pub fn filter_shared_objects(&self) -> Vec<ObjectRef> {
-        self.objects
-            .iter()
-            .filter(|(kind, _)| matches!(kind, InputObjectKind::SharedMoveObject(_)))
-            .map(|(_, obj)| obj.compute_object_reference())
-            .collect()
+        classification
     }
 
     pub fn transaction_dependencies(&self) -> BTreeSet<TransactionDigest> {
@@ -1940,6 +2085,13 @@ impl CertifiedTransaction {
     pub fn epoch(&self) -> EpochId {
         self.auth_sign_info.epoch
     }
+
+    /// The exact bytes the authorities signed to form this certificate, i.e. the tagged BCS
+    /// encoding of the sender signed data. This lets third parties check the aggregated
+    /// signature with their own BLS implementation.
+    pub fn signed_message_bytes(&self) -> Vec<u8> {
+        signable_bytes(&self.signed_data)
+    }
 }
 
 impl Display for CertifiedTransaction {
@@ -125,16 +125,15 @@ impl SuiSystemState {
     }
 
     pub fn get_next_epoch_committee(&self) -> Committee {
-        Committee::new(
-            self.epoch + 1,
-            self.validators
-                .next_epoch_validators
-                .iter()
-                .map(ValidatorMetadata::to_validator_and_stake_pair)
-                .collect(),
-        )
-        // unwrap is safe because we should have verified the committee on-chain.
-        // TODO: Make sure we actually verify it.
-        .unwrap()
+        let validators: Vec<_> = self
+            .validators
+            .next_epoch_validators
+            .iter()
+            .map(ValidatorMetadata::to_validator_and_stake_pair)
+            .collect();
+        Committee::from_validator_set(self.epoch + 1, &validators)
+            // unwrap is safe because we should have verified the committee on-chain.
+            // TODO: Make sure we actually verify it.
+            .unwrap()
     }
 }
@@ -377,3 +377,50 @@ fn test_address_backwards_compatibility() {
                also require updates if they use fixed values generated by the old algorithm."
     );
 }
+
+#[test]
+fn test_address_matches_prefix() {
+    let address = SuiAddress::from_str("0x32866f0109fa1ba911392dcd2d4260f1d8243133").unwrap();
+    assert!(address.matches_prefix("3286"));
+    assert!(address.matches_prefix("0x32866F"));
+    assert!(!address.matches_prefix("3287"));
+    assert!(!address.matches_prefix("not hex"));
+    assert!(address.matches_prefix(""));
+}
+
+#[test]
+fn test_address_prefix_set() {
+    let address = SuiAddress::from_str("0x32866f0109fa1ba911392dcd2d4260f1d8243133").unwrap();
+    let other = SuiAddress::from_str("0xab866f0109fa1ba911392dcd2d4260f1d8243133").unwrap();
+
+    let mut set = SuiAddressPrefixSet::new();
+    assert!(set.is_empty());
+    assert!(!set.contains(&address));
+
+    set.insert("0x3286").unwrap();
+    set.insert("ffff").unwrap();
+    assert!(set.contains(&address));
+    assert!(!set.contains(&other));
+
+    // Odd-length prefixes match at nibble granularity.
+    set.insert("a").unwrap();
+    assert!(set.contains(&other));
+
+    // A full-length prefix is an exact match.
+    let mut exact = SuiAddressPrefixSet::new();
+    exact.insert(&format!("{:x}", address)).unwrap();
+    assert!(exact.contains(&address));
+    assert!(!exact.contains(&other));
+
+    assert!(set.insert("xyz").is_err());
+    assert!(set.insert(&"0".repeat(SUI_ADDRESS_LENGTH * 2 + 1)).is_err());
+}
+
+#[test]
+fn test_address_prefix_set_empty_prefix() {
+    let mut set = SuiAddressPrefixSet::new();
+    set.insert("").unwrap();
+    assert!(!set.is_empty());
+    assert!(set.contains(&SuiAddress::random_for_testing_only()));
+    assert!(set.contains(&SuiAddress::default()));
+}
@@ -0,0 +1,112 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::committee::Committee;
+use crate::crypto::{get_key_pair, AuthorityKeyPair, NetworkKeyPair};
+use fastcrypto::traits::KeyPair;
+use std::collections::BTreeMap;
+
+fn random_transactions(
+    start: TxSequenceNumber,
+    count: u64,
+) -> Vec<(TxSequenceNumber, ExecutionDigests)> {
+    (start..start + count)
+        .map(|seq| (seq, ExecutionDigests::random()))
+        .collect()
+}
+
+#[test]
+fn test_verify_contents() {
+    let initial = AuthorityBatch::initial();
+    assert!(initial.verify_contents(&[]).is_ok());
+
+    let transactions = random_transactions(0, 5);
+    let batch = AuthorityBatch::make_next(&initial, &transactions).unwrap();
+    assert!(batch.verify_contents(&transactions).is_ok());
+}
+
+#[test]
+fn test_verify_contents_mismatch() {
+    let initial = AuthorityBatch::initial();
+    let transactions = random_transactions(0, 5);
+    let batch = AuthorityBatch::make_next(&initial, &transactions).unwrap();
+
+    // Same sequence numbers, different digest for one transaction.
+    let mut swapped = transactions.clone();
+    swapped[2].1 = ExecutionDigests::random();
+    assert!(batch.verify_contents(&swapped).is_err());
+
+    // Missing the last transaction.
+    assert!(batch.verify_contents(&transactions[..4]).is_err());
+
+    // Shifted sequence numbers.
+    let shifted: Vec<_> = transactions
+        .iter()
+        .map(|(seq, digests)| (seq + 1, *digests))
+        .collect();
+    assert!(batch.verify_contents(&shifted).is_err());
+
+    assert!(initial.verify_contents(&transactions).is_err());
+}
+
+#[test]
+fn test_signed_batch_epoch() {
+    let (_, key): (_, AuthorityKeyPair) = get_key_pair();
+    let name: AuthorityName = key.public().into();
+    let batch =
+        AuthorityBatch::make_next(&AuthorityBatch::initial(), &random_transactions(0, 3)).unwrap();
+
+    let signed = SignedBatch::new(5, batch.clone(), &key, name);
+    assert_eq!(signed.auth_sig().epoch, 5);
+    assert_eq!(signed.auth_sig().authority, name);
+    let committee = Committee::new(5, BTreeMap::from([(name, 1)])).unwrap();
+    signed.verify(&committee).unwrap();
+
+    let zero = SignedBatch::new_with_zero_epoch(batch, &key, name);
+    assert_eq!(zero.auth_sig().epoch, 0);
+}
+
+#[test]
+fn test_sealed_batch_round_trip() {
+    let (_, key): (_, AuthorityKeyPair) = get_key_pair();
+    let name: AuthorityName = key.public().into();
+    let committee = Committee::new(0, BTreeMap::from([(name, 1)])).unwrap();
+    let batch =
+        AuthorityBatch::make_next(&AuthorityBatch::initial(), &random_transactions(0, 3)).unwrap();
+    let signed = SignedBatch::new(0, batch, &key, name);
+
+    let (_, recipient): (_, NetworkKeyPair) = get_key_pair();
+    let sealed = signed.seal_to(recipient.public()).unwrap();
+    let opened = sealed.open(&recipient.copy().private()).unwrap();
+    assert_eq!(opened, signed);
+    opened.verify(&committee).unwrap();
+
+    // Sealing the same batch again uses a fresh key and nonce.
+    let resealed = signed.seal_to(recipient.public()).unwrap();
+    assert_ne!(resealed.nonce, sealed.nonce);
+    assert_ne!(resealed.ciphertext, sealed.ciphertext);
+}
+
+#[test]
+fn test_sealed_batch_wrong_recipient() {
+    let (_, key): (_, AuthorityKeyPair) = get_key_pair();
+    let name: AuthorityName = key.public().into();
+    let signed = SignedBatch::new(0, AuthorityBatch::initial(), &key, name);
+
+    let (_, recipient): (_, NetworkKeyPair) = get_key_pair();
+    let (_, other): (_, NetworkKeyPair) = get_key_pair();
+    let sealed = signed.seal_to(recipient.public()).unwrap();
+    assert!(matches!(
+        sealed.open(&other.private()),
+        Err(SuiError::InvalidSealedBatch { .. })
+    ));
+
+    // Nor does a tampered batch open for its recipient.
+    let mut tampered = sealed;
+    tampered.ciphertext[0] ^= 1;
+    assert!(matches!(
+        tampered.open(&recipient.private()),
+        Err(SuiError::InvalidSealedBatch { .. })
+    ));
+}
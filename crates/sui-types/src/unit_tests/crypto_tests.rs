@@ -0,0 +1,1020 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::base_types::dbg_addr;
+use crate::crypto::bcs_signable_test::Foo;
+use rand::{rngs::StdRng, SeedableRng};
+
+#[test]
+fn test_sha3_hash_matches_generic_hash() {
+    let value = Foo("some data".to_string());
+
+    let mut digest = Sha3_256::default();
+    value.write(&mut digest);
+    let expected: [u8; 32] = digest.finalize().into();
+
+    assert_eq!(sha3_hash(&value), expected);
+    let generic: [u8; 32] = hash::<Sha3_256, _>(&value).into();
+    assert_eq!(generic, expected);
+}
+
+fn committee_of(keys: &[&AuthorityKeyPair]) -> Committee {
+    let authorities = keys
+        .iter()
+        .map(|kp| (AuthorityPublicKeyBytes::from(kp.public()), 1))
+        .collect();
+    Committee::new(0, authorities).unwrap()
+}
+
+#[test]
+fn test_authority_sign_info_from_raw_bytes() {
+    let (_, kp): (_, AuthorityKeyPair) = get_key_pair();
+    let committee = committee_of(&[&kp]);
+    let name = AuthorityPublicKeyBytes::from(kp.public());
+    let value = Foo("some data".to_string());
+
+    // Bytes as an external signer would hand them to us.
+    let sig_bytes = AuthoritySignature::new(&value, &kp).as_ref().to_vec();
+
+    let sign_info = AuthoritySignInfo::new_from_raw(committee.epoch(), name, &sig_bytes).unwrap();
+    assert!(sign_info.verify(&value, &committee).is_ok());
+    assert!(sign_info
+        .verify(&Foo("other data".to_string()), &committee)
+        .is_err());
+
+    assert!(matches!(
+        AuthoritySignInfo::new_from_raw(committee.epoch(), name, &sig_bytes[1..]),
+        Err(SuiError::InvalidSignature { .. })
+    ));
+}
+
+#[test]
+fn test_quorum_excludes_forbidden_signers() {
+    let keys: Vec<AuthorityKeyPair> = (0..4).map(|_| get_key_pair().1).collect();
+    let committee = committee_of(&keys.iter().collect::<Vec<_>>());
+    let names: Vec<AuthorityName> = keys.iter().map(|kp| kp.public().into()).collect();
+    let value = Foo("some data".to_string());
+
+    // Signed by the first three authorities, the minimum quorum of four.
+    let quorum = AuthorityStrongQuorumSignInfo::new_with_signatures(
+        keys[..3]
+            .iter()
+            .map(|kp| (kp.public().into(), AuthoritySignature::new(&value, kp)))
+            .collect(),
+        &committee,
+    )
+    .unwrap();
+
+    // The last authority did not sign, so excluding it changes nothing.
+    quorum.excludes(&names[3..], &committee).unwrap();
+    quorum.holds_without(&names[3..], &committee).unwrap();
+
+    // A forbidden signer is present, and the quorum relies on it.
+    assert_eq!(
+        quorum.excludes(&names[1..2], &committee),
+        Err(SuiError::ForbiddenSigner { signer: names[1] })
+    );
+    assert_eq!(
+        quorum.holds_without(&names[1..2], &committee),
+        Err(SuiError::CertificateRequiresQuorum)
+    );
+
+    // A weak quorum still holds without one of its signers.
+    let weak_quorum = AuthorityWeakQuorumSignInfo::new_with_signatures(
+        keys[..3]
+            .iter()
+            .map(|kp| (kp.public().into(), AuthoritySignature::new(&value, kp)))
+            .collect(),
+        &committee,
+    )
+    .unwrap();
+    assert!(weak_quorum.excludes(&names[1..2], &committee).is_err());
+    weak_quorum.holds_without(&names[1..2], &committee).unwrap();
+}
+
+#[test]
+fn test_public_key_ordering() {
+    let mut rng = StdRng::from_seed([0; 32]);
+    let mut keys: Vec<PublicKey> = Vec::new();
+    for _ in 0..3 {
+        let (_, kp): (_, Secp256k1KeyPair) = get_key_pair_from_rng(&mut rng);
+        keys.push(kp.public().clone().into());
+        let (_, kp): (_, Ed25519KeyPair) = get_key_pair_from_rng(&mut rng);
+        keys.push(kp.public().clone().into());
+    }
+
+    let mut sorted = keys.clone();
+    sorted.sort();
+
+    // All ed25519 keys (flag 0x00) come before all secp256k1 keys (flag 0x01),
+    // and keys of the same scheme are ordered by their bytes.
+    let flags: Vec<u8> = sorted.iter().map(|pk| pk.flag()).collect();
+    assert_eq!(flags, vec![0x00, 0x00, 0x00, 0x01, 0x01, 0x01]);
+    for pair in sorted.windows(2) {
+        if pair[0].flag() == pair[1].flag() {
+            assert!(pair[0].as_ref() < pair[1].as_ref());
+        }
+    }
+
+    // The order does not depend on the input order.
+    let mut reversed = keys;
+    reversed.reverse();
+    reversed.sort();
+    assert_eq!(reversed, sorted);
+}
+
+#[test]
+fn test_authority_sign_info_verify_against_key() {
+    let (_, kp): (_, AuthorityKeyPair) = get_key_pair();
+    let (_, other_kp): (_, AuthorityKeyPair) = get_key_pair();
+    let value = Foo("some data".to_string());
+
+    let sign_info = AuthoritySignInfo {
+        epoch: 0,
+        authority: AuthorityPublicKeyBytes::from(kp.public()),
+        signature: AuthoritySignature::new(&value, &kp),
+    };
+
+    assert!(sign_info.verify_against_key(&value, kp.public()).is_ok());
+    assert!(sign_info
+        .verify_against_key(&value, other_kp.public())
+        .is_err());
+    assert!(sign_info
+        .verify_against_key(&Foo("other data".to_string()), kp.public())
+        .is_err());
+}
+
+#[test]
+fn test_signature_reencode_round_trip() {
+    let value = Foo("some data".to_string());
+    let (ed25519_addr, ed25519_kp): (_, AccountKeyPair) = get_key_pair();
+    let (secp256k1_addr, secp256k1_kp): (_, Secp256k1KeyPair) = get_key_pair();
+
+    for (signature, author) in [
+        (Signature::new(&value, &ed25519_kp), ed25519_addr),
+        (Signature::new(&value, &secp256k1_kp), secp256k1_addr),
+    ] {
+        let v1 = signature.reencode(SignatureVersion::V1).unwrap();
+        assert_eq!(v1, signature.as_ref());
+
+        // The flag moves to the end, everything else keeps its position relative to it.
+        let v2 = signature.reencode(SignatureVersion::V2).unwrap();
+        assert_eq!(v2.len(), v1.len());
+        assert_eq!(v2.last(), v1.first());
+        assert_eq!(&v2[..v2.len() - 1], &v1[1..]);
+
+        let decoded = Signature::from_versioned_bytes(&v2, SignatureVersion::V2).unwrap();
+        assert_eq!(decoded, signature);
+        decoded.verify(&value, author).unwrap();
+
+        // Truncated bytes or unknown schemes cannot be transformed losslessly.
+        assert!(Signature::from_versioned_bytes(&v2[1..], SignatureVersion::V2).is_err());
+        let mut unknown_scheme = v2.clone();
+        *unknown_scheme.last_mut().unwrap() = 0x7f;
+        assert!(Signature::from_versioned_bytes(&unknown_scheme, SignatureVersion::V2).is_err());
+    }
+}
+
+#[test]
+fn test_verify_untagged_signature() {
+    let value = Foo("some data".to_string());
+    let (address, kp): (_, AccountKeyPair) = get_key_pair();
+
+    // An external tool signing the raw BCS bytes, without our type name tag.
+    let untagged: Signature = signature::Signer::sign(&kp, &bcs::to_bytes(&value).unwrap());
+    untagged.verify_untagged(&value, address).unwrap();
+    assert!(untagged.verify(&value, address).is_err());
+
+    // Tagged signatures do not verify as untagged ones, and vice versa.
+    let tagged = Signature::new(&value, &kp);
+    tagged.verify(&value, address).unwrap();
+    assert!(tagged.verify_untagged(&value, address).is_err());
+
+    // The author is still checked.
+    let (other_address, _): (_, AccountKeyPair) = get_key_pair();
+    assert!(untagged.verify_untagged(&value, other_address).is_err());
+}
+
+#[test]
+fn test_verify_reader() {
+    /// Yields the message a few bytes at a time, like a file or socket would.
+    struct ChunkedReader<'a>(&'a [u8]);
+
+    impl std::io::Read for ChunkedReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = buf.len().min(self.0.len()).min(1000);
+            buf[..len].copy_from_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+            Ok(len)
+        }
+    }
+
+    // A large value streamed as its Signable bytes, signed over their SHA3-256 digest.
+    let text: String = (0..4_000_000u32)
+        .map(|i| char::from(b'a' + (i % 26) as u8))
+        .collect();
+    let value = Foo(text.clone());
+    let mut tampered_text = text.into_bytes();
+    tampered_text[2_000_000] = b'!';
+    let tampered = Foo(String::from_utf8(tampered_text).unwrap());
+    let message = signable_bytes(&value);
+    assert_eq!(Sha3_256::digest(&message)[..], sha3_hash(&value)[..]);
+
+    let (address, kp): (_, AccountKeyPair) = get_key_pair();
+    let (secp_address, secp_kp): (_, Secp256k1KeyPair) = get_key_pair();
+    let (other_address, _): (_, AccountKeyPair) = get_key_pair();
+
+    for (signature, author) in [
+        (Signer::<Signature>::sign(&kp, &sha3_hash(&value)), address),
+        (
+            Signer::<Signature>::sign(&secp_kp, &sha3_hash(&value)),
+            secp_address,
+        ),
+    ] {
+        // Streaming accepts exactly what verifying the in-memory digest accepts.
+        for (candidate, author) in [
+            (&value, author),
+            (&tampered, author),
+            (&value, other_address),
+        ] {
+            assert_eq!(
+                signature
+                    .verify_reader(ChunkedReader(&signable_bytes(candidate)), author)
+                    .is_ok(),
+                signature
+                    .verify_untagged(&sha3_hash(candidate), author)
+                    .is_ok()
+            );
+        }
+        signature
+            .verify_reader(ChunkedReader(&message), author)
+            .unwrap();
+        assert!(signature
+            .verify_reader(ChunkedReader(&signable_bytes(&tampered)), author)
+            .is_err());
+        assert!(signature
+            .verify_reader(ChunkedReader(&message), other_address)
+            .is_err());
+    }
+}
+
+#[test]
+fn test_count_schemes() {
+    let value = Foo("some data".to_string());
+    let (_, ed25519_kp): (_, AccountKeyPair) = get_key_pair();
+    let (_, secp256k1_kp): (_, Secp256k1KeyPair) = get_key_pair();
+
+    let sigs = vec![
+        Signature::new(&value, &ed25519_kp),
+        Signature::new(&value, &secp256k1_kp),
+        Signature::new(&value, &ed25519_kp),
+    ];
+    let counts = count_schemes(&sigs);
+    assert_eq!(counts.len(), 2);
+    assert_eq!(counts[&SignatureScheme::ED25519], 2);
+    assert_eq!(counts[&SignatureScheme::Secp256k1], 1);
+    assert!(!counts.contains_key(&SignatureScheme::BLS12381));
+
+    assert!(count_schemes(&[]).is_empty());
+}
+
+#[test]
+fn test_signable_bytes() {
+    let value = Foo("some data".to_string());
+    let bytes = signable_bytes(&value);
+
+    // The name tag comes first, followed by the BCS bytes of the value.
+    let tag = b"Foo::";
+    assert!(bytes.starts_with(tag));
+    assert_eq!(&bytes[tag.len()..], bcs::to_bytes(&value).unwrap());
+
+    // These are the bytes that signatures are produced over.
+    let (address, kp): (_, AccountKeyPair) = get_key_pair();
+    let signature: Signature = signature::Signer::sign(&kp, &bytes);
+    signature.verify(&value, address).unwrap();
+}
+
+/// Accepts every signature, counting how often it was asked to.
+#[derive(Default)]
+struct MockVerifier {
+    single: std::sync::atomic::AtomicUsize,
+    aggregates: std::sync::atomic::AtomicUsize,
+}
+
+impl SignatureVerifier for MockVerifier {
+    fn verify<P: VerifyingKey>(&self, _: &P, _: &[u8], _: &P::Sig) -> SuiResult<()> {
+        self.single
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn verify_aggregates(
+        &self,
+        signatures: &[&AggregateAuthoritySignature],
+        _: Vec<std::slice::Iter<'_, AuthorityPublicKey>>,
+        _: &[&[u8]],
+    ) -> SuiResult<()> {
+        self.aggregates
+            .fetch_add(signatures.len(), std::sync::atomic::Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_mock_signature_verifier() {
+    use std::sync::atomic::Ordering;
+
+    let value = Foo("some data".to_string());
+    let other_value = Foo("other data".to_string());
+    let verifier = MockVerifier::default();
+
+    // A sender signature over the wrong message only passes with the mock verifier.
+    let (address, kp): (_, AccountKeyPair) = get_key_pair();
+    let signature = Signature::new(&value, &kp);
+    assert!(signature.verify(&other_value, address).is_err());
+    signature
+        .verify_with(&other_value, address, &verifier)
+        .unwrap();
+    assert_eq!(verifier.single.load(Ordering::Relaxed), 1);
+
+    // The author check happens before the verifier is consulted.
+    let (other_address, _): (_, AccountKeyPair) = get_key_pair();
+    assert!(signature
+        .verify_with(&value, other_address, &verifier)
+        .is_err());
+    assert_eq!(verifier.single.load(Ordering::Relaxed), 1);
+
+    // Same for authority signatures batched in an obligation.
+    let (_, authority_kp): (_, AuthorityKeyPair) = get_key_pair();
+    let committee = committee_of(&[&authority_kp]);
+    let sign_info = AuthoritySignInfo {
+        epoch: committee.epoch(),
+        authority: AuthorityPublicKeyBytes::from(authority_kp.public()),
+        signature: AuthoritySignature::new(&value, &authority_kp),
+    };
+    let obligation_for = |message: &Foo| {
+        let mut obligation = VerificationObligation::new();
+        let idx = obligation.add_message(message);
+        sign_info
+            .add_to_verification_obligation(&committee, &mut obligation, idx)
+            .unwrap();
+        obligation
+    };
+    assert!(obligation_for(&other_value).verify_all().is_err());
+    obligation_for(&other_value)
+        .verify_all_with(&verifier)
+        .unwrap();
+    assert_eq!(verifier.aggregates.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn test_signature_fault_recorder() {
+    let value = Foo("some data".to_string());
+    let (good_address, good_kp): (_, AccountKeyPair) = get_key_pair();
+    let (bad_address, bad_kp): (_, AccountKeyPair) = get_key_pair();
+    let recorder = Arc::new(SignatureFaultRecorder::new());
+
+    let mut obligation = VerificationObligation::new().with_fault_recorder(recorder.clone());
+    let idx = obligation.add_message(&value);
+
+    // A valid signature is not recorded.
+    Signature::new(&value, &good_kp)
+        .add_to_verification_obligation_or_verify(good_address, &mut obligation, idx)
+        .unwrap();
+    assert!(recorder.snapshot().is_empty());
+
+    // The bad signer signs something other than the message, twice.
+    let bad_signature = Signature::new(&Foo("other data".to_string()), &bad_kp);
+    for _ in 0..2 {
+        assert!(bad_signature
+            .add_to_verification_obligation_or_verify(bad_address, &mut obligation, idx)
+            .is_err());
+    }
+    let snapshot = recorder.snapshot();
+    assert_eq!(snapshot.len(), 1);
+    assert_eq!(snapshot[&bad_address], 2);
+
+    // Without a recorder attached, nothing is reported.
+    let (mut obligation, idx) = bcs_signable_test::get_obligation_input(&value);
+    assert!(bad_signature
+        .add_to_verification_obligation_or_verify(bad_address, &mut obligation, idx)
+        .is_err());
+    assert_eq!(recorder.snapshot()[&bad_address], 2);
+}
+
+#[test]
+fn test_signature_fault_recorder_attributes_authorities() {
+    let value = Foo("some data".to_string());
+    let (_, good_kp): (_, AuthorityKeyPair) = get_key_pair();
+    let (_, bad_kp): (_, AuthorityKeyPair) = get_key_pair();
+    let committee = committee_of(&[&good_kp, &bad_kp]);
+    let good_name = AuthorityPublicKeyBytes::from(good_kp.public());
+    let bad_name = AuthorityPublicKeyBytes::from(bad_kp.public());
+    let recorder = Arc::new(SignatureFaultRecorder::new());
+
+    let mut obligation = VerificationObligation::new().with_fault_recorder(recorder.clone());
+    let idx = obligation.add_message(&value);
+    for (name, signature) in [
+        (good_name, AuthoritySignature::new(&value, &good_kp)),
+        // The bad authority signs something other than the message.
+        (
+            bad_name,
+            AuthoritySignature::new(&Foo("other data".to_string()), &bad_kp),
+        ),
+    ] {
+        AuthoritySignInfo {
+            epoch: committee.epoch(),
+            authority: name,
+            signature,
+        }
+        .add_to_verification_obligation(&committee, &mut obligation, idx)
+        .unwrap();
+    }
+
+    // The aggregated check fails, and only the bad authority is blamed for it.
+    assert!(obligation.verify_all().is_err());
+    let snapshot = recorder.snapshot();
+    assert_eq!(snapshot.len(), 1);
+    assert_eq!(snapshot[&SuiAddress::from(&bad_name)], 1);
+}
+
+#[test]
+fn test_authority_name_round_trip() {
+    let (_, kp): (_, AuthorityKeyPair) = get_key_pair();
+    let public_key = kp.public().clone();
+
+    let name = public_key.to_name();
+    assert_eq!(name, AuthorityPublicKeyBytes::from(kp.public()));
+    assert_eq!(name, AuthorityName::from(public_key.clone()));
+    assert_eq!(AuthorityPublicKey::from_name(&name).unwrap(), public_key);
+
+    // Not every byte string names a valid key.
+    assert!(matches!(
+        AuthorityPublicKey::from_name(&AuthorityName::ZERO),
+        Err(SuiError::KeyConversionError(_))
+    ));
+}
+
+#[test]
+fn test_signature_matches_address() {
+    let value = Foo("some data".to_string());
+    let (address, kp): (_, AccountKeyPair) = get_key_pair();
+    let (secp_address, secp_kp): (_, Secp256k1KeyPair) = get_key_pair();
+    let (other_address, _): (_, AccountKeyPair) = get_key_pair();
+
+    let signature = Signature::new(&value, &kp);
+    assert!(signature.matches_address(address));
+    assert!(!signature.matches_address(other_address));
+
+    let secp_signature = Signature::new(&value, &secp_kp);
+    assert!(secp_signature.matches_address(secp_address));
+    assert!(!secp_signature.matches_address(address));
+
+    // Only the key is checked, not the signature: one over other data still matches.
+    let unrelated = Signature::new(&Foo("other data".to_string()), &kp);
+    assert!(unrelated.matches_address(address));
+}
+
+#[test]
+fn test_verify_with_allowed_schemes() {
+    let value = Foo("some data".to_string());
+    let (address, kp): (_, AccountKeyPair) = get_key_pair();
+    let (secp_address, secp_kp): (_, Secp256k1KeyPair) = get_key_pair();
+    let only_ed25519 = [SignatureScheme::ED25519];
+
+    Signature::new(&value, &kp)
+        .verify_with_allowed_schemes(&value, address, &only_ed25519)
+        .unwrap();
+
+    let secp_signature = Signature::new(&value, &secp_kp);
+    secp_signature.verify(&value, secp_address).unwrap();
+    assert_eq!(
+        secp_signature.verify_with_allowed_schemes(&value, secp_address, &only_ed25519),
+        Err(SuiError::DisallowedSignatureScheme {
+            scheme: SignatureScheme::Secp256k1
+        })
+    );
+}
+
+#[test]
+fn test_public_key_from_flagged_bytes() {
+    let (_, ed25519_kp): (_, AccountKeyPair) = get_key_pair();
+    let (_, secp256k1_kp): (_, Secp256k1KeyPair) = get_key_pair();
+    let keys = [
+        PublicKey::Ed25519KeyPair(ed25519_kp.public().clone()),
+        PublicKey::Secp256k1KeyPair(secp256k1_kp.public().clone()),
+    ];
+
+    for key in keys {
+        // The flagged bytes are exactly what `encode_base64` encodes.
+        let flagged = base64ct::Base64::decode_vec(&key.encode_base64()).unwrap();
+        assert_eq!(flagged[0], key.flag());
+        assert_eq!(PublicKey::from_flagged_bytes(&flagged).unwrap(), key);
+
+        let mut bad_flag = flagged;
+        bad_flag[0] = SignatureScheme::BLS12381.flag();
+        assert!(matches!(
+            PublicKey::from_flagged_bytes(&bad_flag),
+            Err(SuiError::KeyConversionError(_))
+        ));
+    }
+
+    assert!(PublicKey::from_flagged_bytes(&[]).is_err());
+}
+
+#[test]
+fn test_deterministic_key_pairs() {
+    let public_keys = |seed| {
+        deterministic_key_pairs::<AuthorityKeyPair>(4, seed)
+            .iter()
+            .map(|kp| AuthorityPublicKeyBytes::from(kp.public()))
+            .collect::<Vec<_>>()
+    };
+
+    let keys = public_keys([7; 32]);
+    assert_eq!(keys.len(), 4);
+    assert_eq!(keys, public_keys([7; 32]));
+    assert_ne!(keys, public_keys([8; 32]));
+
+    // Keys within one call are still distinct.
+    assert_eq!(
+        keys.iter().collect::<std::collections::BTreeSet<_>>().len(),
+        keys.len()
+    );
+}
+
+#[test]
+fn test_split_signature_bytes() {
+    let value = Foo("some data".to_string());
+    let (_, kp): (_, AccountKeyPair) = get_key_pair();
+    let (_, secp_kp): (_, Secp256k1KeyPair) = get_key_pair();
+
+    for signature in [
+        Signature::new(&value, &kp),
+        Signature::new(&value, &secp_kp),
+    ] {
+        let (scheme, sig, pk) = split_signature_bytes(signature.as_ref()).unwrap();
+        assert_eq!(scheme, signature.scheme());
+        assert_eq!(sig, signature.signature_bytes());
+        assert_eq!(pk, signature.public_key_bytes());
+
+        let bytes = signature.as_ref();
+        assert!(split_signature_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    assert!(split_signature_bytes(&[]).is_err());
+    assert!(split_signature_bytes(&[SignatureScheme::ED25519.flag()]).is_err());
+    assert!(split_signature_bytes(&[SignatureScheme::BLS12381.flag(); 97]).is_err());
+}
+
+#[test]
+fn test_signature_length_mismatch() {
+    let value = Foo("some data".to_string());
+    let (_, kp): (_, AccountKeyPair) = get_key_pair();
+    let (_, secp_kp): (_, Secp256k1KeyPair) = get_key_pair();
+
+    for (signature, expected) in [
+        (Signature::new(&value, &kp), Ed25519SuiSignature::LENGTH),
+        (
+            Signature::new(&value, &secp_kp),
+            Secp256k1SuiSignature::LENGTH,
+        ),
+    ] {
+        let scheme = signature.scheme();
+        let mut too_long = signature.as_ref().to_vec();
+        too_long.push(0);
+        let too_short = &signature.as_ref()[..expected - 1];
+
+        for bytes in [too_short, &too_long[..]] {
+            let length_error = SuiError::InvalidSignatureLength {
+                scheme,
+                expected,
+                got: bytes.len(),
+            };
+            // Parsed as the scheme's own signature type or through `Signature`.
+            let error = match scheme {
+                SignatureScheme::ED25519 => {
+                    <Ed25519SuiSignature as signature::Signature>::from_bytes(bytes).unwrap_err()
+                }
+                _ => {
+                    <Secp256k1SuiSignature as signature::Signature>::from_bytes(bytes).unwrap_err()
+                }
+            };
+            assert_eq!(SuiError::from(error), length_error);
+            let error = <Signature as signature::Signature>::from_bytes(bytes).unwrap_err();
+            assert_eq!(SuiError::from(error), length_error);
+            assert_eq!(split_signature_bytes(bytes).unwrap_err(), length_error);
+        }
+    }
+}
+
+#[test]
+fn test_key_pair_from_ed25519_seed() {
+    let (address, kp): (_, Ed25519KeyPair) = get_key_pair();
+    let seed: [u8; 32] = kp.copy().private().as_ref().try_into().unwrap();
+
+    let from_seed = SuiKeyPair::from_ed25519_seed(&seed).unwrap();
+    assert_eq!(SuiAddress::from(&from_seed.public()), address);
+
+    // The same key as imported through the flagged base64 encoding.
+    let mut bytes = vec![SignatureScheme::ED25519.flag()];
+    bytes.extend_from_slice(kp.public().as_ref());
+    bytes.extend_from_slice(&seed);
+    let decoded = SuiKeyPair::decode_base64(&base64ct::Base64::encode_string(&bytes)).unwrap();
+    assert_eq!(SuiAddress::from(&decoded.public()), address);
+    assert_eq!(from_seed.encode_base64(), decoded.encode_base64());
+}
+
+#[test]
+fn test_rotate_key_pair() {
+    let seed = [7u8; 32];
+    for scheme in [SignatureScheme::ED25519, SignatureScheme::Secp256k1] {
+        let (first, _) = derive_key_pair_from_path(&seed, None, &scheme).unwrap();
+
+        let (address, kp, index) = SuiKeyPair::rotate(&seed, &scheme, 0).unwrap();
+        assert_eq!(index, 1);
+        assert_eq!(address, SuiAddress::from(&kp.public()));
+        assert_ne!(address, first);
+
+        // Rotating from the same index always gives the same key.
+        let (again, _, again_index) = SuiKeyPair::rotate(&seed, &scheme, 0).unwrap();
+        assert_eq!((again, again_index), (address, index));
+
+        let (next, _, next_index) = SuiKeyPair::rotate(&seed, &scheme, index).unwrap();
+        assert_eq!(next_index, 2);
+        assert_ne!(next, address);
+    }
+
+    assert!(SuiKeyPair::rotate(&seed, &SignatureScheme::BLS12381, 0).is_err());
+    assert!(SuiKeyPair::rotate(&seed, &SignatureScheme::ED25519, u32::MAX).is_err());
+}
+
+#[test]
+fn test_signature_scheme_lists() {
+    let all = SignatureScheme::all();
+    let user = SignatureScheme::user_schemes();
+    assert!(!user.contains(&SignatureScheme::BLS12381));
+    assert!(user.contains(&SignatureScheme::ED25519));
+    assert!(user.contains(&SignatureScheme::Secp256k1));
+    assert!(user.iter().all(|scheme| all.contains(scheme)));
+
+    // Each scheme is listed once, and the only one left out for users is BLS12381.
+    let flags: std::collections::BTreeSet<_> = all.iter().map(|scheme| scheme.flag()).collect();
+    assert_eq!(flags.len(), all.len());
+    assert_eq!(all.len(), user.len() + 1);
+    assert!(all.contains(&SignatureScheme::BLS12381));
+
+    // Every user scheme can derive keys, which is what makes it usable for accounts.
+    for scheme in user {
+        derive_key_pair_from_path(&[0; 32], None, scheme).unwrap();
+    }
+    assert!(derive_key_pair_from_path(&[0; 32], None, &SignatureScheme::BLS12381).is_err());
+}
+
+#[test]
+fn test_verify_any() {
+    let value = Foo("some data".to_string());
+    let (address, kp): (_, AccountKeyPair) = get_key_pair();
+    let (secp_address, secp_kp): (_, Secp256k1KeyPair) = get_key_pair();
+    let (other_address, _): (_, AccountKeyPair) = get_key_pair();
+    let allowed = [other_address, address, secp_address];
+
+    let signature = Signature::new(&value, &kp);
+    assert_eq!(signature.verify_any(&value, &allowed), Ok(address));
+    assert_eq!(
+        Signature::new(&value, &secp_kp).verify_any(&value, &allowed),
+        Ok(secp_address)
+    );
+
+    // The signer is not in the set.
+    assert!(matches!(
+        signature.verify_any(&value, &[other_address, secp_address]),
+        Err(SuiError::IncorrectSigner { .. })
+    ));
+    // The signer is in the set, but the signature is over something else.
+    assert!(signature
+        .verify_any(&Foo("other data".to_string()), &allowed)
+        .is_err());
+}
+
+#[test]
+fn test_signature_display() {
+    let value = Foo("some data".to_string());
+    let (address, kp): (_, AccountKeyPair) = get_key_pair();
+    let (secp_address, secp_kp): (_, Secp256k1KeyPair) = get_key_pair();
+
+    let signature = Signature::new(&value, &kp);
+    assert_eq!(signature.to_string(), format!("ed25519:{address}"));
+    let secp_signature = Signature::new(&value, &secp_kp);
+    assert_eq!(
+        secp_signature.to_string(),
+        format!("secp256k1:{secp_address}")
+    );
+
+    // None of the signature bytes make it into the output.
+    let sig_base64 = base64ct::Base64::encode_string(signature.signature_bytes());
+    assert!(!signature.to_string().contains(&sig_base64));
+    assert!(format!("{:?}", signature).contains(&sig_base64));
+}
+
+#[test]
+fn test_signature_set_round_trip() {
+    let (_, kp1): (_, AccountKeyPair) = get_key_pair();
+    let (_, kp2): (_, AccountKeyPair) = get_key_pair();
+    let (_, secp_kp): (_, Secp256k1KeyPair) = get_key_pair();
+
+    let signatures: Vec<Signature> = vec![
+        Signer::<Signature>::sign(&secp_kp, b"first"),
+        Signer::<Signature>::sign(&kp1, b"second"),
+        Signer::<Signature>::sign(&kp2, b"third"),
+    ];
+    let mut set = SignatureSet::new(signatures.clone());
+    assert_eq!(set.len(), 3);
+    // Duplicates are dropped.
+    assert!(!set.insert(signatures[1].clone()));
+    assert_eq!(set.len(), 3);
+
+    let bytes = bcs::to_bytes(&set).unwrap();
+    assert_eq!(bcs::from_bytes::<SignatureSet>(&bytes).unwrap(), set);
+    assert!(bytes.len() < bcs::to_bytes(&signatures).unwrap().len());
+
+    let json = serde_json::to_string(&set).unwrap();
+    assert_eq!(serde_json::from_str::<SignatureSet>(&json).unwrap(), set);
+
+    // A truncated group is rejected.
+    let truncated = bcs::to_bytes(&(vec![0u8; 10], Vec::<u8>::new())).unwrap();
+    assert!(bcs::from_bytes::<SignatureSet>(&truncated).is_err());
+}
+
+#[test]
+fn test_signature_set_verify_all() {
+    let (addr1, kp1): (_, AccountKeyPair) = get_key_pair();
+    let (addr2, secp_kp): (_, Secp256k1KeyPair) = get_key_pair();
+    let (other_address, _): (_, AccountKeyPair) = get_key_pair();
+
+    let set = SignatureSet::new([
+        Signer::<Signature>::sign(&kp1, b"first"),
+        Signer::<Signature>::sign(&secp_kp, b"second"),
+    ]);
+    set.verify_all(&[(&b"first"[..], addr1), (&b"second"[..], addr2)])
+        .unwrap();
+
+    // One member signed a different message.
+    assert!(matches!(
+        set.verify_all(&[(&b"first"[..], addr1), (&b"other"[..], addr2)]),
+        Err(SuiError::InvalidSignature { .. })
+    ));
+    // No member was signed by this author.
+    assert!(matches!(
+        set.verify_all(&[(&b"first"[..], other_address)]),
+        Err(SuiError::IncorrectSigner { .. })
+    ));
+}
+
+#[test]
+fn test_signature_set_verify_all_author_signs_several_messages() {
+    let (addr1, kp1): (_, AccountKeyPair) = get_key_pair();
+    let (addr2, kp2): (_, AccountKeyPair) = get_key_pair();
+
+    let set = SignatureSet::new([
+        Signer::<Signature>::sign(&kp1, b"first"),
+        Signer::<Signature>::sign(&kp2, b"second"),
+        Signer::<Signature>::sign(&kp1, b"third"),
+    ]);
+    set.verify_all(&[
+        (&b"first"[..], addr1),
+        (&b"second"[..], addr2),
+        (&b"third"[..], addr1),
+    ])
+    .unwrap();
+
+    // The author signed two messages, but not this one.
+    assert!(matches!(
+        set.verify_all(&[(&b"third"[..], addr1), (&b"other"[..], addr1)]),
+        Err(SuiError::InvalidSignature { .. })
+    ));
+}
+
+#[test]
+fn test_verify_user_signatures_parallel() {
+    let (_, other_kp): (_, AccountKeyPair) = get_key_pair();
+    let mut items = Vec::new();
+    for i in 0..20 {
+        let message = format!("message {i}").into_bytes();
+        let item = if i % 2 == 0 {
+            let (address, kp): (_, AccountKeyPair) = get_key_pair();
+            (Signer::<Signature>::sign(&kp, &message), message, address)
+        } else {
+            let (address, kp): (_, Secp256k1KeyPair) = get_key_pair();
+            (Signer::<Signature>::sign(&kp, &message), message, address)
+        };
+        items.push(item);
+    }
+    // Scatter invalid entries across both schemes: a tampered message, a signature by another
+    // key than the author's, and a secp256k1 signature over another item's message.
+    items[3].1 = b"tampered".to_vec();
+    items[8].0 = Signer::<Signature>::sign(&other_kp, &items[8].1);
+    items[15].1 = items[1].1.clone();
+
+    let results = verify_user_signatures_parallel(&items);
+    assert_eq!(results.len(), items.len());
+    for (idx, result) in results.iter().enumerate() {
+        match idx {
+            3 | 15 => assert!(matches!(result, Err(SuiError::InvalidSignature { .. }))),
+            8 => assert!(matches!(result, Err(SuiError::IncorrectSigner { .. }))),
+            _ => assert!(result.is_ok(), "item {idx}: {result:?}"),
+        }
+    }
+    assert!(verify_user_signatures_parallel(&[]).is_empty());
+}
+
+#[test]
+fn test_authority_signature_verify_bytes() {
+    let (_, kp): (_, AuthorityKeyPair) = get_key_pair();
+    let (_, other_kp): (_, AuthorityKeyPair) = get_key_pair();
+    let author = AuthorityPublicKeyBytes::from(kp.public());
+    let other_author = AuthorityPublicKeyBytes::from(other_kp.public());
+    let value = Foo("some data".to_string());
+    let signature = AuthoritySignature::new(&value, &kp);
+
+    // Verifying the value and its serialized bytes agree, whether the signature is valid or not.
+    for (message, author) in [
+        (Foo("some data".to_string()), author),
+        (Foo("some data".to_string()), other_author),
+        (Foo("other data".to_string()), author),
+    ] {
+        assert_eq!(
+            signature.verify(&message, author).is_ok(),
+            signature
+                .verify_bytes(&signable_bytes(&message), author)
+                .is_ok()
+        );
+    }
+    assert!(signature
+        .verify_bytes(&signable_bytes(&value), author)
+        .is_ok());
+}
+
+#[test]
+fn test_signature_from_parts() {
+    let value = Foo("some data".to_string());
+    let (address, kp): (_, AccountKeyPair) = get_key_pair();
+    let (secp_address, secp_kp): (_, Secp256k1KeyPair) = get_key_pair();
+
+    for (signature, author) in [
+        (Signature::new(&value, &kp), address),
+        (Signature::new(&value, &secp_kp), secp_address),
+    ] {
+        let sig = signature.signature_bytes();
+        let pk = signature.public_key_bytes();
+        let assembled = Signature::from_parts(signature.scheme(), sig, pk).unwrap();
+        assert_eq!(assembled, signature);
+        assembled.verify(&value, author).unwrap();
+
+        // Truncated or swapped components are rejected.
+        assert!(Signature::from_parts(signature.scheme(), &sig[1..], pk).is_err());
+        assert!(Signature::from_parts(signature.scheme(), sig, &pk[1..]).is_err());
+        assert!(Signature::from_parts(signature.scheme(), pk, sig).is_err());
+    }
+
+    // The parts of one scheme do not fit the other, and authority signatures are not accepted.
+    let signature = Signature::new(&value, &kp);
+    for scheme in [SignatureScheme::Secp256k1, SignatureScheme::BLS12381] {
+        assert!(Signature::from_parts(
+            scheme,
+            signature.signature_bytes(),
+            signature.public_key_bytes()
+        )
+        .is_err());
+    }
+}
+
+#[test]
+fn test_high_s_secp256k1_signature_rejected() {
+    // The order of the secp256k1 curve, big-endian.
+    const ORDER: [u8; 32] = [
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xfe, 0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36,
+        0x41, 0x41,
+    ];
+    let value = Foo("some data".to_string());
+    let (address, kp): (_, Secp256k1KeyPair) = get_key_pair();
+    let signature = Signature::new(&value, &kp);
+    signature.verify(&value, address).unwrap();
+
+    // Malleate the signature into (r, n - s), flipping the recovery id to match.
+    let mut sig = signature.signature_bytes().to_vec();
+    let mut borrow = 0;
+    for i in (0..32).rev() {
+        let diff = ORDER[i] as i16 - sig[32 + i] as i16 - borrow;
+        sig[32 + i] = diff.rem_euclid(256) as u8;
+        borrow = (diff < 0) as i16;
+    }
+    sig[64] ^= 1;
+    let malleated = Signature::from_parts(
+        SignatureScheme::Secp256k1,
+        &sig,
+        signature.public_key_bytes(),
+    )
+    .unwrap();
+    assert_ne!(malleated, signature);
+
+    assert!(matches!(
+        malleated.verify(&value, address),
+        Err(SuiError::NonCanonicalSignature { .. })
+    ));
+}
+
+#[test]
+fn test_owns_public_key() {
+    let (_, kp): (_, Ed25519KeyPair) = get_key_pair();
+    let (_, other_kp): (_, Ed25519KeyPair) = get_key_pair();
+    let (_, secp_kp): (_, Secp256k1KeyPair) = get_key_pair();
+    let kp = SuiKeyPair::Ed25519SuiKeyPair(kp);
+    let other_kp = SuiKeyPair::Ed25519SuiKeyPair(other_kp);
+    let secp_kp = SuiKeyPair::Secp256k1SuiKeyPair(secp_kp);
+
+    for key_pair in [&kp, &other_kp, &secp_kp] {
+        assert!(key_pair.owns_public_key(&key_pair.public()));
+    }
+    assert!(!kp.owns_public_key(&other_kp.public()));
+    assert!(!kp.owns_public_key(&secp_kp.public()));
+    assert!(!secp_kp.owns_public_key(&kp.public()));
+}
+
+#[test]
+fn test_register_authority() {
+    let (_, kp): (_, AuthorityKeyPair) = get_key_pair();
+    let (_, other_kp): (_, AuthorityKeyPair) = get_key_pair();
+    let address = dbg_addr(1);
+    let pop = generate_proof_of_possession(&kp, address);
+
+    let name = register_authority(kp.public().as_bytes(), &pop, address).unwrap();
+    assert_eq!(name, AuthorityPublicKeyBytes::from(kp.public()));
+
+    // The proof is bound to both the key and the address.
+    assert!(matches!(
+        register_authority(other_kp.public().as_bytes(), &pop, address),
+        Err(SuiError::InvalidSignature { .. })
+    ));
+    assert!(matches!(
+        register_authority(kp.public().as_bytes(), &pop, dbg_addr(2)),
+        Err(SuiError::InvalidSignature { .. })
+    ));
+    let other_pop = generate_proof_of_possession(&other_kp, address);
+    assert!(register_authority(kp.public().as_bytes(), &other_pop, address).is_err());
+
+    assert!(matches!(
+        register_authority(&[0; 3], &pop, address),
+        Err(SuiError::KeyConversionError(_))
+    ));
+}
+
+#[test]
+fn test_generate_authority_key_with_pop() {
+    let address = dbg_addr(1);
+    let (kp, pop) = generate_authority_key_with_pop(address);
+
+    verify_proof_of_possession(&pop, kp.public(), address).unwrap();
+    assert!(verify_proof_of_possession(&pop, kp.public(), dbg_addr(2)).is_err());
+}
+
+#[test]
+fn test_verify_with_public_key() {
+    let value = Foo("some data".to_string());
+    let other_value = Foo("other data".to_string());
+    let (address, kp): (_, Ed25519KeyPair) = get_key_pair();
+    let (secp_address, secp_kp): (_, Secp256k1KeyPair) = get_key_pair();
+    let kp = SuiKeyPair::Ed25519SuiKeyPair(kp);
+    let secp_kp = SuiKeyPair::Secp256k1SuiKeyPair(secp_kp);
+
+    for (key_pair, author, other) in [(&kp, address, &secp_kp), (&secp_kp, secp_address, &kp)] {
+        let signature = Signature::new(&value, key_pair);
+        let pk = key_pair.public();
+
+        // Both paths agree on valid and invalid signatures.
+        assert!(signature.verify(&value, author).is_ok());
+        assert!(signature.verify_with_public_key(&value, &pk).is_ok());
+        assert!(signature.verify(&other_value, author).is_err());
+        assert!(signature.verify_with_public_key(&other_value, &pk).is_err());
+
+        // A key other than the signer's is rejected, whatever its scheme.
+        let (_, same_scheme_kp) = match key_pair {
+            SuiKeyPair::Ed25519SuiKeyPair(_) => {
+                let (a, k): (_, Ed25519KeyPair) = get_key_pair();
+                (a, SuiKeyPair::Ed25519SuiKeyPair(k))
+            }
+            SuiKeyPair::Secp256k1SuiKeyPair(_) => {
+                let (a, k): (_, Secp256k1KeyPair) = get_key_pair();
+                (a, SuiKeyPair::Secp256k1SuiKeyPair(k))
+            }
+        };
+        for wrong in [same_scheme_kp.public(), other.public()] {
+            assert!(matches!(
+                signature.verify_with_public_key(&value, &wrong),
+                Err(SuiError::IncorrectSigner { .. })
+            ));
+        }
+    }
+}
@@ -13,6 +13,7 @@ use crate::crypto::bcs_signable_test::{get_obligation_input, Foo};
 use crate::crypto::Secp256k1SuiSignature;
 use crate::crypto::SuiKeyPair;
 use crate::crypto::{get_key_pair, AccountKeyPair, AuthorityKeyPair, AuthorityPublicKeyBytes};
+use crate::gas::KindGasMultipliers;
 use crate::messages_checkpoint::CheckpointContents;
 use crate::messages_checkpoint::CheckpointSummary;
 use crate::object::Owner;
@@ -174,6 +175,14 @@ fn test_certificates() {
 
     assert!(c.verify(&committee).is_ok());
 
+    // The certificate exposes the message the authorities signed, so each signature can be
+    // checked against it directly.
+    let message = c.signed_message_bytes();
+    let mut expected = Vec::new();
+    c.signed_data.write(&mut expected);
+    assert_eq!(message, expected);
+    signature::Verifier::verify(sec1.public(), &message, &v1.auth_sign_info.signature).unwrap();
+
     let mut builder = SignatureAggregator::try_new(transaction, &committee).unwrap();
     assert!(builder
         .append(v1.auth_sign_info.authority, v1.auth_sign_info.signature)
@@ -281,6 +290,44 @@ fn test_bitmap_out_of_range() {
         .is_err());
 }
 
+#[test]
+fn test_repeated_verification_uses_current_signers() {
+    let message: messages_tests::Foo = Foo("some data".to_string());
+    let mut signatures: Vec<(AuthorityName, AuthoritySignature)> = Vec::new();
+    let mut authorities: BTreeMap<AuthorityPublicKeyBytes, u64> = BTreeMap::new();
+    for i in 0..5 {
+        let (_, sec): (_, AuthorityKeyPair) = get_key_pair();
+        let sig = AuthoritySignature::new(&message, &sec);
+        authorities.insert(AuthorityPublicKeyBytes::from(sec.public()), 1);
+        if i < 4 {
+            signatures.push((AuthorityPublicKeyBytes::from(sec.public()), sig));
+        }
+    }
+
+    let committee = Committee::new(0, authorities).unwrap();
+    let mut quorum =
+        AuthorityStrongQuorumSignInfo::new_with_signatures(signatures, &committee).unwrap();
+
+    // Verifying repeatedly gives the same result as the first verification.
+    for _ in 0..3 {
+        let (mut obligation, idx) = get_obligation_input(&message);
+        quorum
+            .add_to_verification_obligation(&committee, &mut obligation, idx)
+            .unwrap();
+        assert_eq!(obligation.public_keys[idx].len(), 4);
+        assert!(obligation.verify_all().is_ok());
+    }
+
+    // Dropping a signer after the first verification is not masked by the earlier result.
+    let first = quorum.signers_map.min().unwrap();
+    quorum.signers_map.remove(first);
+    let (mut obligation, idx) = get_obligation_input(&message);
+    assert!(matches!(
+        quorum.add_to_verification_obligation(&committee, &mut obligation, idx),
+        Err(SuiError::CertificateRequiresQuorum)
+    ));
+}
+
 #[test]
 fn test_reject_extra_public_key() {
     let message: messages_tests::Foo = Foo("some data".to_string());
@@ -698,3 +745,270 @@ fn verify_sender_signature_correctly_with_flag() {
         .verify(&transaction.signed_data, &committee)
         .is_err());
 }
+
+#[test]
+fn test_mutated_object_ids() {
+    let (sender, _): (_, AccountKeyPair) = get_key_pair();
+    let object = Object::with_id_owner_for_testing(ObjectID::random(), sender);
+    let gas = Object::with_id_owner_for_testing(ObjectID::random(), sender);
+    let package = Object::immutable_with_id_for_testing(ObjectID::random());
+
+    let data = TransactionData::new_transfer(
+        dbg_addr(2),
+        object.compute_object_reference(),
+        sender,
+        gas.compute_object_reference(),
+        10000,
+    );
+    let inputs = InputObjects::new(vec![
+        (
+            InputObjectKind::ImmOrOwnedMoveObject(object.compute_object_reference()),
+            object.clone(),
+        ),
+        (
+            InputObjectKind::ImmOrOwnedMoveObject(package.compute_object_reference()),
+            package,
+        ),
+        (
+            InputObjectKind::ImmOrOwnedMoveObject(gas.compute_object_reference()),
+            gas.clone(),
+        ),
+    ]);
+
+    // The immutable object is read but never mutated.
+    assert_eq!(
+        data.mutated_object_ids(&inputs),
+        vec![object.id(), gas.id()]
+    );
+}
+
+#[test]
+fn test_input_objects_contains_id() {
+    let (sender, _): (_, AccountKeyPair) = get_key_pair();
+    let owned = Object::with_id_owner_for_testing(ObjectID::random(), sender);
+    let immutable = Object::immutable_with_id_for_testing(ObjectID::random());
+
+    let inputs = InputObjects::new(vec![
+        (
+            InputObjectKind::ImmOrOwnedMoveObject(owned.compute_object_reference()),
+            owned.clone(),
+        ),
+        (
+            InputObjectKind::ImmOrOwnedMoveObject(immutable.compute_object_reference()),
+            immutable.clone(),
+        ),
+    ]);
+
+    assert!(inputs.contains_id(&owned.id()));
+    assert!(inputs.contains_id(&immutable.id()));
+    assert!(!inputs.contains_id(&ObjectID::random()));
+    assert!(!InputObjects::new(vec![]).contains_id(&owned.id()));
+}
+
+#[test]
+fn test_input_objects_classify() {
+    let (sender, _): (_, AccountKeyPair) = get_key_pair();
+    let owned = Object::with_id_owner_for_testing(ObjectID::random(), sender);
+    let child = Object::with_object_owner_for_testing(ObjectID::random(), owned.id());
+    let immutable = Object::immutable_with_id_for_testing(ObjectID::random());
+    let mut shared = Object::with_id_owner_for_testing(ObjectID::random(), sender);
+    shared.owner = Owner::Shared;
+
+    let inputs = InputObjects::new(vec![
+        (
+            InputObjectKind::SharedMoveObject(shared.id()),
+            shared.clone(),
+        ),
+        (
+            InputObjectKind::ImmOrOwnedMoveObject(owned.compute_object_reference()),
+            owned.clone(),
+        ),
+        (
+            InputObjectKind::ImmOrOwnedMoveObject(immutable.compute_object_reference()),
+            immutable.clone(),
+        ),
+        (
+            InputObjectKind::ImmOrOwnedMoveObject(child.compute_object_reference()),
+            child.clone(),
+        ),
+    ]);
+
+    let classification = inputs.classify();
+    assert_eq!(
+        classification,
+        InputObjectClassification {
+            immutable: vec![immutable.compute_object_reference()],
+            owned: vec![
+                owned.compute_object_reference(),
+                child.compute_object_reference()
+            ],
+            shared: vec![shared.compute_object_reference()],
+        }
+    );
+    assert_eq!(InputObjects::new(vec![]).classify(), Default::default());
+}
+
+#[test]
+fn test_fast_path_eligible() {
+    let (sender, _): (_, AccountKeyPair) = get_key_pair();
+    let object = Object::with_id_owner_for_testing(ObjectID::random(), sender);
+    let gas = Object::with_id_owner_for_testing(ObjectID::random(), sender);
+    let package = Object::immutable_with_id_for_testing(ObjectID::random());
+
+    let transfer = TransactionData::new_transfer(
+        dbg_addr(2),
+        object.compute_object_reference(),
+        sender,
+        gas.compute_object_reference(),
+        10000,
+    );
+    assert!(transfer.is_owned_only());
+    assert_eq!(transfer.fast_path_eligible(), Ok(()));
+
+    let shared_id = ObjectID::random();
+    let call = TransactionData::new_move_call(
+        sender,
+        package.compute_object_reference(),
+        Identifier::new("module").unwrap(),
+        Identifier::new("function").unwrap(),
+        vec![],
+        gas.compute_object_reference(),
+        vec![
+            CallArg::Object(ObjectArg::ImmOrOwnedObject(
+                object.compute_object_reference(),
+            )),
+            CallArg::Object(ObjectArg::SharedObject(shared_id)),
+        ],
+        10000,
+    );
+    assert!(!call.is_owned_only());
+    assert_eq!(call.fast_path_eligible(), Err(vec![shared_id]));
+}
+
+#[test]
+fn test_quorum_signers_as_names() {
+    let message: messages_tests::Foo = Foo("some data".to_string());
+    let mut signatures: Vec<(AuthorityName, AuthoritySignature)> = Vec::new();
+    let mut authorities: BTreeMap<AuthorityPublicKeyBytes, u64> = BTreeMap::new();
+    for i in 0..5 {
+        let (_, sec): (_, AuthorityKeyPair) = get_key_pair();
+        authorities.insert(AuthorityPublicKeyBytes::from(sec.public()), 1);
+        if i < 4 {
+            let sig = AuthoritySignature::new(&message, &sec);
+            signatures.push((AuthorityPublicKeyBytes::from(sec.public()), sig));
+        }
+    }
+
+    let committee = Committee::new(0, authorities).unwrap();
+    let quorum =
+        AuthorityStrongQuorumSignInfo::new_with_signatures(signatures.clone(), &committee).unwrap();
+
+    let mut expected: Vec<_> = signatures.iter().map(|(name, _)| *name).collect();
+    expected.sort();
+    let names = quorum.signers_as_names(&committee).unwrap();
+    assert_eq!(names, expected);
+    assert_eq!(
+        names
+            .iter()
+            .map(|name| committee.authority_index(name).unwrap())
+            .collect::<Vec<_>>(),
+        quorum.signers_map.iter().collect::<Vec<_>>()
+    );
+
+    // Only the debug representation lists the names.
+    let debug = serde_json::to_value(quorum.to_debug(&committee).unwrap()).unwrap();
+    assert_eq!(debug["signers"], serde_json::to_value(&names).unwrap());
+    let wire = serde_json::to_value(&quorum).unwrap();
+    assert!(wire.get("signers").is_none());
+    assert!(wire.get("signers_map").is_some());
+}
+
+#[test]
+fn test_verify_matches_bytes() {
+    let (sender, _): (_, AccountKeyPair) = get_key_pair();
+    let object = Object::with_id_owner_for_testing(ObjectID::random(), sender);
+    let gas = Object::with_id_owner_for_testing(ObjectID::random(), sender);
+    let data = TransactionData::new_transfer(
+        dbg_addr(2),
+        object.compute_object_reference(),
+        sender,
+        gas.compute_object_reference(),
+        10000,
+    );
+
+    let bytes = signable_bytes(&data);
+    data.verify_matches_bytes(&bytes).unwrap();
+
+    // A wallet that changed a byte, e.g. by encoding a different gas budget.
+    let mut changed = bytes.clone();
+    *changed.last_mut().unwrap() ^= 1;
+    assert!(matches!(
+        data.verify_matches_bytes(&changed),
+        Err(SuiError::TransactionBytesMismatch { error }) if error.contains(&format!("byte {}", bytes.len() - 1))
+    ));
+    // Truncated bytes, and plain BCS without the domain prefix, do not match either.
+    assert!(data
+        .verify_matches_bytes(&bytes[..bytes.len() - 1])
+        .is_err());
+    assert!(data
+        .verify_matches_bytes(&bcs::to_bytes(&data).unwrap())
+        .is_err());
+}
+
+#[test]
+fn test_sub_transactions_touching() {
+    let (sender, _): (_, AccountKeyPair) = get_key_pair();
+    let transfers: Vec<_> = (0..3).map(|_| random_object_ref()).collect();
+    let kind = TransactionKind::Batch(
+        transfers
+            .iter()
+            .map(|object_ref| {
+                SingleTransactionKind::TransferObject(TransferObject {
+                    recipient: dbg_addr(2),
+                    object_ref: *object_ref,
+                })
+            })
+            .collect(),
+    );
+    let gas = random_object_ref();
+    let data = TransactionData::new(kind, sender, gas, 10000);
+
+    assert_eq!(data.sub_transactions_touching(&transfers[1].0), vec![1]);
+    // The gas object is not an input of any of the transactions in the batch.
+    assert!(data.sub_transactions_touching(&gas.0).is_empty());
+    assert!(data
+        .sub_transactions_touching(&ObjectID::random())
+        .is_empty());
+}
+
+#[test]
+fn test_minimum_gas_budget_grows_with_inputs() {
+    let gas_params = EpochGasParams {
+        storage_gas_price: 1,
+        consensus_surcharge: 0,
+        kind_gas_multipliers: KindGasMultipliers::default(),
+    };
+    let transfer = || {
+        SingleTransactionKind::TransferObject(TransferObject {
+            recipient: dbg_addr(2),
+            object_ref: random_object_ref(),
+        })
+    };
+
+    let single = TransactionData::new(
+        TransactionKind::Single(transfer()),
+        dbg_addr(1),
+        random_object_ref(),
+        10000,
+    );
+    let batch = TransactionData::new(
+        TransactionKind::Batch((0..10).map(|_| transfer()).collect()),
+        dbg_addr(1),
+        random_object_ref(),
+        10000,
+    );
+
+    let single_minimum = single.minimum_gas_budget(&gas_params);
+    assert!(single_minimum >= *gas::MIN_GAS_BUDGET);
+    assert!(batch.minimum_gas_budget(&gas_params) > single_minimum);
+}
@@ -482,6 +482,7 @@ pub async fn wait_for_all_txes(wait_digests: Vec<TransactionDigest>, state: Arc<
             .handle_batch_streaming(BatchInfoRequest {
                 start: max_seq,
                 length: 1000,
+                headers_only: false,
             })
             .await
             .unwrap(),
@@ -518,6 +519,7 @@ pub async fn wait_for_all_txes(wait_digests: Vec<TransactionDigest>, state: Arc<
                                     .handle_batch_streaming(BatchInfoRequest {
                                         start: max_seq,
                                         length: 1000,
+                                        headers_only: false,
                                     })
                                     .await
                                     .unwrap(),
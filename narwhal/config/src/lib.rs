@@ -127,6 +127,14 @@ pub struct Parameters {
     /// is not reached.
     #[serde(with = "duration_format")]
     pub max_batch_delay: Duration,
+    /// Whether the workers drop duplicate transactions (by digest) within the batch they are
+    /// currently assembling. Duplicates across batches are not detected.
+    #[serde(default)]
+    pub dedup_batch_transactions: bool,
+    /// The maximum size of a single transaction accepted by the workers. Larger transactions are
+    /// rejected back to their submitter. Denominated in bytes, unlimited by default.
+    #[serde(default = "Parameters::default_max_transaction_bytes")]
+    pub max_transaction_bytes: usize,
     /// The parameters for the block synchronizer
     pub block_synchronizer: BlockSynchronizerParameters,
     /// The parameters for the Consensus API gRPC server
@@ -137,6 +145,10 @@ pub struct Parameters {
     pub prometheus_metrics: PrometheusMetricsParameters,
     /// Network admin server ports for primary & worker.
     pub network_admin_server: NetworkAdminServerParameters,
+    /// Templates of parameterized network routes, e.g. `/block/{digest}`. The network metrics
+    /// label every route matching a template with the template itself.
+    #[serde(default)]
+    pub network_route_templates: Vec<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -286,16 +298,23 @@ impl Default for Parameters {
             sync_retry_nodes: 3,
             batch_size: 500_000,
             max_batch_delay: Duration::from_millis(100),
+            dedup_batch_transactions: false,
+            max_transaction_bytes: Parameters::default_max_transaction_bytes(),
             block_synchronizer: BlockSynchronizerParameters::default(),
             consensus_api_grpc: ConsensusAPIGrpcParameters::default(),
             max_concurrent_requests: 500_000,
             prometheus_metrics: PrometheusMetricsParameters::default(),
             network_admin_server: NetworkAdminServerParameters::default(),
+            network_route_templates: Vec::new(),
         }
     }
 }
 
 impl Parameters {
+    fn default_max_transaction_bytes() -> usize {
+        usize::MAX
+    }
+
     pub fn tracing(&self) {
         info!("Header size set to {} B", self.header_size);
         info!(
@@ -313,6 +332,14 @@ impl Parameters {
             "Max batch delay set to {} ms",
             self.max_batch_delay.as_millis()
         );
+        info!(
+            "Batch transaction deduplication set to {}",
+            self.dedup_batch_transactions
+        );
+        info!(
+            "Max transaction size set to {} B",
+            self.max_transaction_bytes
+        );
         info!(
             "Synchronize range timeout set to {} s",
             self.block_synchronizer.range_synchronize_timeout.as_secs()
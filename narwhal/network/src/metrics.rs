@@ -6,7 +6,7 @@ use prometheus::{
     register_int_gauge_vec_with_registry, HistogramTimer, HistogramVec, IntCounterVec, IntGaugeVec,
     Registry,
 };
-use std::sync::Arc;
+use std::{borrow::Cow, sync::Arc};
 
 #[derive(Clone, Debug)]
 pub struct NetworkConnectionMetrics {
@@ -42,6 +42,17 @@ pub struct NetworkMetrics {
     inflight_requests: IntGaugeVec,
     /// Failed requests by route
     errors: IntCounterVec,
+    /// Bytes sent to each peer, counting request bodies for outbound requests and response
+    /// bodies for inbound ones
+    bytes_sent: IntCounterVec,
+    /// Bytes received from each peer, counting response bodies for outbound requests and
+    /// request bodies for inbound ones
+    bytes_received: IntCounterVec,
+    /// Whether the request is initiated by us
+    outbound: bool,
+    /// Whether to label the byte counters by peer. When disabled, all peers share one label
+    /// to keep the metric cardinality low.
+    peer_labels: bool,
 }
 
 const LATENCY_SEC_BUCKETS: &[f64] = &[
@@ -104,6 +115,22 @@ impl NetworkMetrics {
         )
         .unwrap();
 
+        let bytes_sent = register_int_counter_vec_with_registry!(
+            format!("{node}_{direction}_bytes_sent"),
+            "Number of body bytes sent by peer",
+            &["peer_id"],
+            registry,
+        )
+        .unwrap();
+
+        let bytes_received = register_int_counter_vec_with_registry!(
+            format!("{node}_{direction}_bytes_received"),
+            "Number of body bytes received by peer",
+            &["peer_id"],
+            registry,
+        )
+        .unwrap();
+
         Self {
             requests,
             request_latency,
@@ -111,6 +138,33 @@ impl NetworkMetrics {
             response_size,
             inflight_requests,
             errors,
+            bytes_sent,
+            bytes_received,
+            outbound: direction == "outbound",
+            peer_labels: true,
+        }
+    }
+
+    /// Enable or disable the `peer_id` label on the byte counters.
+    pub fn with_peer_labels(mut self, enabled: bool) -> Self {
+        self.peer_labels = enabled;
+        self
+    }
+
+    fn peer_label(&self, peer: Option<&anemo::PeerId>) -> String {
+        match peer {
+            Some(peer) if self.peer_labels => format!("{peer}"),
+            Some(_) => "all".to_owned(),
+            None => "unknown".to_owned(),
+        }
+    }
+
+    /// The counters for bytes in the request and in the response, respectively.
+    fn request_response_bytes(&self) -> (&IntCounterVec, &IntCounterVec) {
+        if self.outbound {
+            (&self.bytes_sent, &self.bytes_received)
+        } else {
+            (&self.bytes_received, &self.bytes_sent)
         }
     }
 }
@@ -118,11 +172,39 @@ impl NetworkMetrics {
 #[derive(Clone)]
 pub struct MetricsMakeCallbackHandler {
     metrics: Arc<NetworkMetrics>,
+    /// Templates of parameterized routes, e.g. `/block/{digest}`. A route matching a template
+    /// is labelled with the template, so that every request to it shares one label.
+    route_templates: Arc<[String]>,
 }
 
 impl MetricsMakeCallbackHandler {
     pub fn new(metrics: Arc<NetworkMetrics>) -> Self {
-        Self { metrics }
+        Self::with_route_templates(metrics, Vec::<String>::new())
+    }
+
+    /// Like `new`, collapsing the routes that match one of `templates` into one label each. In
+    /// a template, a segment in braces such as `{digest}` matches any single path segment.
+    pub fn with_route_templates<I, S>(metrics: Arc<NetworkMetrics>, templates: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            metrics,
+            route_templates: templates.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// The label for `route`: the first template it matches, or the route itself.
+    pub fn normalize_route<'a>(&'a self, route: &'a str) -> Cow<'a, str> {
+        match self
+            .route_templates
+            .iter()
+            .find(|template| route_matches_template(route, template))
+        {
+            Some(template) => Cow::Borrowed(template.as_str()),
+            None => Cow::Borrowed(route),
+        }
     }
 }
 
@@ -130,7 +212,7 @@ impl MakeCallbackHandler for MetricsMakeCallbackHandler {
     type Handler = MetricsResponseHandler;
 
     fn make_handler(&self, request: &anemo::Request<bytes::Bytes>) -> Self::Handler {
-        let route = request.route().to_owned();
+        let route = self.normalize_route(request.route()).into_owned();
 
         self.metrics.requests.with_label_values(&[&route]).inc();
         self.metrics
@@ -142,6 +224,12 @@ impl MakeCallbackHandler for MetricsMakeCallbackHandler {
             .with_label_values(&[&route])
             .observe(request.body().len() as f64);
 
+        let peer = self.metrics.peer_label(request.peer_id());
+        let (request_bytes, _) = self.metrics.request_response_bytes();
+        request_bytes
+            .with_label_values(&[&peer])
+            .inc_by(request.body().len() as u64);
+
         let timer = self
             .metrics
             .request_latency
@@ -152,6 +240,7 @@ impl MakeCallbackHandler for MetricsMakeCallbackHandler {
             metrics: self.metrics.clone(),
             timer,
             route,
+            peer,
         }
     }
 }
@@ -162,6 +251,7 @@ pub struct MetricsResponseHandler {
     #[allow(unused)]
     timer: HistogramTimer,
     route: String,
+    peer: String,
 }
 
 impl ResponseHandler for MetricsResponseHandler {
@@ -171,6 +261,11 @@ impl ResponseHandler for MetricsResponseHandler {
             .with_label_values(&[&self.route])
             .observe(response.body().len() as f64);
 
+        let (_, response_bytes) = self.metrics.request_response_bytes();
+        response_bytes
+            .with_label_values(&[&self.peer])
+            .inc_by(response.body().len() as u64);
+
         if !response.status().is_success() {
             let status = response.status().to_u16().to_string();
             self.metrics
@@ -181,6 +276,8 @@ impl ResponseHandler for MetricsResponseHandler {
     }
 
     fn on_error<E>(self, _error: &E) {
+        // Failed responses are labelled with their status code above. This is a request that
+        // never got a response, and the error type is opaque here.
         self.metrics
             .errors
             .with_label_values(&[&self.route, "unknown"])
@@ -188,6 +285,25 @@ impl ResponseHandler for MetricsResponseHandler {
     }
 }
 
+/// Whether `route` matches `template` segment by segment, where a template segment in
+/// braces matches anything.
+fn route_matches_template(route: &str, template: &str) -> bool {
+    let mut route_segments = route.split('/');
+    let mut template_segments = template.split('/');
+    loop {
+        match (route_segments.next(), template_segments.next()) {
+            (None, None) => return true,
+            (Some(segment), Some(pattern)) => {
+                let placeholder = pattern.starts_with('{') && pattern.ends_with('}');
+                if !placeholder && segment != pattern {
+                    return false;
+                }
+            }
+            _ => return false,
+        }
+    }
+}
+
 impl Drop for MetricsResponseHandler {
     fn drop(&mut self) {
         self.metrics
@@ -196,3 +312,119 @@ impl Drop for MetricsResponseHandler {
             .dec();
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use anemo::PeerId;
+    use bytes::Bytes;
+
+    fn request(peer: PeerId, len: usize) -> anemo::Request<Bytes> {
+        let mut request = anemo::Request::new(Bytes::from(vec![0u8; len]));
+        request.extensions_mut().insert(peer);
+        request
+    }
+
+    fn response(len: usize) -> anemo::Response<Bytes> {
+        anemo::Response::new(Bytes::from(vec![0u8; len]))
+    }
+
+    #[test]
+    fn bytes_by_peer() {
+        let registry = Registry::new();
+        let outbound = Arc::new(NetworkMetrics::new("test", "outbound", &registry));
+        let inbound = Arc::new(NetworkMetrics::new("test", "inbound", &registry));
+        let peer = PeerId([1; 32]);
+        let label = format!("{peer}");
+
+        // We send the request and receive the response.
+        let handler = MetricsMakeCallbackHandler::new(outbound.clone());
+        handler
+            .make_handler(&request(peer, 10))
+            .on_response(&response(25));
+        handler
+            .make_handler(&request(peer, 5))
+            .on_response(&response(0));
+        assert_eq!(outbound.bytes_sent.with_label_values(&[&label]).get(), 15);
+        assert_eq!(
+            outbound.bytes_received.with_label_values(&[&label]).get(),
+            25
+        );
+
+        // We receive the request and send the response.
+        let handler = MetricsMakeCallbackHandler::new(inbound.clone());
+        handler
+            .make_handler(&request(peer, 10))
+            .on_response(&response(25));
+        assert_eq!(
+            inbound.bytes_received.with_label_values(&[&label]).get(),
+            10
+        );
+        assert_eq!(inbound.bytes_sent.with_label_values(&[&label]).get(), 25);
+    }
+
+    #[test]
+    fn bytes_without_peer_labels() {
+        let metrics = Arc::new(
+            NetworkMetrics::new("test", "outbound", &Registry::new()).with_peer_labels(false),
+        );
+        let handler = MetricsMakeCallbackHandler::new(metrics.clone());
+        handler
+            .make_handler(&request(PeerId([1; 32]), 10))
+            .on_response(&response(1));
+        handler
+            .make_handler(&request(PeerId([2; 32]), 10))
+            .on_response(&response(1));
+
+        assert_eq!(metrics.bytes_sent.with_label_values(&["all"]).get(), 20);
+        assert_eq!(metrics.bytes_received.with_label_values(&["all"]).get(), 2);
+    }
+
+    #[test]
+    fn error_status_labels() {
+        let metrics = Arc::new(NetworkMetrics::new("test", "outbound", &Registry::new()));
+        let handler = MetricsMakeCallbackHandler::new(metrics.clone());
+        let request = request(PeerId([1; 32]), 0);
+        let route = request.route().to_owned();
+
+        // Successful responses are not errors.
+        handler.make_handler(&request).on_response(&response(0));
+        assert_eq!(
+            metrics.errors.with_label_values(&[&route, "unknown"]).get(),
+            0
+        );
+
+        // Any error without a response gets the same label, whatever its type.
+        let connection = std::io::Error::from(std::io::ErrorKind::ConnectionReset);
+        handler.make_handler(&request).on_error(&connection);
+        handler.make_handler(&request).on_error(&"something else");
+        assert_eq!(
+            metrics.errors.with_label_values(&[&route, "unknown"]).get(),
+            2
+        );
+    }
+
+    #[test]
+    fn parameterized_routes_share_a_label() {
+        let metrics = Arc::new(NetworkMetrics::new("test", "inbound", &Registry::new()));
+        let handler =
+            MetricsMakeCallbackHandler::with_route_templates(metrics.clone(), ["/block/{digest}"]);
+        for route in ["/block/aaa", "/block/bbb"] {
+            handler
+                .make_handler(&request(PeerId([1; 32]), 0).with_route(route))
+                .on_response(&response(0));
+        }
+
+        assert_eq!(handler.normalize_route("/block/aaa"), "/block/{digest}");
+        assert_eq!(
+            metrics
+                .requests
+                .with_label_values(&["/block/{digest}"])
+                .get(),
+            2
+        );
+        // Routes outside of any template are left alone.
+        assert_eq!(handler.normalize_route("/block/aaa/bbb"), "/block/aaa/bbb");
+        assert_eq!(handler.normalize_route("/header/aaa"), "/header/aaa");
+    }
+}
@@ -391,6 +391,8 @@ impl Node {
                 parameters.clone(),
                 store.batch_store.clone(),
                 metrics.clone(),
+                /* validity */ None,
+                /* rx_flush */ None,
             );
             handles.extend(worker_handles);
         }
@@ -252,16 +252,22 @@ impl Primary {
 
         let service = ServiceBuilder::new()
             .layer(TraceLayer::new())
-            .layer(CallbackLayer::new(MetricsMakeCallbackHandler::new(
-                inbound_network_metrics,
-            )))
+            .layer(CallbackLayer::new(
+                MetricsMakeCallbackHandler::with_route_templates(
+                    inbound_network_metrics,
+                    parameters.network_route_templates.clone(),
+                ),
+            ))
             .service(routes);
 
         let outbound_layer = ServiceBuilder::new()
             .layer(TraceLayer::new())
-            .layer(CallbackLayer::new(MetricsMakeCallbackHandler::new(
-                outbound_network_metrics,
-            )))
+            .layer(CallbackLayer::new(
+                MetricsMakeCallbackHandler::with_route_templates(
+                    outbound_network_metrics,
+                    parameters.network_route_templates.clone(),
+                ),
+            ))
             .into_inner();
 
         let network = anemo::Network::bind(addr.clone())
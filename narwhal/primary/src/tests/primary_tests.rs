@@ -110,6 +110,8 @@ async fn get_network_peers_from_admin_server() {
         worker_1_parameters.clone(),
         store.batch_store,
         metrics_1,
+        /* validity */ None,
+        /* rx_flush */ None,
     );
 
     // Test getting all known peers for primary 1
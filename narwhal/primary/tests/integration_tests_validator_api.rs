@@ -144,6 +144,8 @@ async fn test_get_collections() {
         parameters.clone(),
         store.batch_store.clone(),
         metrics,
+        /* validity */ None,
+        /* rx_flush */ None,
     );
 
     // Wait for tasks to start
@@ -361,6 +363,8 @@ async fn test_remove_collections() {
         parameters.clone(),
         store.batch_store.clone(),
         metrics,
+        /* validity */ None,
+        /* rx_flush */ None,
     );
 
     // Test remove no collections
@@ -966,6 +970,8 @@ async fn test_get_collections_with_missing_certificates() {
         parameters.clone(),
         store_primary_1.batch_store,
         metrics_1,
+        /* validity */ None,
+        /* rx_flush */ None,
     );
 
     // Spawn the primary 2 - a peer to fetch missing certificates from
@@ -1015,6 +1021,8 @@ async fn test_get_collections_with_missing_certificates() {
         parameters.clone(),
         store_primary_2.batch_store,
         metrics_2,
+        /* validity */ None,
+        /* rx_flush */ None,
     );
 
     // Wait for tasks to start
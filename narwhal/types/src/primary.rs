@@ -26,6 +26,10 @@ use std::{
     fmt,
 };
 
+#[cfg(test)]
+#[path = "tests/batch_digest_tests.rs"]
+mod batch_digest_tests;
+
 /// The round number.
 pub type Round = u64;
 
@@ -60,6 +64,21 @@ impl BatchDigest {
     pub fn new(val: [u8; DIGEST_LEN]) -> BatchDigest {
         BatchDigest(val)
     }
+
+    /// Builds a digest from raw bytes, e.g. to construct known digests in tests.
+    pub fn from_bytes(bytes: [u8; DIGEST_LEN]) -> BatchDigest {
+        BatchDigest(bytes)
+    }
+
+    /// The all-zero digest, which sorts before any other digest.
+    pub fn zero() -> BatchDigest {
+        BatchDigest([0; DIGEST_LEN])
+    }
+
+    /// The full digest, hex encoded.
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
 }
 
 impl Hash for Batch {
@@ -0,0 +1,22 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::BatchDigest;
+use fastcrypto::DIGEST_LEN;
+
+#[test]
+fn known_batch_digests() {
+    let zero = BatchDigest::zero();
+    let mut bytes = [0u8; DIGEST_LEN];
+    bytes[DIGEST_LEN - 1] = 1;
+    let one = BatchDigest::from_bytes(bytes);
+
+    // Digests built from the same bytes are equal, and order follows the bytes.
+    assert_eq!(zero, BatchDigest::from_bytes([0; DIGEST_LEN]));
+    assert_eq!(one, BatchDigest::new(bytes));
+    assert_ne!(zero, one);
+    assert!(zero < one);
+
+    assert_eq!(zero.to_hex(), "00".repeat(DIGEST_LEN));
+    assert_eq!(one.to_hex(), format!("{}01", "00".repeat(DIGEST_LEN - 1)));
+}
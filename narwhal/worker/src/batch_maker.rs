@@ -5,24 +5,28 @@ use crate::metrics::WorkerMetrics;
 #[cfg(feature = "trace_transaction")]
 use byteorder::{BigEndian, ReadBytesExt};
 use config::Committee;
+use fastcrypto::Hash;
 #[cfg(feature = "benchmark")]
 use std::convert::TryInto;
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 use tokio::{
-    sync::watch,
+    sync::{mpsc, watch},
     task::JoinHandle,
     time::{sleep, Duration, Instant},
 };
 use types::{
     error::DagError,
     metered_channel::{Receiver, Sender},
-    Batch, ReconfigureNotification, Transaction,
+    Batch, BatchDigest, ReconfigureNotification, Transaction,
 };
 
 #[cfg(test)]
 #[path = "tests/batch_maker_tests.rs"]
 pub mod batch_maker_tests;
 
+/// Decides whether a transaction is allowed into a batch.
+pub type TransactionValidity = Arc<dyn Fn(&Transaction) -> bool + Send + Sync>;
+
 /// Assemble clients transactions into batches.
 pub struct BatchMaker {
     /// The committee information.
@@ -31,16 +35,26 @@ pub struct BatchMaker {
     batch_size: usize,
     /// The maximum delay after which to seal the batch.
     max_batch_delay: Duration,
+    /// Whether to drop transactions already included in the current batch.
+    dedup_transactions: bool,
+    /// The maximum size of a single transaction (in bytes). Larger transactions are dropped.
+    max_transaction_bytes: usize,
+    /// Transactions rejected by this predicate are dropped. Accepts everything by default.
+    validity: TransactionValidity,
     /// Receive reconfiguration updates.
     rx_reconfigure: watch::Receiver<ReconfigureNotification>,
     /// Channel to receive transactions from the network.
     rx_transaction: Receiver<Transaction>,
-    /// Output channel to deliver sealed batches to the `QuorumWaiter`.
-    tx_message: Sender<Batch>,
+    /// Receive requests to seal the current batch right away, whatever its size.
+    rx_flush: mpsc::Receiver<()>,
+    /// Output channel to deliver sealed batches, along with their digest, to the `QuorumWaiter`.
+    tx_message: Sender<(Batch, BatchDigest)>,
     /// Holds the current batch.
     current_batch: Batch,
     /// Holds the size of the current batch (in bytes).
     current_batch_size: usize,
+    /// Holds the digests of the transactions in the current batch, when deduplicating.
+    current_batch_digests: HashSet<[u8; 32]>,
     /// Metrics handler
     node_metrics: Arc<WorkerMetrics>,
 }
@@ -51,9 +65,13 @@ impl BatchMaker {
         committee: Committee,
         batch_size: usize,
         max_batch_delay: Duration,
+        dedup_transactions: bool,
+        max_transaction_bytes: usize,
+        validity: Option<TransactionValidity>,
         rx_reconfigure: watch::Receiver<ReconfigureNotification>,
         rx_transaction: Receiver<Transaction>,
-        tx_message: Sender<Batch>,
+        rx_flush: mpsc::Receiver<()>,
+        tx_message: Sender<(Batch, BatchDigest)>,
         node_metrics: Arc<WorkerMetrics>,
     ) -> JoinHandle<()> {
         tokio::spawn(async move {
@@ -61,11 +79,16 @@ impl BatchMaker {
                 committee,
                 batch_size,
                 max_batch_delay,
+                dedup_transactions,
+                max_transaction_bytes,
+                validity: validity.unwrap_or_else(|| Arc::new(|_| true)),
                 rx_reconfigure,
                 rx_transaction,
+                rx_flush,
                 tx_message,
                 current_batch: Batch(Vec::with_capacity(batch_size * 2)),
                 current_batch_size: 0,
+                current_batch_digests: HashSet::new(),
                 node_metrics,
             }
             .run()
@@ -82,18 +105,27 @@ impl BatchMaker {
             tokio::select! {
                 // Assemble client transactions into batches of preset size.
                 Some(transaction) = self.rx_transaction.recv() => {
-                    self.current_batch_size += transaction.len();
-                    self.current_batch.0.push(transaction);
-                    if self.current_batch_size >= self.batch_size {
-                        self.seal(false).await;
+                    if self.add_transaction(transaction).await {
                         timer.as_mut().reset(Instant::now() + self.max_batch_delay);
                     }
                 },
 
+                // Seal the batch on demand, without waiting for the size or the timer.
+                Some(()) = self.rx_flush.recv() => {
+                    // Transactions already queued were sent before the flush was requested.
+                    while let Ok(transaction) = self.rx_transaction.try_recv() {
+                        self.add_transaction(transaction).await;
+                    }
+                    if !self.current_batch.0.is_empty() {
+                        self.seal("flush").await;
+                    }
+                    timer.as_mut().reset(Instant::now() + self.max_batch_delay);
+                }
+
                 // If the timer triggers, seal the batch even if it contains few transactions.
                 () = &mut timer => {
                     if !self.current_batch.0.is_empty() {
-                        self.seal(true).await;
+                        self.seal("timeout").await;
                     }
                     timer.as_mut().reset(Instant::now() + self.max_batch_delay);
                 }
@@ -104,6 +136,13 @@ impl BatchMaker {
                     let message = self.rx_reconfigure.borrow().clone();
                     match message {
                         ReconfigureNotification::NewEpoch(new_committee) => {
+                            // Seal the transactions buffered during the old epoch rather than
+                            // carrying them over or losing them.
+                            if !self.current_batch.0.is_empty() {
+                                self.seal("reconfig").await;
+                            }
+                            timer.as_mut().reset(Instant::now() + self.max_batch_delay);
+                            self.node_metrics.batch_maker_reconfigurations.inc();
                             self.committee = new_committee;
                         },
                         ReconfigureNotification::UpdateCommittee(new_committee) => {
@@ -121,19 +160,61 @@ impl BatchMaker {
         }
     }
 
-    /// Seal and broadcast the current batch.
-    async fn seal(&mut self, timeout: bool) {
+    /// Add a client transaction to the current batch, unless it is dropped, and seal the batch
+    /// once it is large enough. Returns whether a batch was sealed.
+    async fn add_transaction(&mut self, transaction: Transaction) -> bool {
+        if transaction.len() > self.max_transaction_bytes {
+            // Never let an oversized transaction into the batch buffer.
+            self.node_metrics
+                .dropped_oversized_transactions
+                .with_label_values(&[self.committee.epoch.to_string().as_str()])
+                .inc();
+            tracing::debug!(
+                "Dropping transaction of {} B, above the limit of {} B",
+                transaction.len(),
+                self.max_transaction_bytes
+            );
+        } else if !(self.validity)(&transaction) {
+            self.node_metrics
+                .dropped_invalid_transactions
+                .with_label_values(&[self.committee.epoch.to_string().as_str()])
+                .inc();
+            tracing::debug!("Dropping transaction rejected by the validity predicate");
+        } else if self.is_duplicate(&transaction) {
+            // Drop exact duplicates of a transaction already in this batch.
+            tracing::debug!("Dropping duplicate transaction from the current batch");
+        } else {
+            self.current_batch_size += transaction.len();
+            self.current_batch.0.push(transaction);
+            if self.current_batch_size >= self.batch_size {
+                self.seal("size_reached").await;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Whether the transaction is already part of the current batch. Always false when
+    /// deduplication is disabled. Otherwise the transaction is remembered for this batch.
+    fn is_duplicate(&mut self, transaction: &Transaction) -> bool {
+        self.dedup_transactions
+            && !self
+                .current_batch_digests
+                .insert(fastcrypto::blake2b_256(|hasher| hasher.update(transaction)))
+    }
+
+    /// Seal and broadcast the current batch. The `reason` labels the batch size metric.
+    async fn seal(&mut self, reason: &str) {
         let size = self.current_batch_size;
 
         // Serialize the batch.
         self.current_batch_size = 0;
+        self.current_batch_digests.clear();
         let batch: Batch = Batch(self.current_batch.0.drain(..).collect());
+        let digest = batch.digest();
 
         #[cfg(feature = "benchmark")]
         {
-            use fastcrypto::Hash;
-            let digest = batch.digest();
-
             // Look for sample txs (they all start with 0) and gather their txs id (the next 8 bytes).
             let tx_ids: Vec<_> = batch
                 .0
@@ -179,15 +260,13 @@ impl BatchMaker {
             tracing::info!("Batch {:?} contains {} B", digest, size);
         }
 
-        let reason = if timeout { "timeout" } else { "size_reached" };
-
         self.node_metrics
             .created_batch_size
             .with_label_values(&[self.committee.epoch.to_string().as_str(), reason])
             .observe(size as f64);
 
         // Send the batch through the deliver channel for further processing.
-        if self.tx_message.send(batch).await.is_err() {
+        if self.tx_message.send((batch, digest)).await.is_err() {
             tracing::debug!("{}", DagError::ShuttingDown);
         }
     }
@@ -17,4 +17,5 @@ mod quorum_waiter;
 mod synchronizer;
 mod worker;
 
+pub use crate::batch_maker::TransactionValidity;
 pub use crate::worker::{Worker, WorkerMessage};
@@ -3,10 +3,10 @@
 use mysten_network::metrics::MetricsCallbackProvider;
 use network::metrics::{NetworkConnectionMetrics, NetworkMetrics};
 use prometheus::{
-    default_registry, register_histogram_vec_with_registry, register_int_counter_vec_with_registry,
-    register_int_counter_with_registry, register_int_gauge_vec_with_registry,
-    register_int_gauge_with_registry, HistogramVec, IntCounter, IntCounterVec, IntGauge,
-    IntGaugeVec, Registry,
+    default_registry, register_histogram_vec_with_registry, register_histogram_with_registry,
+    register_int_counter_vec_with_registry, register_int_counter_with_registry,
+    register_int_gauge_vec_with_registry, register_int_gauge_with_registry, Histogram,
+    HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Registry,
 };
 use std::time::Duration;
 use tonic::Code;
@@ -55,6 +55,14 @@ pub struct WorkerMetrics {
     pub pending_elements_worker_synchronizer: IntGaugeVec,
     /// Number of created batches from the batch_maker
     pub created_batch_size: HistogramVec,
+    /// Time it takes the processor to write a batch to the store
+    pub batch_store_write_latency: Histogram,
+    /// Number of transactions dropped by the batch_maker for exceeding the size limit
+    pub dropped_oversized_transactions: IntCounterVec,
+    /// Number of transactions dropped by the batch_maker for failing the validity predicate
+    pub dropped_invalid_transactions: IntCounterVec,
+    /// Number of epoch changes handled by the batch_maker
+    pub batch_maker_reconfigurations: IntCounter,
 }
 
 impl WorkerMetrics {
@@ -74,6 +82,34 @@ impl WorkerMetrics {
                 registry
             )
             .unwrap(),
+            batch_store_write_latency: register_histogram_with_registry!(
+                "batch_store_write_latency",
+                "Time it takes the processor to write a batch to the store",
+                // the buckets defined in seconds
+                vec![0.0005, 0.001, 0.005, 0.01, 0.02, 0.05, 0.1, 0.25, 0.5, 1.0, 2.0, 5.0, 10.0],
+                registry
+            )
+            .unwrap(),
+            dropped_oversized_transactions: register_int_counter_vec_with_registry!(
+                "dropped_oversized_transactions",
+                "Number of transactions dropped by the batch maker for exceeding the size limit",
+                &["epoch"],
+                registry
+            )
+            .unwrap(),
+            dropped_invalid_transactions: register_int_counter_vec_with_registry!(
+                "dropped_invalid_transactions",
+                "Number of transactions dropped by the batch maker for failing the validity predicate",
+                &["epoch"],
+                registry
+            )
+            .unwrap(),
+            batch_maker_reconfigurations: register_int_counter_with_registry!(
+                "batch_maker_reconfigurations",
+                "Number of epoch changes handled by the batch maker",
+                registry
+            )
+            .unwrap(),
         }
     }
 }
@@ -2,8 +2,10 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::metrics::WorkerMetrics;
 use config::WorkerId;
 use fastcrypto::Hash;
+use std::sync::Arc;
 use store::Store;
 use tokio::{sync::watch, task::JoinHandle};
 use types::{
@@ -34,6 +36,8 @@ impl Processor {
         tx_digest: Sender<WorkerPrimaryMessage>,
         // Whether we are processing our own batches or the batches of other nodes.
         own_digest: bool,
+        // Metrics handler
+        node_metrics: Arc<WorkerMetrics>,
     ) -> JoinHandle<()> {
         tokio::spawn(async move {
             loop {
@@ -43,7 +47,9 @@ impl Processor {
                         let digest = batch.digest();
 
                         // Store the batch.
+                        let timer = node_metrics.batch_store_write_latency.start_timer();
                         store.write(digest, batch).await;
+                        timer.observe_duration();
 
                         // Deliver the batch's digest.
                         let message = match own_digest {
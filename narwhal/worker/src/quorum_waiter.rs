@@ -9,7 +9,7 @@ use tokio::{sync::watch, task::JoinHandle};
 use types::{
     error::DagError,
     metered_channel::{Receiver, Sender},
-    Batch, ReconfigureNotification, WorkerMessage,
+    Batch, BatchDigest, ReconfigureNotification, WorkerMessage,
 };
 
 #[cfg(test)]
@@ -28,8 +28,8 @@ pub struct QuorumWaiter {
     worker_cache: SharedWorkerCache,
     /// Receive reconfiguration updates.
     rx_reconfigure: watch::Receiver<ReconfigureNotification>,
-    /// Input Channel to receive commands.
-    rx_message: Receiver<Batch>,
+    /// Input Channel to receive sealed batches and their digest.
+    rx_message: Receiver<(Batch, BatchDigest)>,
     /// Channel to deliver batches for which we have enough acknowledgments.
     tx_batch: Sender<Batch>,
     /// A network sender to broadcast the batches to the other workers.
@@ -45,7 +45,7 @@ impl QuorumWaiter {
         committee: Committee,
         worker_cache: SharedWorkerCache,
         rx_reconfigure: watch::Receiver<ReconfigureNotification>,
-        rx_message: Receiver<Batch>,
+        rx_message: Receiver<(Batch, BatchDigest)>,
         tx_batch: Sender<Batch>,
         network: P2pNetwork,
     ) -> JoinHandle<()> {
@@ -78,7 +78,7 @@ impl QuorumWaiter {
     async fn run(&mut self) {
         loop {
             tokio::select! {
-                Some(batch) = self.rx_message.recv() => {
+                Some((batch, digest)) = self.rx_message.recv() => {
                     // Broadcast the batch to the other workers.
                     let workers: Vec<_> = self
                         .worker_cache
@@ -126,7 +126,7 @@ impl QuorumWaiter {
                                         | ReconfigureNotification::UpdateCommittee(new_committee) => {
                                             self.network.cleanup(self.committee.network_diff(&new_committee));
                                             self.committee = new_committee;
-                                            tracing::debug!("Dropping batch: committee updated to {}", self.committee);
+                                            tracing::debug!("Dropping batch {}: committee updated to {}", digest, self.committee);
                                             break; // Don't wait for acknowledgements.
                                     },
                                     ReconfigureNotification::Shutdown => return
@@ -12,6 +12,7 @@ async fn make_batch() {
     let (_tx_reconfiguration, rx_reconfiguration) =
         watch::channel(ReconfigureNotification::NewEpoch(committee.clone()));
     let (tx_transaction, rx_transaction) = test_utils::test_channel!(1);
+    let (_tx_flush, rx_flush) = tokio::sync::mpsc::channel(1);
     let (tx_message, mut rx_message) = test_utils::test_channel!(1);
     let node_metrics = WorkerMetrics::new(&Registry::new());
 
@@ -21,8 +22,12 @@ async fn make_batch() {
         /* max_batch_size */ 200,
         /* max_batch_delay */
         Duration::from_millis(1_000_000), // Ensure the timer is not triggered.
+        /* dedup_transactions */ false,
+        /* max_transaction_bytes */ 1_000,
+        /* validity */ None,
         rx_reconfiguration,
         rx_transaction,
+        rx_flush,
         tx_message,
         Arc::new(node_metrics),
     );
@@ -34,8 +39,9 @@ async fn make_batch() {
 
     // Ensure the batch is as expected.
     let expected_batch = Batch(vec![tx.clone(), tx.clone()]);
-    let batch = rx_message.recv().await.unwrap();
+    let (batch, digest) = rx_message.recv().await.unwrap();
     assert_eq!(batch, expected_batch);
+    assert_eq!(digest, expected_batch.digest());
 }
 
 #[tokio::test]
@@ -45,6 +51,7 @@ async fn batch_timeout() {
     let (_tx_reconfiguration, rx_reconfiguration) =
         watch::channel(ReconfigureNotification::NewEpoch(committee.clone()));
     let (tx_transaction, rx_transaction) = test_utils::test_channel!(1);
+    let (_tx_flush, rx_flush) = tokio::sync::mpsc::channel(1);
     let (tx_message, mut rx_message) = test_utils::test_channel!(1);
     let node_metrics = WorkerMetrics::new(&Registry::new());
 
@@ -54,8 +61,12 @@ async fn batch_timeout() {
         /* max_batch_size */ 200,
         /* max_batch_delay */
         Duration::from_millis(50), // Ensure the timer is triggered.
+        /* dedup_transactions */ false,
+        /* max_transaction_bytes */ 1_000,
+        /* validity */ None,
         rx_reconfiguration,
         rx_transaction,
+        rx_flush,
         tx_message,
         Arc::new(node_metrics),
     );
@@ -66,6 +77,259 @@ async fn batch_timeout() {
 
     // Ensure the batch is as expected.
     let expected_batch = Batch(vec![tx]);
-    let batch = rx_message.recv().await.unwrap();
+    let (batch, digest) = rx_message.recv().await.unwrap();
     assert_eq!(batch, expected_batch);
+    assert_eq!(digest, expected_batch.digest());
+}
+
+#[test]
+fn batch_digest_is_stable() {
+    let tx: Transaction = vec![1u8; 100];
+    let batch = Batch(vec![tx.clone(), tx]);
+
+    // The digest only depends on the transactions, so it must never change for a known batch.
+    let expected = BatchDigest::new([
+        24, 87, 42, 211, 254, 186, 11, 136, 48, 124, 27, 45, 68, 222, 34, 203, 161, 69, 101, 4,
+        173, 144, 183, 28, 38, 155, 202, 183, 144, 134, 169, 109,
+    ]);
+    assert_eq!(batch.digest(), expected);
+}
+
+#[tokio::test]
+async fn dedup_transactions_within_batch() {
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let (_tx_reconfiguration, rx_reconfiguration) =
+        watch::channel(ReconfigureNotification::NewEpoch(committee.clone()));
+    let (tx_transaction, rx_transaction) = test_utils::test_channel!(1);
+    let (_tx_flush, rx_flush) = tokio::sync::mpsc::channel(1);
+    let (tx_message, mut rx_message) = test_utils::test_channel!(1);
+    let node_metrics = WorkerMetrics::new(&Registry::new());
+
+    // Spawn a `BatchMaker` instance.
+    let _batch_maker_handle = BatchMaker::spawn(
+        committee,
+        /* max_batch_size */ 200,
+        /* max_batch_delay */
+        Duration::from_millis(50), // Ensure the timer is triggered.
+        /* dedup_transactions */ true,
+        /* max_transaction_bytes */ 1_000,
+        /* validity */ None,
+        rx_reconfiguration,
+        rx_transaction,
+        rx_flush,
+        tx_message,
+        Arc::new(node_metrics),
+    );
+
+    // Send the same transaction twice within the same batch window.
+    let tx = transaction();
+    tx_transaction.send(tx.clone()).await.unwrap();
+    tx_transaction.send(tx.clone()).await.unwrap();
+
+    // Ensure the duplicate was dropped.
+    let (batch, _) = rx_message.recv().await.unwrap();
+    assert_eq!(batch, Batch(vec![tx.clone()]));
+
+    // Duplicates are only detected within a batch, so the next batch may contain it again.
+    tx_transaction.send(tx.clone()).await.unwrap();
+    let (batch, _) = rx_message.recv().await.unwrap();
+    assert_eq!(batch, Batch(vec![tx]));
+}
+
+#[tokio::test]
+async fn drop_oversized_transaction() {
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let (_tx_reconfiguration, rx_reconfiguration) =
+        watch::channel(ReconfigureNotification::NewEpoch(committee.clone()));
+    let (tx_transaction, rx_transaction) = test_utils::test_channel!(1);
+    let (_tx_flush, rx_flush) = tokio::sync::mpsc::channel(1);
+    let (tx_message, mut rx_message) = test_utils::test_channel!(1);
+    let node_metrics = Arc::new(WorkerMetrics::new(&Registry::new()));
+
+    // Spawn a `BatchMaker` instance.
+    let _batch_maker_handle = BatchMaker::spawn(
+        committee.clone(),
+        /* max_batch_size */ 200,
+        /* max_batch_delay */
+        Duration::from_millis(50), // Ensure the timer is triggered.
+        /* dedup_transactions */ false,
+        /* max_transaction_bytes */ 150,
+        /* validity */ None,
+        rx_reconfiguration,
+        rx_transaction,
+        rx_flush,
+        tx_message,
+        node_metrics.clone(),
+    );
+
+    // Send a transaction above the limit, followed by a regular one.
+    let oversized: Transaction = vec![0u8; 151];
+    tx_transaction.send(oversized).await.unwrap();
+    let tx = transaction();
+    tx_transaction.send(tx.clone()).await.unwrap();
+
+    // Ensure only the regular transaction made it into the batch.
+    let (batch, _) = rx_message.recv().await.unwrap();
+    assert_eq!(batch, Batch(vec![tx]));
+    assert_eq!(
+        node_metrics
+            .dropped_oversized_transactions
+            .with_label_values(&[committee.epoch.to_string().as_str()])
+            .get(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn flush_partial_batch() {
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let (_tx_reconfiguration, rx_reconfiguration) =
+        watch::channel(ReconfigureNotification::NewEpoch(committee.clone()));
+    let (tx_transaction, rx_transaction) = test_utils::test_channel!(1);
+    let (tx_flush, rx_flush) = tokio::sync::mpsc::channel(1);
+    let (tx_message, mut rx_message) = test_utils::test_channel!(1);
+    let node_metrics = WorkerMetrics::new(&Registry::new());
+
+    // Spawn a `BatchMaker` instance.
+    let _batch_maker_handle = BatchMaker::spawn(
+        committee,
+        /* max_batch_size */ 200,
+        /* max_batch_delay */
+        Duration::from_millis(1_000_000), // Ensure the timer is not triggered.
+        /* dedup_transactions */ false,
+        /* max_transaction_bytes */ 1_000,
+        /* validity */ None,
+        rx_reconfiguration,
+        rx_transaction,
+        rx_flush,
+        tx_message,
+        Arc::new(node_metrics),
+    );
+
+    // Buffer a single transaction, not enough to seal a batch.
+    let tx = transaction();
+    tx_transaction.send(tx.clone()).await.unwrap();
+    // Give the `BatchMaker` a chance to buffer it, the two channels are not ordered.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // Ask for the partial batch to be sealed.
+    tx_flush.send(()).await.unwrap();
+
+    // Ensure the batch is delivered well before the timer would have fired.
+    let (batch, _) = tokio::time::timeout(Duration::from_secs(10), rx_message.recv())
+        .await
+        .expect("The batch should have been flushed")
+        .unwrap();
+    assert_eq!(batch, Batch(vec![tx]));
+}
+
+#[tokio::test]
+async fn seal_buffered_transactions_on_new_epoch() {
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let (tx_reconfiguration, rx_reconfiguration) =
+        watch::channel(ReconfigureNotification::NewEpoch(committee.clone()));
+    let (tx_transaction, rx_transaction) = test_utils::test_channel!(1);
+    let (tx_flush, rx_flush) = tokio::sync::mpsc::channel(1);
+    let (tx_message, mut rx_message) = test_utils::test_channel!(1);
+    let node_metrics = Arc::new(WorkerMetrics::new(&Registry::new()));
+
+    // Spawn a `BatchMaker` instance.
+    let _batch_maker_handle = BatchMaker::spawn(
+        committee.clone(),
+        /* max_batch_size */ 1_000,
+        /* max_batch_delay */
+        Duration::from_millis(1_000_000), // Ensure the timer is not triggered.
+        /* dedup_transactions */ false,
+        /* max_transaction_bytes */ 1_000,
+        /* validity */ None,
+        rx_reconfiguration,
+        rx_transaction,
+        rx_flush,
+        tx_message,
+        node_metrics.clone(),
+    );
+
+    // Buffer a few transactions, not enough to seal a batch.
+    let tx = transaction();
+    for _ in 0..3 {
+        tx_transaction.send(tx.clone()).await.unwrap();
+    }
+    // Give the `BatchMaker` a chance to buffer them, the two channels are not ordered.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // Move to the next epoch.
+    let mut new_committee = committee.clone();
+    new_committee.epoch = committee.epoch + 1;
+    tx_reconfiguration
+        .send(ReconfigureNotification::NewEpoch(new_committee))
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // Ensure the buffered transactions were sealed in a batch of the old epoch.
+    let (batch, _) = rx_message.recv().await.unwrap();
+    assert_eq!(batch, Batch(vec![tx.clone(), tx.clone(), tx.clone()]));
+    assert_eq!(
+        node_metrics
+            .created_batch_size
+            .with_label_values(&[committee.epoch.to_string().as_str(), "reconfig"])
+            .get_sample_count(),
+        1
+    );
+    assert_eq!(node_metrics.batch_maker_reconfigurations.get(), 1);
+
+    // Transactions of the new epoch go in a fresh batch.
+    tx_transaction.send(tx.clone()).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    tx_flush.send(()).await.unwrap();
+    let (batch, _) = rx_message.recv().await.unwrap();
+    assert_eq!(batch, Batch(vec![tx]));
+}
+
+#[tokio::test]
+async fn drop_invalid_transaction() {
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let (_tx_reconfiguration, rx_reconfiguration) =
+        watch::channel(ReconfigureNotification::NewEpoch(committee.clone()));
+    let (tx_transaction, rx_transaction) = test_utils::test_channel!(1);
+    let (_tx_flush, rx_flush) = tokio::sync::mpsc::channel(1);
+    let (tx_message, mut rx_message) = test_utils::test_channel!(1);
+    let node_metrics = Arc::new(WorkerMetrics::new(&Registry::new()));
+    let reject_empty: TransactionValidity = Arc::new(|tx: &Transaction| !tx.is_empty());
+
+    // Spawn a `BatchMaker` instance.
+    let _batch_maker_handle = BatchMaker::spawn(
+        committee.clone(),
+        /* max_batch_size */ 200,
+        /* max_batch_delay */
+        Duration::from_millis(50), // Ensure the timer is triggered.
+        /* dedup_transactions */ false,
+        /* max_transaction_bytes */ 1_000,
+        /* validity */ Some(reject_empty),
+        rx_reconfiguration,
+        rx_transaction,
+        rx_flush,
+        tx_message,
+        node_metrics.clone(),
+    );
+
+    // Send an empty transaction, followed by a regular one.
+    tx_transaction.send(Vec::new()).await.unwrap();
+    let tx = transaction();
+    tx_transaction.send(tx.clone()).await.unwrap();
+
+    // Ensure only the regular transaction made it into the batch.
+    let (batch, _) = rx_message.recv().await.unwrap();
+    assert_eq!(batch, Batch(vec![tx]));
+    assert_eq!(
+        node_metrics
+            .dropped_invalid_transactions
+            .with_label_values(&[committee.epoch.to_string().as_str()])
+            .get(),
+        1
+    );
 }
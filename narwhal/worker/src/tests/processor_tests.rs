@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 use super::*;
 use fastcrypto::Hash;
+use prometheus::Registry;
 use store::rocks;
 use test_utils::{batch, temp_dir, CommitteeFixture};
 
@@ -29,6 +30,7 @@ async fn hash_and_store_our_batch() {
         rx_batch,
         tx_digest,
         /* own_batch */ true,
+        Arc::new(WorkerMetrics::new(&Registry::new())),
     );
 
     // Send a batch to the `Processor`.
@@ -81,6 +83,7 @@ async fn hash_and_store_others_batch() {
         rx_batch,
         tx_digest,
         /* own_batch */ false,
+        Arc::new(WorkerMetrics::new(&Registry::new())),
     );
 
     // Send a batch to the `Processor`.
@@ -108,6 +111,35 @@ async fn hash_and_store_others_batch() {
     }
 }
 
+#[tokio::test]
+async fn store_write_latency_is_recorded() {
+    let (tx_batch, rx_batch) = test_utils::test_channel!(1);
+    let (tx_digest, mut rx_digest) = test_utils::test_channel!(1);
+
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let (_tx_reconfiguration, rx_reconfiguration) =
+        watch::channel(ReconfigureNotification::NewEpoch(committee.clone()));
+
+    let store = create_batches_store();
+    let node_metrics = Arc::new(WorkerMetrics::new(&Registry::new()));
+    let _processor_handler = Processor::spawn(
+        0,
+        store,
+        rx_reconfiguration,
+        rx_batch,
+        tx_digest,
+        /* own_batch */ true,
+        node_metrics.clone(),
+    );
+
+    // Write a batch, and wait for its digest to be sure the write completed.
+    tx_batch.send(batch()).await.unwrap();
+    rx_digest.recv().await.unwrap();
+
+    assert_eq!(node_metrics.batch_store_write_latency.get_sample_count(), 1);
+}
+
 fn create_batches_store() -> Store<BatchDigest, Batch> {
     let db = rocks::DBMap::<BatchDigest, Batch>::open(temp_dir(), None, Some("batches")).unwrap();
     Store::new(db)
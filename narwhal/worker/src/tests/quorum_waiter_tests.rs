@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 use super::*;
 use crate::worker::WorkerMessage;
+use fastcrypto::Hash;
 use test_utils::{batch, test_network, CommitteeFixture, WorkerToWorkerMockServer};
 
 #[tokio::test]
@@ -51,7 +52,10 @@ async fn wait_for_quorum() {
     }
 
     // Forward the batch along with the handlers to the `QuorumWaiter`.
-    tx_message.send(batch.clone()).await.unwrap();
+    tx_message
+        .send((batch.clone(), batch.digest()))
+        .await
+        .unwrap();
 
     // Wait for the `QuorumWaiter` to gather enough acknowledgements and output the batch.
     let output = rx_batch.recv().await.unwrap();
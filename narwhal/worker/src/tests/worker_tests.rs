@@ -41,6 +41,7 @@ async fn handle_clients_transactions() {
     let metrics = initialise_metrics(&registry);
 
     // Spawn a `Worker` instance.
+    let (tx_flush, rx_flush) = tokio::sync::mpsc::channel(1);
     Worker::spawn(
         name.clone(),
         myself.keypair(),
@@ -50,6 +51,8 @@ async fn handle_clients_transactions() {
         parameters,
         store,
         metrics,
+        /* validity */ None,
+        Some(rx_flush),
     );
 
     // Spawn a network listener to receive our batch's digest.
@@ -91,6 +94,240 @@ async fn handle_clients_transactions() {
     assert_eq!(handle.recv().await.unwrap(), expected);
 }
 
+#[tokio::test]
+async fn drop_transactions_rejected_by_validity() {
+    let fixture = CommitteeFixture::builder().randomize_ports(true).build();
+    let committee = fixture.committee();
+    let worker_cache = fixture.shared_worker_cache();
+
+    let worker_id = 0;
+    let my_primary = fixture.authorities().next().unwrap();
+    let myself = my_primary.worker(worker_id);
+    let name = my_primary.public_key();
+
+    let parameters = Parameters {
+        batch_size: 200, // Two transactions.
+        ..Parameters::default()
+    };
+
+    // Create a new test store.
+    let db = rocks::DBMap::<BatchDigest, Batch>::open(temp_dir(), None, Some("batches")).unwrap();
+    let store = Store::new(db);
+
+    let registry = Registry::new();
+    let metrics = initialise_metrics(&registry);
+
+    // Spawn a `Worker` instance that rejects transactions starting with 0xff.
+    let validity: TransactionValidity = Arc::new(|tx: &Transaction| tx.first() != Some(&0xff));
+    Worker::spawn(
+        name.clone(),
+        myself.keypair(),
+        worker_id,
+        Arc::new(ArcSwap::from_pointee(committee.clone())),
+        worker_cache.clone(),
+        parameters,
+        store,
+        metrics,
+        Some(validity),
+        /* rx_flush */ None,
+    );
+
+    // The rejected transaction is left out of the batch.
+    let invalid = vec![0xffu8; 100];
+    let batch = Batch(vec![vec![0u8; 100], vec![1u8; 100]]);
+    let batch_digest = batch.digest();
+
+    let primary_address = committee.primary(&name).unwrap();
+    let expected = WorkerPrimaryMessage::OurBatch(batch_digest, worker_id);
+    let (mut handle, _network) =
+        WorkerToPrimaryMockServer::spawn(my_primary.network_keypair().copy(), primary_address);
+
+    // Spawn enough workers' listeners to acknowledge our batches.
+    let mut other_workers = Vec::new();
+    for worker in fixture.authorities().skip(1).map(|a| a.worker(worker_id)) {
+        let handle =
+            WorkerToWorkerMockServer::spawn(worker.keypair(), worker.info().worker_address.clone());
+        other_workers.push(handle);
+    }
+
+    // Wait till other services have been able to start up
+    tokio::task::yield_now().await;
+    let address = worker_cache
+        .load()
+        .worker(&name, &worker_id)
+        .unwrap()
+        .transactions;
+    let config = mysten_network::config::Config::new();
+    let channel = config.connect_lazy(&address).unwrap();
+    let mut client = TransactionsClient::new(channel);
+    for tx in std::iter::once(invalid).chain(batch.0) {
+        let txn = TransactionProto {
+            transaction: Bytes::from(tx),
+        };
+        client.submit_transaction(txn).await.unwrap();
+    }
+
+    // Only the valid transactions made it into the batch.
+    assert_eq!(handle.recv().await.unwrap(), expected);
+}
+
+#[tokio::test]
+async fn flush_partial_batch_on_demand() {
+    let fixture = CommitteeFixture::builder().randomize_ports(true).build();
+    let committee = fixture.committee();
+    let worker_cache = fixture.shared_worker_cache();
+
+    let worker_id = 0;
+    let my_primary = fixture.authorities().next().unwrap();
+    let myself = my_primary.worker(worker_id);
+    let name = my_primary.public_key();
+
+    let parameters = Parameters {
+        batch_size: 1_000_000,                           // Never reached.
+        max_batch_delay: Duration::from_secs(1_000_000), // Never triggered.
+        ..Parameters::default()
+    };
+
+    // Create a new test store.
+    let db = rocks::DBMap::<BatchDigest, Batch>::open(temp_dir(), None, Some("batches")).unwrap();
+    let store = Store::new(db);
+
+    let registry = Registry::new();
+    let metrics = initialise_metrics(&registry);
+
+    // Spawn a `Worker` instance.
+    let (tx_flush, rx_flush) = tokio::sync::mpsc::channel(1);
+    Worker::spawn(
+        name.clone(),
+        myself.keypair(),
+        worker_id,
+        Arc::new(ArcSwap::from_pointee(committee.clone())),
+        worker_cache.clone(),
+        parameters,
+        store,
+        metrics,
+        /* validity */ None,
+        Some(rx_flush),
+    );
+
+    // Spawn a network listener to receive our batch's digest.
+    let batch = batch();
+    let batch_digest = batch.digest();
+
+    let primary_address = committee.primary(&name).unwrap();
+    let expected = WorkerPrimaryMessage::OurBatch(batch_digest, worker_id);
+    let (mut handle, _network) =
+        WorkerToPrimaryMockServer::spawn(my_primary.network_keypair().copy(), primary_address);
+
+    // Spawn enough workers' listeners to acknowledge our batches.
+    let mut other_workers = Vec::new();
+    for worker in fixture.authorities().skip(1).map(|a| a.worker(worker_id)) {
+        let handle =
+            WorkerToWorkerMockServer::spawn(worker.keypair(), worker.info().worker_address.clone());
+        other_workers.push(handle);
+    }
+
+    // Wait till other services have been able to start up
+    tokio::task::yield_now().await;
+    // Stream fewer transactions than a batch holds.
+    let address = worker_cache
+        .load()
+        .worker(&name, &worker_id)
+        .unwrap()
+        .transactions;
+    let config = mysten_network::config::Config::new();
+    let channel = config.connect_lazy(&address).unwrap();
+    let mut client = TransactionsClient::new(channel);
+    let transactions: Vec<_> = batch
+        .0
+        .iter()
+        .map(|tx| TransactionProto {
+            transaction: Bytes::from(tx.clone()),
+        })
+        .collect();
+    client
+        .submit_transaction_stream(futures::stream::iter(transactions))
+        .await
+        .unwrap();
+
+    // Ending the stream does not seal the partial batch.
+    assert!(
+        tokio::time::timeout(Duration::from_millis(500), handle.recv())
+            .await
+            .is_err()
+    );
+
+    // Ensure the partial batch is sealed once a flush is requested.
+    tx_flush.send(()).await.unwrap();
+    let received = tokio::time::timeout(Duration::from_secs(10), handle.recv())
+        .await
+        .expect("The batch should have been flushed")
+        .unwrap();
+    assert_eq!(received, expected);
+}
+
+#[tokio::test]
+async fn reject_oversized_client_transaction() {
+    let fixture = CommitteeFixture::builder().randomize_ports(true).build();
+    let committee = fixture.committee();
+    let worker_cache = fixture.shared_worker_cache();
+
+    let worker_id = 0;
+    let my_primary = fixture.authorities().next().unwrap();
+    let myself = my_primary.worker(worker_id);
+    let name = my_primary.public_key();
+
+    let parameters = Parameters {
+        max_transaction_bytes: 10,
+        ..Parameters::default()
+    };
+
+    // Create a new test store.
+    let db = rocks::DBMap::<BatchDigest, Batch>::open(temp_dir(), None, Some("batches")).unwrap();
+    let store = Store::new(db);
+
+    let registry = Registry::new();
+    let metrics = initialise_metrics(&registry);
+
+    // Spawn a `Worker` instance.
+    Worker::spawn(
+        name.clone(),
+        myself.keypair(),
+        worker_id,
+        Arc::new(ArcSwap::from_pointee(committee.clone())),
+        worker_cache.clone(),
+        parameters,
+        store,
+        metrics,
+        /* validity */ None,
+        /* rx_flush */ None,
+    );
+
+    // Wait till other services have been able to start up
+    tokio::task::yield_now().await;
+    let address = worker_cache
+        .load()
+        .worker(&name, &worker_id)
+        .unwrap()
+        .transactions;
+    let config = mysten_network::config::Config::new();
+    let channel = config.connect_lazy(&address).unwrap();
+    let mut client = TransactionsClient::new(channel);
+
+    // The submitter is told the transaction is too large.
+    let txn = TransactionProto {
+        transaction: Bytes::from(vec![0u8; 11]),
+    };
+    let status = client.submit_transaction(txn).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+    // Transactions within the limit are accepted.
+    let txn = TransactionProto {
+        transaction: Bytes::from(vec![0u8; 10]),
+    };
+    client.submit_transaction(txn).await.unwrap();
+}
+
 #[tokio::test]
 async fn get_network_peers_from_admin_server() {
     // telemetry_subscribers::init_for_testing();
@@ -167,6 +404,8 @@ async fn get_network_peers_from_admin_server() {
         worker_1_parameters.clone(),
         store.batch_store.clone(),
         metrics_1.clone(),
+        /* validity */ None,
+        /* rx_flush */ None,
     );
 
     let primary_1_peer_id = hex::encode(authority_1.network_keypair().copy().public().0.as_bytes());
@@ -281,6 +520,8 @@ async fn get_network_peers_from_admin_server() {
         worker_2_parameters.clone(),
         store.batch_store,
         metrics_2.clone(),
+        /* validity */ None,
+        /* rx_flush */ None,
     );
 
     // Wait for tasks to start. Sleeping longer here to ensure all primaries and workers
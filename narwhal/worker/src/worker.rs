@@ -2,7 +2,7 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
-    batch_maker::BatchMaker,
+    batch_maker::{BatchMaker, TransactionValidity},
     handlers::{PrimaryReceiverHandler, WorkerReceiverHandler},
     metrics::WorkerChannelMetrics,
     primary_connector::PrimaryConnector,
@@ -58,6 +58,8 @@ pub struct Worker {
     parameters: Parameters,
     /// The persistent storage.
     store: Store<BatchDigest, Batch>,
+    /// Transactions rejected by this predicate are dropped before batching.
+    validity: Option<TransactionValidity>,
 }
 
 impl Worker {
@@ -70,6 +72,8 @@ impl Worker {
         parameters: Parameters,
         store: Store<BatchDigest, Batch>,
         metrics: Metrics,
+        validity: Option<TransactionValidity>,
+        rx_flush: Option<tokio::sync::mpsc::Receiver<()>>,
     ) -> Vec<JoinHandle<()>> {
         // Define a worker instance.
         let worker = Self {
@@ -80,6 +84,7 @@ impl Worker {
             worker_cache,
             parameters: parameters.clone(),
             store,
+            validity,
         };
 
         let node_metrics = Arc::new(metrics.worker_metrics.unwrap());
@@ -146,16 +151,22 @@ impl Worker {
 
         let service = ServiceBuilder::new()
             .layer(TraceLayer::new())
-            .layer(CallbackLayer::new(MetricsMakeCallbackHandler::new(
-                inbound_network_metrics,
-            )))
+            .layer(CallbackLayer::new(
+                MetricsMakeCallbackHandler::with_route_templates(
+                    inbound_network_metrics,
+                    worker.parameters.network_route_templates.clone(),
+                ),
+            ))
             .service(routes);
 
         let outbound_layer = ServiceBuilder::new()
             .layer(TraceLayer::new())
-            .layer(CallbackLayer::new(MetricsMakeCallbackHandler::new(
-                outbound_network_metrics,
-            )))
+            .layer(CallbackLayer::new(
+                MetricsMakeCallbackHandler::with_route_templates(
+                    outbound_network_metrics,
+                    worker.parameters.network_route_templates.clone(),
+                ),
+            ))
             .into_inner();
         let network = anemo::Network::bind(addr)
             .server_name("narwhal")
@@ -234,10 +245,13 @@ impl Worker {
             rx_primary,
             network::P2pNetwork::new(network.clone()),
         );
+        // Without a flush hook, the batch maker only seals on size, timeout and reconfiguration.
+        let rx_flush = rx_flush.unwrap_or_else(|| tokio::sync::mpsc::channel(1).1);
         let client_flow_handles = worker.handle_clients_transactions(
             &tx_reconfigure,
             tx_primary.clone(),
-            node_metrics,
+            rx_flush,
+            node_metrics.clone(),
             channel_metrics,
             endpoint_metrics,
             network.clone(),
@@ -246,6 +260,7 @@ impl Worker {
             &tx_reconfigure,
             tx_primary.clone(),
             rx_worker_processor,
+            node_metrics,
         );
         let primary_flow_handles =
             worker.handle_primary_messages(rx_synchronizer, tx_reconfigure, tx_primary, network);
@@ -297,6 +312,7 @@ impl Worker {
         &self,
         tx_reconfigure: &watch::Sender<ReconfigureNotification>,
         tx_primary: Sender<WorkerPrimaryMessage>,
+        rx_flush: tokio::sync::mpsc::Receiver<()>,
         node_metrics: Arc<WorkerMetrics>,
         channel_metrics: Arc<WorkerChannelMetrics>,
         endpoint_metrics: WorkerEndpointMetrics,
@@ -328,7 +344,11 @@ impl Worker {
         let address = address
             .replace(0, |_protocol| Some(Protocol::Ip4(Ipv4Addr::UNSPECIFIED)))
             .unwrap();
-        let tx_receiver_handle = TxReceiverHandler { tx_batch_maker }.spawn(
+        let tx_receiver_handle = TxReceiverHandler {
+            tx_batch_maker,
+            max_transaction_bytes: self.parameters.max_transaction_bytes,
+        }
+        .spawn(
             address.clone(),
             tx_reconfigure.subscribe(),
             endpoint_metrics,
@@ -341,10 +361,14 @@ impl Worker {
             (*(*(*self.committee).load()).clone()).clone(),
             self.parameters.batch_size,
             self.parameters.max_batch_delay,
+            self.parameters.dedup_batch_transactions,
+            self.parameters.max_transaction_bytes,
+            self.validity.clone(),
             tx_reconfigure.subscribe(),
             /* rx_transaction */ rx_batch_maker,
+            rx_flush,
             /* tx_message */ tx_quorum_waiter,
-            node_metrics,
+            node_metrics.clone(),
         );
 
         // The `QuorumWaiter` waits for 2f authorities to acknowledge reception of the batch. It then forwards
@@ -369,6 +393,7 @@ impl Worker {
             /* rx_batch */ rx_client_processor,
             /* tx_digest */ tx_primary,
             /* own_batch */ true,
+            node_metrics,
         );
 
         info!(
@@ -390,6 +415,7 @@ impl Worker {
         tx_reconfigure: &watch::Sender<ReconfigureNotification>,
         tx_primary: Sender<WorkerPrimaryMessage>,
         rx_worker_processor: types::metered_channel::Receiver<Batch>,
+        node_metrics: Arc<WorkerMetrics>,
     ) -> Vec<JoinHandle<()>> {
         // This `Processor` hashes and stores the batches we receive from the other workers. It then forwards the
         // batch's digest to the `PrimaryConnector` that will send it to our primary.
@@ -400,6 +426,7 @@ impl Worker {
             /* rx_batch */ rx_worker_processor,
             /* tx_digest */ tx_primary,
             /* own_batch */ false,
+            node_metrics,
         );
 
         vec![processor_handle]
@@ -410,9 +437,23 @@ impl Worker {
 #[derive(Clone)]
 struct TxReceiverHandler {
     tx_batch_maker: Sender<Transaction>,
+    max_transaction_bytes: usize,
 }
 
 impl TxReceiverHandler {
+    /// Reject transactions the `BatchMaker` would not accept, so that their submitter learns
+    /// about it.
+    fn check_size(&self, transaction: &[u8]) -> Result<(), Status> {
+        if transaction.len() > self.max_transaction_bytes {
+            return Err(Status::invalid_argument(format!(
+                "Transaction of {} B is above the limit of {} B",
+                transaction.len(),
+                self.max_transaction_bytes
+            )));
+        }
+        Ok(())
+    }
+
     async fn wait_for_shutdown(mut rx_reconfigure: watch::Receiver<ReconfigureNotification>) {
         loop {
             let result = rx_reconfigure.changed().await;
@@ -454,6 +495,7 @@ impl Transactions for TxReceiverHandler {
         request: Request<TransactionProto>,
     ) -> Result<Response<Empty>, Status> {
         let message = request.into_inner().transaction;
+        self.check_size(&message)?;
         // Send the transaction to the batch maker.
         self.tx_batch_maker
             .send(message.to_vec())
@@ -471,6 +513,7 @@ impl Transactions for TxReceiverHandler {
         let mut transactions = request.into_inner();
 
         while let Some(Ok(txn)) = transactions.next().await {
+            self.check_size(&txn.transaction)?;
             // Send the transaction to the batch maker.
             self.tx_batch_maker
                 .send(txn.transaction.to_vec())